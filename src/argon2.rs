@@ -0,0 +1,491 @@
+use Error;
+
+use crypto::blake2b::Blake2b;
+use crypto::digest::Digest;
+
+pub const VERSION_10: u32 = 0x10;
+pub const VERSION_13: u32 = 0x13;
+
+const BLOCK_SIZE: usize = 1024;
+const BLOCK_WORDS: usize = BLOCK_SIZE / 8;
+const SYNC_POINTS: u32 = 4;
+const ADDRESSES_IN_BLOCK: u32 = BLOCK_WORDS as u32;
+const MAX_PARALLELISM: u32 = 0x00FFFFFF;
+const MIN_SALT_LENGTH: usize = 8;
+
+pub const MAX_MEMORY: u32 = 1024 * 1024;
+const MAX_WORK: u64 = 64 * MAX_MEMORY as u64;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Variant {
+    Argon2d,
+    Argon2id,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Parameters {
+    pub variant: Variant,
+    pub version: u32,
+    pub memory: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+    pub salt: Vec<u8>,
+    pub secret: Vec<u8>,
+    pub associated_data: Vec<u8>,
+}
+
+#[derive(Clone, Copy)]
+struct Block([u64; BLOCK_WORDS]);
+
+struct Position {
+    pass: u32,
+    lane: u32,
+    slice: u32,
+}
+
+struct Instance {
+    variant: Variant,
+    version: u32,
+    passes: u32,
+    lanes: u32,
+    lane_length: u32,
+    segment_length: u32,
+    memory: Vec<Block>,
+}
+
+impl Variant {
+    fn type_id(&self) -> u32 {
+        match *self {
+            Variant::Argon2d => 0,
+            Variant::Argon2id => 2,
+        }
+    }
+}
+
+impl Block {
+    fn zero() -> Block {
+        Block([0; BLOCK_WORDS])
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Block {
+        let mut block = Block::zero();
+        for (word, chunk) in block.0.iter_mut().zip(bytes.chunks(8)) {
+            *word = chunk.iter().rev().fold(0, |value, &byte| (value << 8) | byte as u64);
+        }
+        block
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(BLOCK_SIZE);
+        for word in self.0.iter() {
+            for i in 0..8 {
+                bytes.push((word >> (8 * i)) as u8);
+            }
+        }
+        bytes
+    }
+
+    fn xor(&mut self, other: &Block) {
+        for (word, other) in self.0.iter_mut().zip(other.0.iter()) {
+            *word ^= *other;
+        }
+    }
+}
+
+impl Instance {
+    fn fill_memory(&mut self) {
+        for pass in 0..self.passes {
+            for slice in 0..SYNC_POINTS {
+                for lane in 0..self.lanes {
+                    self.fill_segment(&Position {
+                        pass: pass,
+                        lane: lane,
+                        slice: slice,
+                    });
+                }
+            }
+        }
+    }
+
+    fn fill_segment(&mut self, position: &Position) {
+        let data_independent = self.variant == Variant::Argon2id && position.pass == 0 &&
+                               position.slice < SYNC_POINTS / 2;
+
+        let mut input_block = Block::zero();
+        let mut address_block = Block::zero();
+
+        if data_independent {
+            input_block.0[0] = position.pass as u64;
+            input_block.0[1] = position.lane as u64;
+            input_block.0[2] = position.slice as u64;
+            input_block.0[3] = self.memory.len() as u64;
+            input_block.0[4] = self.passes as u64;
+            input_block.0[5] = self.variant.type_id() as u64;
+        }
+
+        let starting_index = if position.pass == 0 && position.slice == 0 {
+            if data_independent {
+                next_addresses(&mut address_block, &mut input_block);
+            }
+            2
+        } else {
+            0
+        };
+
+        let mut current_offset = position.lane * self.lane_length +
+                                 position.slice * self.segment_length +
+                                 starting_index;
+        let mut previous_offset = if current_offset % self.lane_length == 0 {
+            current_offset + self.lane_length - 1
+        } else {
+            current_offset - 1
+        };
+
+        for index in starting_index..self.segment_length {
+            if current_offset % self.lane_length == 1 {
+                previous_offset = current_offset - 1;
+            }
+
+            let pseudo_random = if data_independent {
+                if index % ADDRESSES_IN_BLOCK == 0 {
+                    next_addresses(&mut address_block, &mut input_block);
+                }
+                address_block.0[(index % ADDRESSES_IN_BLOCK) as usize]
+            } else {
+                self.memory[previous_offset as usize].0[0]
+            };
+
+            let reference_lane = if position.pass == 0 && position.slice == 0 {
+                position.lane
+            } else {
+                ((pseudo_random >> 32) % self.lanes as u64) as u32
+            };
+
+            let reference_index = self.reference_index(position,
+                                                       index,
+                                                       pseudo_random as u32,
+                                                       reference_lane == position.lane);
+            let reference_offset = reference_lane * self.lane_length + reference_index;
+
+            let mut block = compress(&self.memory[previous_offset as usize],
+                                     &self.memory[reference_offset as usize]);
+            if self.version != VERSION_10 && position.pass != 0 {
+                block.xor(&self.memory[current_offset as usize]);
+            }
+            self.memory[current_offset as usize] = block;
+
+            current_offset += 1;
+            previous_offset += 1;
+        }
+    }
+
+    fn reference_index(&self,
+                       position: &Position,
+                       index: u32,
+                       pseudo_random: u32,
+                       same_lane: bool)
+                       -> u32 {
+        let reference_area_size = if position.pass == 0 {
+            if position.slice == 0 {
+                index - 1
+            } else if same_lane {
+                position.slice * self.segment_length + index - 1
+            } else if index == 0 {
+                position.slice * self.segment_length - 1
+            } else {
+                position.slice * self.segment_length
+            }
+        } else if same_lane {
+            self.lane_length - self.segment_length + index - 1
+        } else if index == 0 {
+            self.lane_length - self.segment_length - 1
+        } else {
+            self.lane_length - self.segment_length
+        };
+
+        let relative_position = pseudo_random as u64;
+        let relative_position = (relative_position * relative_position) >> 32;
+        let relative_position = reference_area_size as u64 - 1 -
+                                ((reference_area_size as u64 * relative_position) >> 32);
+
+        let start_position = if position.pass != 0 && position.slice != SYNC_POINTS - 1 {
+            (position.slice + 1) * self.segment_length
+        } else {
+            0
+        };
+
+        ((start_position as u64 + relative_position) % self.lane_length as u64) as u32
+    }
+
+    fn finalize(&self, length: usize) -> Vec<u8> {
+        let mut block = self.memory[self.lane_length as usize - 1];
+        for lane in 1..self.lanes {
+            let last = (lane * self.lane_length + self.lane_length - 1) as usize;
+            block.xor(&self.memory[last]);
+        }
+
+        variable_hash(&block.to_bytes(), length)
+    }
+}
+
+pub fn hash(parameters: &Parameters, password: &[u8], length: usize) -> Result<Vec<u8>, Error> {
+    try!(check_parameters(parameters));
+
+    let lanes = parameters.parallelism;
+    let segment_length = parameters.memory / (lanes * SYNC_POINTS);
+    let lane_length = segment_length * SYNC_POINTS;
+
+    let mut instance = Instance {
+        variant: parameters.variant,
+        version: parameters.version,
+        passes: parameters.iterations,
+        lanes: lanes,
+        lane_length: lane_length,
+        segment_length: segment_length,
+        memory: vec![Block::zero(); (lane_length * lanes) as usize],
+    };
+
+    let initial_hash = initial_hash(parameters, password, length);
+    for lane in 0..lanes {
+        for i in 0..2 {
+            let mut input = initial_hash.to_vec();
+            input.extend(le32(i).iter().cloned());
+            input.extend(le32(lane).iter().cloned());
+
+            let offset = (lane * lane_length + i) as usize;
+            instance.memory[offset] = Block::from_bytes(&variable_hash(&input, BLOCK_SIZE));
+        }
+    }
+
+    instance.fill_memory();
+
+    Ok(instance.finalize(length))
+}
+
+fn check_parameters(parameters: &Parameters) -> Result<(), Error> {
+    if parameters.version != VERSION_10 && parameters.version != VERSION_13 {
+        Err(Error::InvalidKdfParameters)
+    } else if parameters.parallelism == 0 || parameters.parallelism > MAX_PARALLELISM {
+        Err(Error::InvalidKdfParameters)
+    } else if parameters.memory / 8 < parameters.parallelism {
+        Err(Error::InvalidKdfParameters)
+    } else if parameters.memory > MAX_MEMORY {
+        Err(Error::InvalidKdfParameters)
+    } else if parameters.iterations == 0 {
+        Err(Error::InvalidKdfParameters)
+    } else if parameters.memory as u64 * parameters.iterations as u64 > MAX_WORK {
+        Err(Error::InvalidKdfParameters)
+    } else if parameters.salt.len() < MIN_SALT_LENGTH {
+        Err(Error::InvalidKdfParameters)
+    } else {
+        Ok(())
+    }
+}
+
+fn initial_hash(parameters: &Parameters, password: &[u8], length: usize) -> [u8; 64] {
+    let mut digest = Blake2b::new(64);
+    digest.input(&le32(parameters.parallelism));
+    digest.input(&le32(length as u32));
+    digest.input(&le32(parameters.memory));
+    digest.input(&le32(parameters.iterations));
+    digest.input(&le32(parameters.version));
+    digest.input(&le32(parameters.variant.type_id()));
+
+    for value in &[password,
+                  &parameters.salt[..],
+                  &parameters.secret[..],
+                  &parameters.associated_data[..]] {
+        digest.input(&le32(value.len() as u32));
+        digest.input(value);
+    }
+
+    let mut result = [0; 64];
+    digest.result(&mut result);
+    result
+}
+
+fn variable_hash(input: &[u8], length: usize) -> Vec<u8> {
+    if length <= 64 {
+        return blake2b(&[&le32(length as u32)[..], input], length);
+    }
+
+    let mut result = Vec::with_capacity(length);
+    let mut value = blake2b(&[&le32(length as u32)[..], input], 64);
+    result.extend(value[..32].iter().cloned());
+
+    while length - result.len() > 64 {
+        value = blake2b(&[&value[..]], 64);
+        result.extend(value[..32].iter().cloned());
+    }
+
+    let remaining = length - result.len();
+    result.extend(blake2b(&[&value[..]], remaining));
+    result
+}
+
+fn blake2b(inputs: &[&[u8]], length: usize) -> Vec<u8> {
+    let mut digest = Blake2b::new(length);
+    for input in inputs {
+        digest.input(input);
+    }
+
+    let mut result = vec![0; length];
+    digest.result(&mut result);
+    result
+}
+
+fn next_addresses(address_block: &mut Block, input_block: &mut Block) {
+    input_block.0[6] += 1;
+    *address_block = compress(&Block::zero(), &compress(&Block::zero(), input_block));
+}
+
+fn compress(x: &Block, y: &Block) -> Block {
+    let mut r = *x;
+    r.xor(y);
+
+    let mut z = r;
+    let mut indices = [0; 16];
+    for i in 0..8 {
+        for (k, index) in indices.iter_mut().enumerate() {
+            *index = 16 * i + k;
+        }
+        permute(&mut z, &indices);
+    }
+    for i in 0..8 {
+        for j in 0..8 {
+            indices[2 * j] = 2 * i + 16 * j;
+            indices[2 * j + 1] = 2 * i + 16 * j + 1;
+        }
+        permute(&mut z, &indices);
+    }
+
+    z.xor(&r);
+    z
+}
+
+fn permute(block: &mut Block, indices: &[usize; 16]) {
+    let v = |i: usize| indices[i];
+
+    mix(block, v(0), v(4), v(8), v(12));
+    mix(block, v(1), v(5), v(9), v(13));
+    mix(block, v(2), v(6), v(10), v(14));
+    mix(block, v(3), v(7), v(11), v(15));
+    mix(block, v(0), v(5), v(10), v(15));
+    mix(block, v(1), v(6), v(11), v(12));
+    mix(block, v(2), v(7), v(8), v(13));
+    mix(block, v(3), v(4), v(9), v(14));
+}
+
+fn mix(block: &mut Block, a: usize, b: usize, c: usize, d: usize) {
+    let w = &mut block.0;
+
+    w[a] = multiply_add(w[a], w[b]);
+    w[d] = (w[d] ^ w[a]).rotate_right(32);
+    w[c] = multiply_add(w[c], w[d]);
+    w[b] = (w[b] ^ w[c]).rotate_right(24);
+    w[a] = multiply_add(w[a], w[b]);
+    w[d] = (w[d] ^ w[a]).rotate_right(16);
+    w[c] = multiply_add(w[c], w[d]);
+    w[b] = (w[b] ^ w[c]).rotate_right(63);
+}
+
+fn multiply_add(x: u64, y: u64) -> u64 {
+    let product = (x & 0xFFFFFFFF).wrapping_mul(y & 0xFFFFFFFF);
+    x.wrapping_add(y).wrapping_add(product.wrapping_mul(2))
+}
+
+fn le32(value: u32) -> [u8; 4] {
+    [value as u8, (value >> 8) as u8, (value >> 16) as u8, (value >> 24) as u8]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use Error;
+
+    fn rfc_parameters(variant: Variant) -> Parameters {
+        Parameters {
+            variant: variant,
+            version: VERSION_13,
+            memory: 32,
+            iterations: 3,
+            parallelism: 4,
+            salt: vec![0x02; 16],
+            secret: vec![0x03; 8],
+            associated_data: vec![0x04; 12],
+        }
+    }
+
+    #[test]
+    fn should_hash_argon2d_test_vector() {
+        let result = hash(&rfc_parameters(Variant::Argon2d), &[0x01; 32], 32).unwrap();
+        assert_eq!(result,
+                   vec![0x51, 0x2b, 0x39, 0x1b, 0x6f, 0x11, 0x62, 0x97, 0x53, 0x71, 0xd3,
+                        0x09, 0x19, 0x73, 0x42, 0x94, 0xf8, 0x68, 0xe3, 0xbe, 0x39, 0x84,
+                        0xf3, 0xc1, 0xa1, 0x3a, 0x4d, 0xb9, 0xfa, 0xbe, 0x4a, 0xcb]);
+    }
+
+    #[test]
+    fn should_hash_argon2id_test_vector() {
+        let result = hash(&rfc_parameters(Variant::Argon2id), &[0x01; 32], 32).unwrap();
+        assert_eq!(result,
+                   vec![0x0d, 0x64, 0x0d, 0xf5, 0x8d, 0x78, 0x76, 0x6c, 0x08, 0xc0, 0x37,
+                        0xa3, 0x4a, 0x8b, 0x53, 0xc9, 0xd0, 0x1e, 0xf0, 0x45, 0x2d, 0x75,
+                        0xb6, 0x5e, 0xb5, 0x25, 0x20, 0xe9, 0x6b, 0x01, 0xe6, 0x59]);
+    }
+
+    #[test]
+    fn should_return_error_if_invalid_version() {
+        let mut parameters = rfc_parameters(Variant::Argon2d);
+        parameters.version = 0x12;
+
+        let result = hash(&parameters, &[0x01; 32], 32);
+
+        match result {
+            Err(Error::InvalidKdfParameters) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
+    #[test]
+    fn should_return_error_if_not_enough_memory() {
+        let mut parameters = rfc_parameters(Variant::Argon2d);
+        parameters.memory = 31;
+
+        let result = hash(&parameters, &[0x01; 32], 32);
+
+        match result {
+            Err(Error::InvalidKdfParameters) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
+    #[test]
+    fn should_return_error_if_too_much_memory_or_iterations() {
+        let mut parameters = rfc_parameters(Variant::Argon2d);
+        parameters.memory = u32::max_value();
+        let result = hash(&parameters, &[0x01; 32], 32);
+        match result {
+            Err(Error::InvalidKdfParameters) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+
+        let mut parameters = rfc_parameters(Variant::Argon2d);
+        parameters.iterations = u32::max_value();
+        let result = hash(&parameters, &[0x01; 32], 32);
+        match result {
+            Err(Error::InvalidKdfParameters) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+
+        let mut parameters = rfc_parameters(Variant::Argon2d);
+        parameters.memory = MAX_MEMORY;
+        parameters.iterations = 65;
+        let result = hash(&parameters, &[0x01; 32], 32);
+        match result {
+            Err(Error::InvalidKdfParameters) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+}
@@ -1,9 +1,9 @@
 use Error;
 
 use byteorder;
-use byteorder::{LittleEndian, ReadBytesExt};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 
-use std::io::Read;
+use std::io::{Read, Write};
 
 pub fn read_u8(reader: &mut Read) -> Result<u8, Error> {
     reader.read_u8().map_err(handle_error)
@@ -21,6 +21,37 @@ pub fn read_u64(reader: &mut Read) -> Result<u64, Error> {
     reader.read_u64::<LittleEndian>().map_err(handle_error)
 }
 
+pub fn read_bytes(reader: &mut Read, length: usize) -> Result<Vec<u8>, Error> {
+    let mut buf = vec![];
+    try!(reader.take(length as u64).read_to_end(&mut buf).map_err(|e| Error::Io(e)));
+
+    if buf.len() == length {
+        Ok(buf)
+    } else {
+        Err(Error::UnexpectedEOF)
+    }
+}
+
+pub fn write_u8(writer: &mut Write, value: u8) -> Result<(), Error> {
+    writer.write_u8(value).map_err(handle_error)
+}
+
+pub fn write_u16(writer: &mut Write, value: u16) -> Result<(), Error> {
+    writer.write_u16::<LittleEndian>(value).map_err(handle_error)
+}
+
+pub fn write_u32(writer: &mut Write, value: u32) -> Result<(), Error> {
+    writer.write_u32::<LittleEndian>(value).map_err(handle_error)
+}
+
+pub fn write_u64(writer: &mut Write, value: u64) -> Result<(), Error> {
+    writer.write_u64::<LittleEndian>(value).map_err(handle_error)
+}
+
+pub fn write_all(writer: &mut Write, value: &[u8]) -> Result<(), Error> {
+    writer.write_all(value).map_err(|e| Error::Io(e))
+}
+
 fn handle_error(err: byteorder::Error) -> Error {
     match err {
         byteorder::Error::UnexpectedEOF => Error::UnexpectedEOF,
@@ -109,4 +140,66 @@ mod test {
             _ => panic!("Invalid result: {:#?}", result),
         }
     }
+
+    #[test]
+    pub fn should_read_bytes() {
+        let bytes = vec![10, 20, 30];
+        let result = read_bytes(&mut &bytes[..], 2).unwrap();
+
+        assert_eq!(result, vec![10, 20]);
+    }
+
+    #[test]
+    pub fn should_return_error_if_bytes_can_not_be_read() {
+        let bytes = vec![10, 20, 30];
+        let result = read_bytes(&mut &bytes[..], 4);
+
+        match result {
+            Err(Error::UnexpectedEOF) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
+    #[test]
+    pub fn should_return_error_if_bytes_length_exceeds_data() {
+        let bytes = vec![10, 20, 30];
+        let result = read_bytes(&mut &bytes[..], u32::max_value() as usize);
+
+        match result {
+            Err(Error::UnexpectedEOF) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
+    #[test]
+    pub fn should_write_u8() {
+        let mut bytes = vec![];
+        write_u8(&mut bytes, 0x0A).unwrap();
+
+        assert_eq!(bytes, vec![10]);
+    }
+
+    #[test]
+    pub fn should_write_u16() {
+        let mut bytes = vec![];
+        write_u16(&mut bytes, 0x140A).unwrap();
+
+        assert_eq!(bytes, vec![10, 20]);
+    }
+
+    #[test]
+    pub fn should_write_u32() {
+        let mut bytes = vec![];
+        write_u32(&mut bytes, 0x281E140A).unwrap();
+
+        assert_eq!(bytes, vec![10, 20, 30, 40]);
+    }
+
+    #[test]
+    pub fn should_write_u64() {
+        let mut bytes = vec![];
+        write_u64(&mut bytes, 0x50463C32281E140A).unwrap();
+
+        assert_eq!(bytes, vec![10, 20, 30, 40, 50, 60, 70, 80]);
+    }
 }
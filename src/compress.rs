@@ -0,0 +1,35 @@
+use Error;
+use std::io::Write;
+
+use flate2::Compression;
+use flate2::write::GzEncoder;
+
+pub fn none(data: &[u8]) -> Result<Vec<u8>, Error> {
+    Ok(data.to_vec())
+}
+
+pub fn gzip(data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut encoder = GzEncoder::new(vec![], Compression::Default);
+    try!(encoder.write_all(data).map_err(|e| Error::Io(e)));
+
+    encoder.finish().map_err(|e| Error::Io(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use decompress;
+
+    use std::io::{Cursor, Read};
+
+    #[test]
+    fn should_compress_with_gzip() {
+        let data = b"hunter2 hunter2 hunter2";
+        let compressed = super::gzip(data).unwrap();
+
+        let mut decompressed = decompress::gzip(Box::new(Cursor::new(compressed))).unwrap();
+        let mut result = vec![];
+        decompressed.read_to_end(&mut result).unwrap();
+
+        assert_eq!(&result[..], &data[..]);
+    }
+}
@@ -0,0 +1,345 @@
+use Error;
+
+pub const DEFAULT_SEQUENCE: &'static str = "{USERNAME}{TAB}{PASSWORD}{ENTER}";
+
+const SPECIAL_KEYS: [&'static str; 39] = ["TAB", "ENTER", "UP", "DOWN", "LEFT", "RIGHT", "HOME",
+                                          "END", "PGUP", "PGDN", "INSERT", "INS", "DELETE", "DEL",
+                                          "BACKSPACE", "BS", "BKSP", "BREAK", "CAPSLOCK", "ESC",
+                                          "WIN", "LWIN", "RWIN", "APPS", "HELP", "NUMLOCK",
+                                          "PRTSC", "SCROLLLOCK", "SPACE", "ADD", "SUBTRACT",
+                                          "MULTIPLY", "DIVIDE", "LEFTPAREN", "RIGHTPAREN",
+                                          "PLUS", "PERCENT", "CARET", "TILDE"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Obfuscation {
+    None,
+    UseClipboard,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Modifier {
+    Shift,
+    Control,
+    Alt,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum KeyAction {
+    Text(String),
+    Placeholder(String),
+    Key(String, u32),
+    Modifier(Modifier),
+    Delay(u32),
+    SetDelay(u32),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Association {
+    window: String,
+    sequence: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AutoType {
+    enabled: bool,
+    obfuscation: Obfuscation,
+    default_sequence: Option<String>,
+    associations: Vec<Association>,
+}
+
+impl Obfuscation {
+    pub fn from_u32(value: u32) -> Option<Obfuscation> {
+        match value {
+            0 => Some(Obfuscation::None),
+            1 => Some(Obfuscation::UseClipboard),
+            _ => None,
+        }
+    }
+
+    pub fn to_u32(&self) -> u32 {
+        match *self {
+            Obfuscation::None => 0,
+            Obfuscation::UseClipboard => 1,
+        }
+    }
+}
+
+impl Association {
+    pub fn new(window: &str, sequence: Option<String>) -> Association {
+        Association {
+            window: window.to_string(),
+            sequence: sequence,
+        }
+    }
+
+    pub fn window(&self) -> &str {
+        &self.window
+    }
+
+    pub fn sequence(&self) -> &Option<String> {
+        &self.sequence
+    }
+
+    pub fn matches(&self, title: &str) -> bool {
+        matches_wildcard(&self.window.to_lowercase(), &title.to_lowercase())
+    }
+}
+
+impl Default for AutoType {
+    fn default() -> AutoType {
+        AutoType {
+            enabled: true,
+            obfuscation: Obfuscation::None,
+            default_sequence: None,
+            associations: vec![],
+        }
+    }
+}
+
+impl AutoType {
+    pub fn new() -> AutoType {
+        AutoType::default()
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn obfuscation(&self) -> Obfuscation {
+        self.obfuscation
+    }
+
+    pub fn set_obfuscation(&mut self, obfuscation: Obfuscation) {
+        self.obfuscation = obfuscation;
+    }
+
+    pub fn default_sequence(&self) -> &Option<String> {
+        &self.default_sequence
+    }
+
+    pub fn set_default_sequence(&mut self, sequence: Option<String>) {
+        self.default_sequence = sequence;
+    }
+
+    pub fn associations(&self) -> &[Association] {
+        &self.associations
+    }
+
+    pub fn add_association(&mut self, association: Association) {
+        self.associations.push(association);
+    }
+
+    pub fn association(&self, title: &str) -> Option<&Association> {
+        self.associations.iter().find(|association| association.matches(title))
+    }
+
+    pub fn parse_sequence(sequence: &str) -> Result<Vec<KeyAction>, Error> {
+        let mut actions = vec![];
+        let mut text = String::new();
+        let mut chars = sequence.char_indices().peekable();
+
+        while let Some((index, c)) = chars.next() {
+            let action = match c {
+                '{' => {
+                    let rest = &sequence[index + 1..];
+                    let length = if rest.starts_with("}}") {
+                        1
+                    } else {
+                        try!(rest.find('}')
+                                 .ok_or(Error::InvalidAutoTypeSequence(sequence.to_string())))
+                    };
+
+                    let end = index + 1 + length;
+                    while chars.peek().map_or(false, |&(i, _)| i <= end) {
+                        chars.next();
+                    }
+
+                    try!(parse_braced(&rest[..length], sequence))
+                }
+                '}' => return Err(Error::InvalidAutoTypeSequence(sequence.to_string())),
+                '+' => KeyAction::Modifier(Modifier::Shift),
+                '^' => KeyAction::Modifier(Modifier::Control),
+                '%' => KeyAction::Modifier(Modifier::Alt),
+                '~' => KeyAction::Key("ENTER".to_string(), 1),
+                _ => {
+                    text.push(c);
+                    continue;
+                }
+            };
+
+            push_action(&mut actions, &mut text, action);
+        }
+
+        if !text.is_empty() {
+            actions.push(KeyAction::Text(text));
+        }
+
+        Ok(actions)
+    }
+}
+
+fn push_action(actions: &mut Vec<KeyAction>, text: &mut String, action: KeyAction) {
+    if let KeyAction::Text(value) = action {
+        text.push_str(&value);
+        return;
+    }
+
+    if !text.is_empty() {
+        actions.push(KeyAction::Text(text.clone()));
+        text.clear();
+    }
+    actions.push(action);
+}
+
+fn parse_braced(content: &str, sequence: &str) -> Result<KeyAction, Error> {
+    let invalid = || Error::InvalidAutoTypeSequence(sequence.to_string());
+
+    if content.is_empty() {
+        return Err(invalid());
+    }
+    if content.chars().count() == 1 && "{}+^%~()[]".contains(content) {
+        return Ok(KeyAction::Text(content.to_string()));
+    }
+
+    let upper = content.to_uppercase();
+    if upper.starts_with("DELAY=") {
+        return content[6..].trim().parse().map(KeyAction::SetDelay).map_err(|_| invalid());
+    }
+    if upper.starts_with("DELAY ") {
+        return content[6..].trim().parse().map(KeyAction::Delay).map_err(|_| invalid());
+    }
+
+    let (key, repeat) = match upper.rfind(' ') {
+        Some(index) => {
+            match upper[index + 1..].parse() {
+                Ok(repeat) => (&upper[..index], repeat),
+                Err(_) => (&upper[..], 1),
+            }
+        }
+        None => (&upper[..], 1),
+    };
+
+    if is_special_key(key) {
+        Ok(KeyAction::Key(key.to_string(), repeat))
+    } else {
+        Ok(KeyAction::Placeholder(content.to_string()))
+    }
+}
+
+fn is_special_key(key: &str) -> bool {
+    if SPECIAL_KEYS.contains(&key) {
+        return true;
+    }
+
+    let number = |prefix: &str, max: u32| {
+        key.starts_with(prefix) &&
+        key[prefix.len()..].parse::<u32>().ok().map_or(false, |n| n <= max)
+    };
+    number("F", 24) || number("NUMPAD", 9)
+}
+
+fn matches_wildcard(pattern: &str, value: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == value;
+    }
+
+    let first = parts[0];
+    let last = parts[parts.len() - 1];
+    if !value.starts_with(first) || value.len() < first.len() + last.len() ||
+       !value.ends_with(last) {
+        return false;
+    }
+
+    let mut rest = &value[first.len()..value.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Association, AutoType, KeyAction, Modifier};
+
+    use Error;
+
+    fn key(name: &str, repeat: u32) -> KeyAction {
+        KeyAction::Key(name.to_string(), repeat)
+    }
+
+    fn placeholder(name: &str) -> KeyAction {
+        KeyAction::Placeholder(name.to_string())
+    }
+
+    #[test]
+    fn should_parse_default_sequence() {
+        let actions = AutoType::parse_sequence("{USERNAME}{TAB}{PASSWORD}{ENTER}{DELAY 200}")
+                          .unwrap();
+
+        assert_eq!(actions,
+                   vec![placeholder("USERNAME"),
+                        key("TAB", 1),
+                        placeholder("PASSWORD"),
+                        key("ENTER", 1),
+                        KeyAction::Delay(200)]);
+    }
+
+    #[test]
+    fn should_parse_text_modifiers_and_escapes() {
+        let actions = AutoType::parse_sequence("{DELAY=50}^a{DEL}user{+}1{}}{TAB 3}~").unwrap();
+
+        assert_eq!(actions,
+                   vec![KeyAction::SetDelay(50),
+                        KeyAction::Modifier(Modifier::Control),
+                        KeyAction::Text("a".to_string()),
+                        key("DEL", 1),
+                        KeyAction::Text("user+1}".to_string()),
+                        key("TAB", 3),
+                        key("ENTER", 1)]);
+    }
+
+    #[test]
+    fn should_keep_custom_placeholders() {
+        let actions = AutoType::parse_sequence("{S:Kontonummer äö}{F5}ü{NUMPAD7}").unwrap();
+
+        assert_eq!(actions,
+                   vec![placeholder("S:Kontonummer äö"),
+                        key("F5", 1),
+                        KeyAction::Text("ü".to_string()),
+                        key("NUMPAD7", 1)]);
+    }
+
+    #[test]
+    fn should_return_error_if_unbalanced_braces() {
+        for sequence in &["{USERNAME", "{TAB}}", "{}", "{DELAY x}"] {
+            let result = AutoType::parse_sequence(sequence);
+
+            match result {
+                Err(Error::InvalidAutoTypeSequence(ref value)) if value == sequence => (),
+                _ => panic!("Invalid result: {:#?}", result),
+            }
+        }
+    }
+
+    #[test]
+    fn should_match_window_wildcards() {
+        let association = Association::new("*Firefox", None);
+        assert!(association.matches("GitHub - Mozilla Firefox"));
+        assert!(!association.matches("GitHub - Chromium"));
+
+        let association = Association::new("Login*Bank*", None);
+        assert!(association.matches("login - my bank - secure"));
+        assert!(!association.matches("Bank login"));
+
+        assert!(Association::new("PuTTY", None).matches("putty"));
+    }
+}
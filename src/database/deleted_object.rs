@@ -0,0 +1,24 @@
+use {Timestamp, Uuid};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeletedObject {
+    uuid: Uuid,
+    deletion_time: Option<Timestamp>,
+}
+
+impl DeletedObject {
+    pub fn new(uuid: Uuid, deletion_time: Option<Timestamp>) -> DeletedObject {
+        DeletedObject {
+            uuid: uuid,
+            deletion_time: deletion_time,
+        }
+    }
+
+    pub fn uuid(&self) -> &Uuid {
+        &self.uuid
+    }
+
+    pub fn deletion_time(&self) -> Option<Timestamp> {
+        self.deletion_time
+    }
+}
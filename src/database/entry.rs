@@ -1,8 +1,86 @@
-#[derive(Debug)]
+use {AutoType, Error, Times, Uuid, XmlElement};
+
+use std::mem;
+
+pub const TITLE: &'static str = "Title";
+pub const USERNAME: &'static str = "UserName";
+pub const PASSWORD: &'static str = "Password";
+pub const URL: &'static str = "URL";
+pub const NOTES: &'static str = "Notes";
+
+const STANDARD_FIELDS: [&'static str; 5] = [TITLE, USERNAME, PASSWORD, URL, NOTES];
+const MISSING: &'static Option<String> = &None;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    key: String,
+    value: Option<String>,
+    protected: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Attachment {
+    name: String,
+    data: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Default)]
 pub struct DatabaseEntry {
-    title: Option<String>,
-    username: Option<String>,
-    password: Option<String>,
+    uuid: Uuid,
+    icon_id: Option<u32>,
+    custom_icon_uuid: Option<Uuid>,
+    foreground_color: Option<String>,
+    background_color: Option<String>,
+    tags: Vec<String>,
+    auto_type: AutoType,
+    fields: Vec<Field>,
+    attachments: Vec<Attachment>,
+    times: Times,
+    unknown_elements: Vec<(Option<String>, XmlElement)>,
+    history: Vec<DatabaseEntry>,
+}
+
+impl Field {
+    pub fn new(key: &str, value: Option<String>, protected: bool) -> Field {
+        Field {
+            key: key.to_string(),
+            value: value,
+            protected: protected,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn value(&self) -> &Option<String> {
+        &self.value
+    }
+
+    pub fn is_protected(&self) -> bool {
+        self.protected
+    }
+
+    pub fn is_standard(&self) -> bool {
+        STANDARD_FIELDS.contains(&&self.key[..])
+    }
+}
+
+impl Attachment {
+    pub fn new(name: &str, data: Vec<u8>) -> Attachment {
+        Attachment {
+            name: name.to_string(),
+            data: data,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
 }
 
 impl DatabaseEntry {
@@ -10,36 +88,295 @@ impl DatabaseEntry {
                username: Option<String>,
                password: Option<String>)
                -> DatabaseEntry {
-        DatabaseEntry {
-            title: title,
-            username: username,
-            password: password,
+        let mut entry = DatabaseEntry::default();
+        entry.uuid = Uuid::new_v4();
+        entry.set_field(TITLE, title, false);
+        entry.set_field(USERNAME, username, false);
+        entry.set_field(PASSWORD, password, true);
+        entry
+    }
+
+    pub fn uuid(&self) -> &Uuid {
+        &self.uuid
+    }
+
+    pub fn set_uuid(&mut self, uuid: Uuid) {
+        self.uuid = uuid;
+    }
+
+    pub fn icon_id(&self) -> Option<u32> {
+        self.icon_id
+    }
+
+    pub fn set_icon_id(&mut self, icon_id: Option<u32>) {
+        self.icon_id = icon_id;
+    }
+
+    pub fn custom_icon_uuid(&self) -> Option<Uuid> {
+        self.custom_icon_uuid
+    }
+
+    pub fn set_custom_icon_uuid(&mut self, uuid: Option<Uuid>) {
+        self.custom_icon_uuid = uuid;
+    }
+
+    pub fn foreground_color(&self) -> &Option<String> {
+        &self.foreground_color
+    }
+
+    pub fn set_foreground_color(&mut self, color: Option<String>) {
+        self.foreground_color = color;
+    }
+
+    pub fn background_color(&self) -> &Option<String> {
+        &self.background_color
+    }
+
+    pub fn set_background_color(&mut self, color: Option<String>) {
+        self.background_color = color;
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    pub fn set_tags(&mut self, tags: Vec<String>) {
+        self.tags = tags;
+    }
+
+    pub fn add_tag(&mut self, tag: &str) {
+        if !self.has_tag(tag) {
+            self.tags.push(tag.to_string());
         }
     }
 
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.to_lowercase() == tag.to_lowercase())
+    }
+
+    pub fn auto_type(&self) -> &AutoType {
+        &self.auto_type
+    }
+
+    pub fn auto_type_mut(&mut self) -> &mut AutoType {
+        &mut self.auto_type
+    }
+
     pub fn title(&self) -> &Option<String> {
-        &self.title
+        self.value(TITLE)
     }
 
     pub fn username(&self) -> &Option<String> {
-        &self.username
+        self.value(USERNAME)
     }
 
     pub fn password(&self) -> &Option<String> {
-        &self.password
+        self.value(PASSWORD)
+    }
+
+    pub fn url(&self) -> &Option<String> {
+        self.value(URL)
+    }
+
+    pub fn notes(&self) -> &Option<String> {
+        self.value(NOTES)
+    }
+
+    pub fn set_title(&mut self, title: Option<String>) {
+        self.set_value(TITLE, title, false);
+    }
+
+    pub fn set_username(&mut self, username: Option<String>) {
+        self.set_value(USERNAME, username, false);
+    }
+
+    pub fn set_password(&mut self, password: Option<String>) {
+        self.set_value(PASSWORD, password, true);
+    }
+
+    pub fn set_url(&mut self, url: Option<String>) {
+        self.set_value(URL, url, false);
+    }
+
+    pub fn set_notes(&mut self, notes: Option<String>) {
+        self.set_value(NOTES, notes, false);
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.value(key).as_ref().map(|value| &value[..])
+    }
+
+    pub fn field(&self, key: &str) -> Option<&Field> {
+        self.fields.iter().find(|field| field.key == key)
+    }
+
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    pub fn custom_fields<'a>(&'a self) -> Box<Iterator<Item = &'a Field> + 'a> {
+        Box::new(self.fields.iter().filter(|field| !field.is_standard()))
+    }
+
+    pub fn set_field(&mut self, key: &str, value: Option<String>, protected: bool) {
+        let field = Field::new(key, value, protected);
+
+        match self.fields.iter().position(|f| f.key == key) {
+            Some(index) => self.fields[index] = field,
+            None => self.fields.push(field),
+        }
+    }
+
+    fn set_value(&mut self, key: &str, value: Option<String>, protected: bool) {
+        let protected = self.field(key).map_or(protected, |field| field.protected);
+        self.set_field(key, value, protected);
+    }
+
+    pub fn remove_field(&mut self, key: &str) -> Option<Field> {
+        self.fields.iter().position(|field| field.key == key).map(|index| self.fields.remove(index))
+    }
+
+    pub fn attachments(&self) -> &[Attachment] {
+        &self.attachments
+    }
+
+    pub fn attachment(&self, name: &str) -> Option<&Attachment> {
+        self.attachments.iter().find(|attachment| attachment.name == name)
+    }
+
+    pub fn add_attachment(&mut self, attachment: Attachment) {
+        self.attachments.push(attachment);
+    }
+
+    pub fn remove_attachment(&mut self, name: &str) -> Option<Attachment> {
+        self.attachments
+            .iter()
+            .position(|attachment| attachment.name == name)
+            .map(|index| self.attachments.remove(index))
+    }
+
+    pub fn times(&self) -> &Times {
+        &self.times
+    }
+
+    pub fn times_mut(&mut self) -> &mut Times {
+        &mut self.times
+    }
+
+    pub fn unknown_elements(&self) -> Vec<&XmlElement> {
+        self.unknown_elements.iter().map(|&(_, ref element)| element).collect()
+    }
+
+    pub fn unknown_elements_after(&self, name: Option<&str>) -> Vec<&XmlElement> {
+        self.unknown_elements
+            .iter()
+            .filter(|&&(ref after, _)| after.as_ref().map(|after| &after[..]) == name)
+            .map(|&(_, ref element)| element)
+            .collect()
+    }
+
+    pub fn add_unknown_element(&mut self, element: XmlElement, after: Option<String>) {
+        self.unknown_elements.push((after, element));
+    }
+
+    pub fn history(&self) -> &[DatabaseEntry] {
+        &self.history
+    }
+
+    pub fn add_history(&mut self, entry: DatabaseEntry) {
+        self.history.push(entry);
+    }
+
+    pub fn create_backup(&mut self) {
+        let history = mem::replace(&mut self.history, vec![]);
+        let backup = self.clone();
+
+        self.history = history;
+        self.history.push(backup);
+    }
+
+    pub fn restore_history(&mut self, index: usize) -> Result<(), Error> {
+        let past = match self.history.get(index) {
+            Some(entry) => entry.clone(),
+            None => return Err(Error::InvalidHistoryIndex(index)),
+        };
+
+        self.create_backup();
+        self.icon_id = past.icon_id;
+        self.custom_icon_uuid = past.custom_icon_uuid;
+        self.foreground_color = past.foreground_color;
+        self.background_color = past.background_color;
+        self.tags = past.tags;
+        self.auto_type = past.auto_type;
+        self.fields = past.fields;
+        self.attachments = past.attachments;
+
+        Ok(())
+    }
+
+    pub fn remove_history(&mut self, index: usize) -> Result<DatabaseEntry, Error> {
+        if index < self.history.len() {
+            Ok(self.history.remove(index))
+        } else {
+            Err(Error::InvalidHistoryIndex(index))
+        }
+    }
+
+    pub fn trim_history(&mut self, max_items: i32, max_size: i64) {
+        if max_items >= 0 {
+            while self.history.len() > max_items as usize {
+                self.history.remove(0);
+            }
+        }
+
+        if max_size >= 0 {
+            while self.history.iter().map(|entry| entry.size()).sum::<u64>() > max_size as u64 {
+                self.history.remove(0);
+            }
+        }
+    }
+
+    pub fn size(&self) -> u64 {
+        let fields = self.fields.iter().map(|field| {
+            field.key.len() + field.value.as_ref().map_or(0, |value| value.len())
+        });
+        let attachments = self.attachments
+                              .iter()
+                              .map(|attachment| attachment.name.len() + attachment.data.len());
+
+        fields.chain(attachments).map(|size| size as u64).sum()
     }
 
     pub fn matches_title(&self, title: &str) -> bool {
-        match self.title {
+        match *self.title() {
             Some(ref t) => title == t,
             None => false,
         }
     }
+
+    fn value(&self, key: &str) -> &Option<String> {
+        self.field(key).map_or(MISSING, |field| &field.value)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::DatabaseEntry;
+    use super::{Attachment, DatabaseEntry, Field};
+
+    use Error;
+
+    fn entry_with_history() -> DatabaseEntry {
+        let mut entry = DatabaseEntry::new(Some("GitHub".to_string()),
+                                           Some("joe.bloggs".to_string()),
+                                           Some("hunter1".to_string()));
+
+        for password in &["hunter2", "hunter3", "hunter4"] {
+            entry.create_backup();
+            entry.set_field("Password", Some(password.to_string()), true);
+        }
+
+        entry
+    }
 
     #[test]
     fn should_create_entry() {
@@ -56,4 +393,120 @@ mod tests {
         assert!(entry.password().is_some());
         assert_eq!(entry.password().as_ref().unwrap(), "hunter2");
     }
+
+    #[test]
+    fn should_keep_custom_fields_in_order() {
+        let mut entry = DatabaseEntry::new(Some("GitHub".to_string()), None, None);
+        entry.set_field("URL", Some("https://github.com".to_string()), false);
+        entry.set_field("API_KEY", Some("0123456789".to_string()), true);
+        entry.set_field("Region", Some("eu".to_string()), false);
+        entry.set_field("API_KEY", Some("9876543210".to_string()), true);
+
+        assert_eq!(entry.url().as_ref().unwrap(), "https://github.com");
+        assert_eq!(entry.get("API_KEY"), Some("9876543210"));
+        assert_eq!(entry.get("Missing"), None);
+        assert!(entry.notes().is_none());
+        assert!(entry.field("Password").unwrap().is_protected());
+
+        let custom: Vec<&Field> = entry.custom_fields().collect();
+        assert_eq!(custom,
+                   vec![&Field::new("API_KEY", Some("9876543210".to_string()), true),
+                        &Field::new("Region", Some("eu".to_string()), false)]);
+    }
+
+    #[test]
+    fn should_match_tags_case_insensitively() {
+        let mut entry = DatabaseEntry::new(Some("GitHub".to_string()), None, None);
+        entry.add_tag("prod");
+        entry.add_tag("Work");
+        entry.add_tag("PROD");
+
+        assert_eq!(entry.tags(), ["prod", "Work"]);
+        assert!(entry.has_tag("Prod"));
+        assert!(entry.has_tag("work"));
+        assert!(!entry.has_tag("staging"));
+    }
+
+    #[test]
+    fn should_update_and_remove_fields() {
+        let mut entry = DatabaseEntry::new(Some("GitHub".to_string()), None, None);
+        entry.set_username(Some("joe.bloggs".to_string()));
+        entry.set_password(Some("hunter2".to_string()));
+        entry.set_notes(Some("2FA enabled".to_string()));
+        entry.add_attachment(Attachment::new("recovery.txt", b"1234".to_vec()));
+
+        assert_eq!(entry.username().as_ref().unwrap(), "joe.bloggs");
+        assert!(entry.field("Password").unwrap().is_protected());
+
+        let removed = entry.remove_field("Notes").unwrap();
+        assert_eq!(removed.value().as_ref().unwrap(), "2FA enabled");
+        assert!(entry.notes().is_none());
+        assert!(entry.remove_field("Notes").is_none());
+
+        assert_eq!(entry.remove_attachment("recovery.txt").unwrap().data(), b"1234");
+        assert!(entry.attachments().is_empty());
+    }
+
+    #[test]
+    fn should_keep_field_protection_when_updating() {
+        let mut entry = DatabaseEntry::new(Some("GitHub".to_string()), None, None);
+        entry.set_field("UserName", Some("joe.bloggs".to_string()), true);
+        entry.set_field("Password", Some("hunter2".to_string()), false);
+
+        entry.set_username(Some("jbloggs".to_string()));
+        entry.set_password(Some("hunter3".to_string()));
+        entry.set_url(Some("https://github.com".to_string()));
+
+        assert!(entry.field("UserName").unwrap().is_protected());
+        assert!(!entry.field("Password").unwrap().is_protected());
+        assert!(!entry.field("URL").unwrap().is_protected());
+    }
+
+    #[test]
+    fn should_restore_history() {
+        let mut entry = entry_with_history();
+        assert_eq!(entry.history().len(), 3);
+        assert_eq!(entry.history()[0].password().as_ref().unwrap(), "hunter1");
+
+        entry.restore_history(0).unwrap();
+
+        assert_eq!(entry.password().as_ref().unwrap(), "hunter1");
+        assert_eq!(entry.history().len(), 4);
+        assert_eq!(entry.history()[3].password().as_ref().unwrap(), "hunter4");
+    }
+
+    #[test]
+    fn should_return_error_if_invalid_history_index() {
+        let mut entry = entry_with_history();
+        let result = entry.restore_history(3);
+
+        match result {
+            Err(Error::InvalidHistoryIndex(3)) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
+    #[test]
+    fn should_trim_history_by_items() {
+        let mut entry = entry_with_history();
+        entry.trim_history(2, -1);
+
+        assert_eq!(entry.history().len(), 2);
+        assert_eq!(entry.history()[0].password().as_ref().unwrap(), "hunter2");
+
+        entry.trim_history(-1, -1);
+        assert_eq!(entry.history().len(), 2);
+    }
+
+    #[test]
+    fn should_trim_history_by_size() {
+        let mut entry = entry_with_history();
+        let size = entry.history()[0].size();
+        assert_eq!(size, 5 + 6 + 8 + 10 + 8 + 7);
+
+        entry.trim_history(-1, (2 * size) as i64);
+
+        assert_eq!(entry.history().len(), 2);
+        assert_eq!(entry.history()[1].password().as_ref().unwrap(), "hunter3");
+    }
 }
@@ -0,0 +1,370 @@
+use {DatabaseEntry, Times, Uuid, XmlElement};
+
+pub const PATH_SEPARATOR: char = '/';
+
+#[derive(Debug)]
+pub struct Group {
+    uuid: Uuid,
+    name: String,
+    notes: Option<String>,
+    icon_id: Option<u32>,
+    custom_icon_uuid: Option<Uuid>,
+    times: Times,
+    is_expanded: Option<bool>,
+    default_auto_type_sequence: Option<String>,
+    enable_auto_type: Option<bool>,
+    enable_searching: Option<bool>,
+    last_top_visible_entry: Option<Uuid>,
+    tags: Vec<String>,
+    unknown_elements: Vec<(Option<String>, XmlElement)>,
+    groups: Vec<Group>,
+    entries: Vec<DatabaseEntry>,
+}
+
+impl Group {
+    pub fn new(name: &str) -> Group {
+        Group {
+            uuid: Uuid::new_v4(),
+            name: name.to_string(),
+            notes: None,
+            icon_id: None,
+            custom_icon_uuid: None,
+            times: Times::new(),
+            is_expanded: None,
+            default_auto_type_sequence: None,
+            enable_auto_type: None,
+            enable_searching: None,
+            last_top_visible_entry: None,
+            tags: vec![],
+            unknown_elements: vec![],
+            groups: vec![],
+            entries: vec![],
+        }
+    }
+
+    pub fn uuid(&self) -> &Uuid {
+        &self.uuid
+    }
+
+    pub fn set_uuid(&mut self, uuid: Uuid) {
+        self.uuid = uuid;
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    pub fn notes(&self) -> &Option<String> {
+        &self.notes
+    }
+
+    pub fn set_notes(&mut self, notes: Option<String>) {
+        self.notes = notes;
+    }
+
+    pub fn icon_id(&self) -> Option<u32> {
+        self.icon_id
+    }
+
+    pub fn set_icon_id(&mut self, icon_id: Option<u32>) {
+        self.icon_id = icon_id;
+    }
+
+    pub fn custom_icon_uuid(&self) -> Option<Uuid> {
+        self.custom_icon_uuid
+    }
+
+    pub fn set_custom_icon_uuid(&mut self, uuid: Option<Uuid>) {
+        self.custom_icon_uuid = uuid;
+    }
+
+    pub fn times(&self) -> &Times {
+        &self.times
+    }
+
+    pub fn times_mut(&mut self) -> &mut Times {
+        &mut self.times
+    }
+
+    pub fn is_expanded(&self) -> Option<bool> {
+        self.is_expanded
+    }
+
+    pub fn set_expanded(&mut self, is_expanded: Option<bool>) {
+        self.is_expanded = is_expanded;
+    }
+
+    pub fn default_auto_type_sequence(&self) -> &Option<String> {
+        &self.default_auto_type_sequence
+    }
+
+    pub fn set_default_auto_type_sequence(&mut self, sequence: Option<String>) {
+        self.default_auto_type_sequence = sequence;
+    }
+
+    pub fn enable_auto_type(&self) -> Option<bool> {
+        self.enable_auto_type
+    }
+
+    pub fn set_enable_auto_type(&mut self, enable_auto_type: Option<bool>) {
+        self.enable_auto_type = enable_auto_type;
+    }
+
+    pub fn enable_searching(&self) -> Option<bool> {
+        self.enable_searching
+    }
+
+    pub fn set_enable_searching(&mut self, enable_searching: Option<bool>) {
+        self.enable_searching = enable_searching;
+    }
+
+    pub fn last_top_visible_entry(&self) -> Option<Uuid> {
+        self.last_top_visible_entry
+    }
+
+    pub fn set_last_top_visible_entry(&mut self, uuid: Option<Uuid>) {
+        self.last_top_visible_entry = uuid;
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    pub fn set_tags(&mut self, tags: Vec<String>) {
+        self.tags = tags;
+    }
+
+    pub fn unknown_elements(&self) -> Vec<&XmlElement> {
+        self.unknown_elements.iter().map(|&(_, ref element)| element).collect()
+    }
+
+    pub fn unknown_elements_after(&self, name: Option<&str>) -> Vec<&XmlElement> {
+        self.unknown_elements
+            .iter()
+            .filter(|&&(ref after, _)| after.as_ref().map(|after| &after[..]) == name)
+            .map(|&(_, ref element)| element)
+            .collect()
+    }
+
+    pub fn add_unknown_element(&mut self, element: XmlElement, after: Option<String>) {
+        self.unknown_elements.push((after, element));
+    }
+
+    pub fn groups(&self) -> &[Group] {
+        &self.groups
+    }
+
+    pub fn entries(&self) -> &[DatabaseEntry] {
+        &self.entries
+    }
+
+    pub fn add_group(&mut self, group: Group) {
+        self.groups.push(group);
+    }
+
+    pub fn add_entry(&mut self, entry: DatabaseEntry) {
+        self.entries.push(entry);
+    }
+
+    pub fn clear(&mut self) {
+        self.groups.clear();
+        self.entries.clear();
+    }
+
+    pub fn all_groups(&self) -> Vec<&Group> {
+        let mut groups = vec![];
+        for group in &self.groups {
+            groups.push(group);
+            groups.extend(group.all_groups());
+        }
+
+        groups
+    }
+
+    pub fn all_entries(&self) -> Vec<&DatabaseEntry> {
+        let mut entries: Vec<&DatabaseEntry> = self.entries.iter().collect();
+        for group in &self.groups {
+            entries.extend(group.all_entries());
+        }
+
+        entries
+    }
+
+    pub fn all_entries_mut(&mut self) -> Vec<&mut DatabaseEntry> {
+        let mut entries: Vec<&mut DatabaseEntry> = self.entries.iter_mut().collect();
+        for group in &mut self.groups {
+            entries.extend(group.all_entries_mut());
+        }
+
+        entries
+    }
+
+    pub fn group(&self, name: &str) -> Option<&Group> {
+        self.groups.iter().find(|group| group.name == name)
+    }
+
+    pub fn find_group(&self, path: &str) -> Option<&Group> {
+        path.split(PATH_SEPARATOR)
+            .filter(|name| !name.is_empty())
+            .fold(Some(self), |group, name| group.and_then(|g| g.group(name)))
+    }
+
+    pub fn find_entry(&self, path: &str) -> Option<&DatabaseEntry> {
+        let (group, title) = match path.rfind(PATH_SEPARATOR) {
+            Some(index) => (self.find_group(&path[..index]), &path[index + 1..]),
+            None => (Some(self), path),
+        };
+
+        group.and_then(|g| g.entries.iter().find(|entry| entry.matches_title(title)))
+    }
+
+    pub fn group_by_uuid(&self, uuid: &Uuid) -> Option<&Group> {
+        if self.uuid == *uuid {
+            return Some(self);
+        }
+
+        self.groups.iter().filter_map(|group| group.group_by_uuid(uuid)).next()
+    }
+
+    pub fn group_by_uuid_mut(&mut self, uuid: &Uuid) -> Option<&mut Group> {
+        if self.uuid == *uuid {
+            return Some(self);
+        }
+
+        self.groups.iter_mut().filter_map(|group| group.group_by_uuid_mut(uuid)).next()
+    }
+
+    pub fn remove_entry(&mut self, uuid: &Uuid) -> Option<DatabaseEntry> {
+        match self.entries.iter().position(|entry| entry.uuid() == uuid) {
+            Some(index) => Some(self.entries.remove(index)),
+            None => self.groups.iter_mut().filter_map(|group| group.remove_entry(uuid)).next(),
+        }
+    }
+
+    pub fn remove_group(&mut self, uuid: &Uuid) -> Option<Group> {
+        match self.groups.iter().position(|group| group.uuid == *uuid) {
+            Some(index) => Some(self.groups.remove(index)),
+            None => self.groups.iter_mut().filter_map(|group| group.remove_group(uuid)).next(),
+        }
+    }
+
+    pub fn entry_path(&self, uuid: &Uuid) -> Option<Vec<&Group>> {
+        if self.entries.iter().any(|entry| entry.uuid() == uuid) {
+            return Some(vec![self]);
+        }
+
+        self.groups.iter().filter_map(|group| group.entry_path(uuid)).next().map(|mut path| {
+            path.insert(0, self);
+            path
+        })
+    }
+
+    pub fn find(&self, title: &str) -> Option<&DatabaseEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.matches_title(title))
+            .or_else(|| self.groups.iter().filter_map(|group| group.find(title)).next())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Group;
+
+    use {DatabaseEntry, Uuid};
+
+    fn entry(title: &str) -> DatabaseEntry {
+        DatabaseEntry::new(Some(title.to_string()), None, None)
+    }
+
+    fn tree() -> Group {
+        let mut work = Group::new("Work");
+        work.add_entry(entry("GitHub"));
+
+        let mut internet = Group::new("Internet");
+        internet.add_entry(entry("Forum"));
+        internet.add_group(work);
+
+        let mut root = Group::new("Root");
+        root.add_entry(entry("Router"));
+        root.add_group(internet);
+        root.add_group(Group::new("Email"));
+        root
+    }
+
+    #[test]
+    fn should_find_group_by_path() {
+        let root = tree();
+
+        let group = root.find_group("Internet/Work");
+        assert!(group.is_some());
+        assert_eq!(group.unwrap().name(), "Work");
+
+        assert!(root.find_group("Internet/Home").is_none());
+        assert_eq!(root.find_group("").unwrap().name(), "Root");
+    }
+
+    #[test]
+    fn should_find_entry_by_path() {
+        let root = tree();
+
+        let entry = root.find_entry("Internet/Work/GitHub");
+        assert!(entry.is_some());
+        assert_eq!(entry.unwrap().title().as_ref().unwrap(), "GitHub");
+
+        assert!(root.find_entry("Router").is_some());
+        assert!(root.find_entry("Internet/GitHub").is_none());
+    }
+
+    #[test]
+    fn should_list_all_entries() {
+        let root = tree();
+
+        let titles: Vec<_> = root.all_entries()
+                                 .iter()
+                                 .map(|entry| entry.title().clone().unwrap())
+                                 .collect();
+
+        assert_eq!(titles, vec!["Router", "Forum", "GitHub"]);
+        assert!(root.find("GitHub").is_some());
+    }
+
+    #[test]
+    fn should_remove_entries_and_groups() {
+        let mut root = tree();
+        let entry = *root.find("GitHub").unwrap().uuid();
+        let group = *root.find_group("Internet").unwrap().uuid();
+
+        assert_eq!(root.all_groups().len(), 3);
+        assert!(root.group_by_uuid(&group).is_some());
+
+        let removed = root.remove_entry(&entry).unwrap();
+        assert_eq!(removed.title().as_ref().unwrap(), "GitHub");
+        assert!(root.find("GitHub").is_none());
+        assert!(root.remove_entry(&entry).is_none());
+
+        let removed = root.remove_group(&group).unwrap();
+        assert_eq!(removed.name(), "Internet");
+        assert_eq!(root.all_groups().len(), 1);
+        assert!(root.group_by_uuid(&group).is_none());
+    }
+
+    #[test]
+    fn should_find_entry_path() {
+        let root = tree();
+        let uuid = *root.find("GitHub").unwrap().uuid();
+
+        let names: Vec<_> = root.entry_path(&uuid)
+                                .unwrap()
+                                .iter()
+                                .map(|group| group.name())
+                                .collect();
+
+        assert_eq!(names, vec!["Root", "Internet", "Work"]);
+        assert!(root.entry_path(&Uuid::nil()).is_none());
+    }
+}
@@ -0,0 +1,337 @@
+use {Timestamp, Uuid, XmlElement};
+
+use super::entry::{NOTES, PASSWORD, TITLE, URL, USERNAME};
+
+const DEFAULT_HISTORY_MAX_ITEMS: i32 = 10;
+const DEFAULT_HISTORY_MAX_SIZE: i64 = 6 * 1024 * 1024;
+const DEFAULT_MAINTENANCE_HISTORY_DAYS: u32 = 365;
+const SECONDS_PER_DAY: i64 = 86400;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MemoryProtection {
+    pub protect_title: bool,
+    pub protect_username: bool,
+    pub protect_password: bool,
+    pub protect_url: bool,
+    pub protect_notes: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CustomIcon {
+    uuid: Uuid,
+    data: Vec<u8>,
+    name: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct Meta {
+    generator: Option<String>,
+    database_name: Option<String>,
+    database_name_changed: Option<Timestamp>,
+    database_description: Option<String>,
+    database_description_changed: Option<Timestamp>,
+    default_username: Option<String>,
+    default_username_changed: Option<Timestamp>,
+    maintenance_history_days: u32,
+    color: Option<String>,
+    master_key_changed: Option<Timestamp>,
+    master_key_change_recommended: i64,
+    master_key_change_forced: i64,
+    memory_protection: MemoryProtection,
+    custom_icons: Vec<CustomIcon>,
+    recycle_bin_enabled: bool,
+    recycle_bin_uuid: Option<Uuid>,
+    recycle_bin_changed: Option<Timestamp>,
+    history_max_items: i32,
+    history_max_size: i64,
+    unknown_elements: Vec<(Option<String>, XmlElement)>,
+}
+
+impl Default for MemoryProtection {
+    fn default() -> MemoryProtection {
+        MemoryProtection {
+            protect_title: false,
+            protect_username: false,
+            protect_password: true,
+            protect_url: false,
+            protect_notes: false,
+        }
+    }
+}
+
+impl MemoryProtection {
+    pub fn protects(&self, key: &str) -> bool {
+        match key {
+            TITLE => self.protect_title,
+            USERNAME => self.protect_username,
+            PASSWORD => self.protect_password,
+            URL => self.protect_url,
+            NOTES => self.protect_notes,
+            _ => false,
+        }
+    }
+}
+
+impl CustomIcon {
+    pub fn new(uuid: Uuid, data: Vec<u8>) -> CustomIcon {
+        CustomIcon {
+            uuid: uuid,
+            data: data,
+            name: None,
+        }
+    }
+
+    pub fn uuid(&self) -> &Uuid {
+        &self.uuid
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn name(&self) -> &Option<String> {
+        &self.name
+    }
+
+    pub fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+}
+
+impl Meta {
+    pub fn new() -> Meta {
+        Meta {
+            generator: None,
+            database_name: None,
+            database_name_changed: None,
+            database_description: None,
+            database_description_changed: None,
+            default_username: None,
+            default_username_changed: None,
+            maintenance_history_days: DEFAULT_MAINTENANCE_HISTORY_DAYS,
+            color: None,
+            master_key_changed: None,
+            master_key_change_recommended: -1,
+            master_key_change_forced: -1,
+            memory_protection: MemoryProtection::default(),
+            custom_icons: vec![],
+            recycle_bin_enabled: true,
+            recycle_bin_uuid: None,
+            recycle_bin_changed: None,
+            history_max_items: DEFAULT_HISTORY_MAX_ITEMS,
+            history_max_size: DEFAULT_HISTORY_MAX_SIZE,
+            unknown_elements: vec![],
+        }
+    }
+
+    pub fn generator(&self) -> &Option<String> {
+        &self.generator
+    }
+
+    pub fn set_generator(&mut self, generator: Option<String>) {
+        self.generator = generator;
+    }
+
+    pub fn database_name(&self) -> &Option<String> {
+        &self.database_name
+    }
+
+    pub fn set_database_name(&mut self, name: Option<String>) {
+        self.database_name = name;
+    }
+
+    pub fn database_name_changed(&self) -> Option<Timestamp> {
+        self.database_name_changed
+    }
+
+    pub fn set_database_name_changed(&mut self, time: Option<Timestamp>) {
+        self.database_name_changed = time;
+    }
+
+    pub fn database_description(&self) -> &Option<String> {
+        &self.database_description
+    }
+
+    pub fn set_database_description(&mut self, description: Option<String>) {
+        self.database_description = description;
+    }
+
+    pub fn database_description_changed(&self) -> Option<Timestamp> {
+        self.database_description_changed
+    }
+
+    pub fn set_database_description_changed(&mut self, time: Option<Timestamp>) {
+        self.database_description_changed = time;
+    }
+
+    pub fn default_username(&self) -> &Option<String> {
+        &self.default_username
+    }
+
+    pub fn set_default_username(&mut self, username: Option<String>) {
+        self.default_username = username;
+    }
+
+    pub fn default_username_changed(&self) -> Option<Timestamp> {
+        self.default_username_changed
+    }
+
+    pub fn set_default_username_changed(&mut self, time: Option<Timestamp>) {
+        self.default_username_changed = time;
+    }
+
+    pub fn maintenance_history_days(&self) -> u32 {
+        self.maintenance_history_days
+    }
+
+    pub fn set_maintenance_history_days(&mut self, days: u32) {
+        self.maintenance_history_days = days;
+    }
+
+    pub fn color(&self) -> &Option<String> {
+        &self.color
+    }
+
+    pub fn set_color(&mut self, color: Option<String>) {
+        self.color = color;
+    }
+
+    pub fn master_key_changed(&self) -> Option<Timestamp> {
+        self.master_key_changed
+    }
+
+    pub fn set_master_key_changed(&mut self, time: Option<Timestamp>) {
+        self.master_key_changed = time;
+    }
+
+    pub fn master_key_change_recommended(&self) -> i64 {
+        self.master_key_change_recommended
+    }
+
+    pub fn set_master_key_change_recommended(&mut self, days: i64) {
+        self.master_key_change_recommended = days;
+    }
+
+    pub fn master_key_change_forced(&self) -> i64 {
+        self.master_key_change_forced
+    }
+
+    pub fn set_master_key_change_forced(&mut self, days: i64) {
+        self.master_key_change_forced = days;
+    }
+
+    pub fn memory_protection(&self) -> &MemoryProtection {
+        &self.memory_protection
+    }
+
+    pub fn memory_protection_mut(&mut self) -> &mut MemoryProtection {
+        &mut self.memory_protection
+    }
+
+    pub fn custom_icons(&self) -> &[CustomIcon] {
+        &self.custom_icons
+    }
+
+    pub fn custom_icon(&self, uuid: &Uuid) -> Option<&CustomIcon> {
+        self.custom_icons.iter().find(|icon| icon.uuid == *uuid)
+    }
+
+    pub fn add_custom_icon(&mut self, icon: CustomIcon) {
+        self.custom_icons.push(icon);
+    }
+
+    pub fn recycle_bin_enabled(&self) -> bool {
+        self.recycle_bin_enabled
+    }
+
+    pub fn set_recycle_bin_enabled(&mut self, enabled: bool) {
+        self.recycle_bin_enabled = enabled;
+    }
+
+    pub fn recycle_bin_uuid(&self) -> Option<Uuid> {
+        self.recycle_bin_uuid
+    }
+
+    pub fn set_recycle_bin_uuid(&mut self, uuid: Option<Uuid>) {
+        self.recycle_bin_uuid = uuid;
+    }
+
+    pub fn recycle_bin_changed(&self) -> Option<Timestamp> {
+        self.recycle_bin_changed
+    }
+
+    pub fn set_recycle_bin_changed(&mut self, time: Option<Timestamp>) {
+        self.recycle_bin_changed = time;
+    }
+
+    pub fn history_max_items(&self) -> i32 {
+        self.history_max_items
+    }
+
+    pub fn set_history_max_items(&mut self, max_items: i32) {
+        self.history_max_items = max_items;
+    }
+
+    pub fn history_max_size(&self) -> i64 {
+        self.history_max_size
+    }
+
+    pub fn set_history_max_size(&mut self, max_size: i64) {
+        self.history_max_size = max_size;
+    }
+
+    pub fn unknown_elements(&self) -> Vec<&XmlElement> {
+        self.unknown_elements.iter().map(|&(_, ref element)| element).collect()
+    }
+
+    pub fn unknown_elements_after(&self, name: Option<&str>) -> Vec<&XmlElement> {
+        self.unknown_elements
+            .iter()
+            .filter(|&&(ref after, _)| after.as_ref().map(|after| &after[..]) == name)
+            .map(|&(_, ref element)| element)
+            .collect()
+    }
+
+    pub fn add_unknown_element(&mut self, element: XmlElement, after: Option<String>) {
+        self.unknown_elements.push((after, element));
+    }
+
+    pub fn is_master_key_change_recommended(&self, now: Timestamp) -> bool {
+        self.is_master_key_older_than(self.master_key_change_recommended, now)
+    }
+
+    pub fn is_master_key_change_forced(&self, now: Timestamp) -> bool {
+        self.is_master_key_older_than(self.master_key_change_forced, now)
+    }
+
+    fn is_master_key_older_than(&self, days: i64, now: Timestamp) -> bool {
+        if days < 0 {
+            return false;
+        }
+
+        self.master_key_changed.map_or(false, |changed| {
+            now.seconds() - changed.seconds() >= days * SECONDS_PER_DAY
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Meta;
+
+    use Timestamp;
+
+    #[test]
+    fn should_recommend_master_key_change() {
+        let mut meta = Meta::new();
+        meta.set_master_key_changed(Some(Timestamp::new(2015, 8, 30, 0, 0, 0)));
+
+        let now = Timestamp::new(2016, 8, 30, 0, 0, 0);
+        assert!(!meta.is_master_key_change_recommended(now));
+
+        meta.set_master_key_change_recommended(365);
+        meta.set_master_key_change_forced(400);
+        assert!(meta.is_master_key_change_recommended(now));
+        assert!(!meta.is_master_key_change_forced(now));
+    }
+}
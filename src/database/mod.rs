@@ -1,29 +1,422 @@
+mod auto_type;
+mod deleted_object;
 mod entry;
+mod group;
+mod meta;
+mod placeholder;
+mod reference;
+mod search;
+mod times;
+mod timestamp;
+mod uuid;
+mod xml_element;
 
-pub use self::entry::DatabaseEntry;
+use {CipherType, CompositeKey, Error};
+
+use std::path::Path;
+use std::vec;
+
+pub use self::auto_type::{Association, AutoType, KeyAction, Modifier, Obfuscation};
+pub use self::deleted_object::DeletedObject;
+pub use self::entry::{Attachment, DatabaseEntry, Field};
+pub use self::group::Group;
+pub use self::meta::{CustomIcon, MemoryProtection, Meta};
+pub use self::placeholder::{PlaceholderProvider, Placeholders};
+pub use self::search::{Search, SearchResult};
+pub use self::times::Times;
+pub use self::timestamp::Timestamp;
+pub use self::uuid::Uuid;
+pub use self::xml_element::{XmlElement, XmlNode};
+
+pub const KDBX_VERSION: u32 = 0x00030001;
+pub const DEFAULT_TRANSFORM_ROUNDS: u64 = 6000;
+
+const ROOT_GROUP_NAME: &'static str = "Root";
+const RECYCLE_BIN_NAME: &'static str = "Recycle Bin";
+const RECYCLE_BIN_ICON_ID: u32 = 43;
 
 #[derive(Debug)]
 pub struct Database {
-    entries: Vec<DatabaseEntry>,
+    version: u32,
+    cipher: CipherType,
+    transform_rounds: u64,
+    meta: Meta,
+    root: Group,
+    deleted_objects: Vec<DeletedObject>,
 }
 
 impl Database {
     pub fn new() -> Database {
-        Database { entries: vec![] }
+        Database::with_root(Group::new(ROOT_GROUP_NAME))
+    }
+
+    pub fn with_root(root: Group) -> Database {
+        Database {
+            version: KDBX_VERSION,
+            cipher: CipherType::Aes,
+            transform_rounds: DEFAULT_TRANSFORM_ROUNDS,
+            meta: Meta::new(),
+            root: root,
+            deleted_objects: vec![],
+        }
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn set_version(&mut self, version: u32) {
+        self.version = version;
+    }
+
+    pub fn cipher(&self) -> CipherType {
+        self.cipher
+    }
+
+    pub fn set_cipher(&mut self, cipher: CipherType) {
+        self.cipher = cipher;
+    }
+
+    pub fn transform_rounds(&self) -> u64 {
+        self.transform_rounds
+    }
+
+    pub fn set_transform_rounds(&mut self, transform_rounds: u64) {
+        self.transform_rounds = transform_rounds;
+    }
+
+    pub fn meta(&self) -> &Meta {
+        &self.meta
+    }
+
+    pub fn meta_mut(&mut self) -> &mut Meta {
+        &mut self.meta
+    }
+
+    pub fn root(&self) -> &Group {
+        &self.root
+    }
+
+    pub fn root_mut(&mut self) -> &mut Group {
+        &mut self.root
+    }
+
+    pub fn deleted_objects(&self) -> &[DeletedObject] {
+        &self.deleted_objects
+    }
+
+    pub fn add_deleted_object(&mut self, deleted_object: DeletedObject) {
+        self.deleted_objects.push(deleted_object);
+    }
+
+    pub fn recycle_bin(&self) -> Option<&Group> {
+        self.meta.recycle_bin_uuid().and_then(|uuid| self.root.group_by_uuid(&uuid))
+    }
+
+    pub fn delete_entry(&mut self, uuid: &Uuid) -> Result<(), Error> {
+        let now = Timestamp::now();
+        let recycled = self.recycle_bin().map_or(false, |bin| bin.entry_path(uuid).is_some());
+        let mut entry = try!(self.root.remove_entry(uuid).ok_or(Error::MissingEntry(*uuid)));
+
+        if self.meta.recycle_bin_enabled() && !recycled {
+            entry.times_mut().set_location_changed(Some(now));
+            self.recycle_bin_mut(now).add_entry(entry);
+        } else {
+            self.deleted_objects.push(DeletedObject::new(*uuid, Some(now)));
+        }
+
+        Ok(())
     }
 
-    pub fn add(&mut self, entry: DatabaseEntry) {
-        self.entries.push(entry);
+    pub fn delete_group(&mut self, uuid: &Uuid) -> Result<(), Error> {
+        if self.root.uuid() == uuid {
+            return Err(Error::CannotDeleteRootGroup);
+        }
+
+        let now = Timestamp::now();
+        let recycled = self.recycle_bin().map_or(false, |bin| bin.group_by_uuid(uuid).is_some());
+        let mut group = try!(self.root.remove_group(uuid).ok_or(Error::MissingGroup(*uuid)));
+
+        if self.meta.recycle_bin_enabled() && !recycled {
+            group.times_mut().set_location_changed(Some(now));
+            self.recycle_bin_mut(now).add_group(group);
+        } else {
+            let mut uuids = descendants(&group);
+            uuids.insert(0, *group.uuid());
+            self.add_tombstones(uuids, now);
+        }
+
+        Ok(())
+    }
+
+    pub fn empty_recycle_bin(&mut self) {
+        let uuids = match self.recycle_bin() {
+            Some(bin) => descendants(bin),
+            None => return,
+        };
+        self.add_tombstones(uuids, Timestamp::now());
+
+        let uuid = self.meta.recycle_bin_uuid().unwrap();
+        self.root.group_by_uuid_mut(&uuid).unwrap().clear();
+    }
+
+    pub fn add(&mut self, mut entry: DatabaseEntry) {
+        if entry.uuid().is_nil() {
+            entry.set_uuid(Uuid::new_v4());
+        }
+        self.root.add_entry(entry);
+    }
+
+    pub fn add_entry(&mut self, parent: &Uuid, mut entry: DatabaseEntry) -> Result<Uuid, Error> {
+        if entry.uuid().is_nil() {
+            entry.set_uuid(Uuid::new_v4());
+        }
+        touch_created(entry.times_mut(), Timestamp::now());
+
+        let uuid = *entry.uuid();
+        let group = try!(self.root.group_by_uuid_mut(parent).ok_or(Error::MissingGroup(*parent)));
+        group.add_entry(entry);
+
+        Ok(uuid)
+    }
+
+    pub fn add_group(&mut self, parent: &Uuid, mut group: Group) -> Result<Uuid, Error> {
+        touch_created(group.times_mut(), Timestamp::now());
+
+        let uuid = *group.uuid();
+        let parent = try!(self.root.group_by_uuid_mut(parent).ok_or(Error::MissingGroup(*parent)));
+        parent.add_group(group);
+
+        Ok(uuid)
+    }
+
+    pub fn update_entry<F>(&mut self, uuid: &Uuid, update: F) -> Result<(), Error>
+        where F: FnOnce(&mut DatabaseEntry)
+    {
+        let max_items = self.meta.history_max_items();
+        let max_size = self.meta.history_max_size();
+        let entry = try!(self.entry_by_uuid_mut(uuid).ok_or(Error::MissingEntry(*uuid)));
+
+        entry.create_backup();
+        update(entry);
+        entry.set_uuid(*uuid);
+        touch_modified(entry.times_mut(), Timestamp::now());
+        entry.trim_history(max_items, max_size);
+
+        Ok(())
+    }
+
+    pub fn update_group<F>(&mut self, uuid: &Uuid, update: F) -> Result<(), Error>
+        where F: FnOnce(&mut Group)
+    {
+        let group = try!(self.root.group_by_uuid_mut(uuid).ok_or(Error::MissingGroup(*uuid)));
+
+        update(group);
+        group.set_uuid(*uuid);
+        touch_modified(group.times_mut(), Timestamp::now());
+
+        Ok(())
+    }
+
+    pub fn move_entry(&mut self, uuid: &Uuid, parent: &Uuid) -> Result<(), Error> {
+        if self.root.group_by_uuid(parent).is_none() {
+            return Err(Error::MissingGroup(*parent));
+        }
+
+        let mut entry = try!(self.root.remove_entry(uuid).ok_or(Error::MissingEntry(*uuid)));
+        entry.times_mut().set_location_changed(Some(Timestamp::now()));
+        self.root.group_by_uuid_mut(parent).unwrap().add_entry(entry);
+
+        Ok(())
+    }
+
+    pub fn move_group(&mut self, uuid: &Uuid, parent: &Uuid) -> Result<(), Error> {
+        if self.root.uuid() == uuid {
+            return Err(Error::InvalidGroupMove(*uuid));
+        }
+        if self.root.group_by_uuid(parent).is_none() {
+            return Err(Error::MissingGroup(*parent));
+        }
+
+        match self.root.group_by_uuid(uuid) {
+            Some(group) if group.group_by_uuid(parent).is_some() => {
+                return Err(Error::InvalidGroupMove(*uuid))
+            }
+            Some(_) => {}
+            None => return Err(Error::MissingGroup(*uuid)),
+        }
+
+        let mut group = self.root.remove_group(uuid).unwrap();
+        group.times_mut().set_location_changed(Some(Timestamp::now()));
+        self.root.group_by_uuid_mut(parent).unwrap().add_group(group);
+
+        Ok(())
+    }
+
+    pub fn entries(&self) -> Vec<&DatabaseEntry> {
+        self.root.all_entries()
+    }
+
+    pub fn expired_entries(&self, now: Timestamp) -> Vec<&DatabaseEntry> {
+        self.entries().into_iter().filter(|entry| entry.times().is_expired(now)).collect()
+    }
+
+    pub fn trim_history(&mut self) {
+        let max_items = self.meta.history_max_items();
+        let max_size = self.meta.history_max_size();
+
+        for entry in self.root.all_entries_mut() {
+            entry.trim_history(max_items, max_size);
+        }
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P, key: &CompositeKey) -> Result<(), Error> {
+        ::write(path, self, key)
     }
 
     pub fn find(&self, title: &str) -> Option<&DatabaseEntry> {
-        self.entries.iter().find(|entry| entry.matches_title(title))
+        self.root.find(title)
+    }
+
+    pub fn search<'a>(&'a self, search: &Search) -> vec::IntoIter<SearchResult<'a>> {
+        search::search(self, search).into_iter()
+    }
+
+    pub fn auto_type_enabled(&self, entry: &DatabaseEntry) -> bool {
+        let path = self.root.entry_path(entry.uuid()).unwrap_or_default();
+        let inherited = path.iter().rev().filter_map(|group| group.enable_auto_type()).next();
+
+        entry.auto_type().enabled() && inherited.unwrap_or(true)
+    }
+
+    pub fn auto_type_sequence(&self,
+                              entry: &DatabaseEntry,
+                              window: Option<&str>)
+                              -> Option<String> {
+        if !self.auto_type_enabled(entry) {
+            return None;
+        }
+
+        let association = window.and_then(|title| entry.auto_type().association(title))
+                                .and_then(|association| association.sequence().clone());
+        let path = self.root.entry_path(entry.uuid()).unwrap_or_default();
+        let inherited = path.iter()
+                            .rev()
+                            .filter_map(|group| group.default_auto_type_sequence().clone());
+
+        let sequence = association.into_iter()
+                                  .chain(entry.auto_type().default_sequence().clone())
+                                  .chain(inherited)
+                                  .find(|sequence| !sequence.is_empty());
+        Some(sequence.unwrap_or_else(|| auto_type::DEFAULT_SEQUENCE.to_string()))
+    }
+
+    pub fn entries_with_tag(&self, tag: &str) -> Vec<&DatabaseEntry> {
+        self.entries().into_iter().filter(|entry| entry.has_tag(tag)).collect()
+    }
+
+    pub fn entry_by_uuid(&self, uuid: &Uuid) -> Option<&DatabaseEntry> {
+        self.entries().into_iter().find(|entry| entry.uuid() == uuid)
+    }
+
+    pub fn resolve_references(&self, value: &str) -> Result<String, Error> {
+        reference::resolve(self, value, &mut vec![])
+    }
+
+    pub fn resolved_field(&self,
+                          entry: &DatabaseEntry,
+                          key: &str)
+                          -> Result<Option<String>, Error> {
+        reference::resolve_field(self, entry, key, &mut vec![])
+    }
+
+    pub fn resolved_title(&self, entry: &DatabaseEntry) -> Result<Option<String>, Error> {
+        self.resolved_field(entry, entry::TITLE)
+    }
+
+    pub fn resolved_username(&self, entry: &DatabaseEntry) -> Result<Option<String>, Error> {
+        self.resolved_field(entry, entry::USERNAME)
+    }
+
+    pub fn resolved_password(&self, entry: &DatabaseEntry) -> Result<Option<String>, Error> {
+        self.resolved_field(entry, entry::PASSWORD)
+    }
+
+    pub fn resolved_url(&self, entry: &DatabaseEntry) -> Result<Option<String>, Error> {
+        self.resolved_field(entry, entry::URL)
+    }
+
+    pub fn resolved_notes(&self, entry: &DatabaseEntry) -> Result<Option<String>, Error> {
+        self.resolved_field(entry, entry::NOTES)
+    }
+
+    pub fn entry_by_uuid_mut(&mut self, uuid: &Uuid) -> Option<&mut DatabaseEntry> {
+        self.root.all_entries_mut().into_iter().find(|entry| entry.uuid() == uuid)
+    }
+
+    pub fn group_by_uuid(&self, uuid: &Uuid) -> Option<&Group> {
+        self.root.group_by_uuid(uuid)
+    }
+
+    pub fn group_by_uuid_mut(&mut self, uuid: &Uuid) -> Option<&mut Group> {
+        self.root.group_by_uuid_mut(uuid)
+    }
+
+    pub fn find_group(&self, path: &str) -> Option<&Group> {
+        self.root.find_group(path)
+    }
+
+    pub fn find_entry(&self, path: &str) -> Option<&DatabaseEntry> {
+        self.root.find_entry(path)
+    }
+
+    fn recycle_bin_mut(&mut self, now: Timestamp) -> &mut Group {
+        if self.recycle_bin().is_none() {
+            let mut bin = Group::new(RECYCLE_BIN_NAME);
+            bin.set_icon_id(Some(RECYCLE_BIN_ICON_ID));
+            bin.set_enable_auto_type(Some(false));
+            bin.set_enable_searching(Some(false));
+            bin.times_mut().set_creation_time(Some(now));
+
+            self.meta.set_recycle_bin_uuid(Some(*bin.uuid()));
+            self.meta.set_recycle_bin_changed(Some(now));
+            self.root.add_group(bin);
+        }
+
+        let uuid = self.meta.recycle_bin_uuid().unwrap();
+        self.root.group_by_uuid_mut(&uuid).unwrap()
+    }
+
+    fn add_tombstones(&mut self, uuids: Vec<Uuid>, now: Timestamp) {
+        for uuid in uuids {
+            self.deleted_objects.push(DeletedObject::new(uuid, Some(now)));
+        }
     }
 }
 
+fn touch_created(times: &mut Times, now: Timestamp) {
+    if times.creation_time().is_none() {
+        times.set_creation_time(Some(now));
+    }
+    times.set_location_changed(Some(now));
+    touch_modified(times, now);
+}
+
+fn touch_modified(times: &mut Times, now: Timestamp) {
+    times.set_last_modification_time(Some(now));
+    times.set_last_access_time(Some(now));
+}
+
+fn descendants(group: &Group) -> Vec<Uuid> {
+    let groups = group.all_groups().into_iter().map(|group| *group.uuid());
+    let entries = group.all_entries().into_iter().map(|entry| *entry.uuid());
+
+    groups.chain(entries).collect()
+}
+
 #[cfg(test)]
 mod tests {
-    use {Database, DatabaseEntry};
+    use {Association, Database, DatabaseEntry, Error, Group, Timestamp, Uuid};
 
     #[test]
     fn should_create_and_find_entry() {
@@ -46,4 +439,251 @@ mod tests {
         assert_eq!(entry.unwrap().username().as_ref().unwrap(), "dick");
         assert_eq!(entry.unwrap().password().as_ref().unwrap(), "hunter2");
     }
+
+    #[test]
+    fn should_trim_history_to_meta_limits() {
+        let mut entry = DatabaseEntry::new(Some("GitHub".to_string()), None, None);
+        for _ in 0..5 {
+            entry.create_backup();
+        }
+
+        let mut database = Database::new();
+        database.add(entry);
+        database.meta_mut().set_history_max_items(3);
+        database.trim_history();
+
+        assert_eq!(database.find("GitHub").unwrap().history().len(), 3);
+    }
+
+    #[test]
+    fn should_find_expired_entries() {
+        let mut expired = DatabaseEntry::new(Some("Expired".to_string()), None, None);
+        expired.times_mut().set_expires(true);
+        expired.times_mut().set_expiry_time(Some(Timestamp::new(2016, 1, 1, 0, 0, 0)));
+
+        let mut current = DatabaseEntry::new(Some("Current".to_string()), None, None);
+        current.times_mut().set_expires(true);
+        current.times_mut().set_expiry_time(Some(Timestamp::new(2017, 1, 1, 0, 0, 0)));
+
+        let mut database = Database::new();
+        database.add(expired);
+        database.add(current);
+        database.add(DatabaseEntry::new(Some("Never".to_string()), None, None));
+
+        let result = database.expired_entries(Timestamp::new(2016, 8, 30, 0, 0, 0));
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].title().as_ref().unwrap(), "Expired");
+    }
+
+    #[test]
+    fn should_find_entry_by_uuid() {
+        let mut database = Database::new();
+        database.add(DatabaseEntry::new(Some("GitHub".to_string()), None, None));
+        database.add(DatabaseEntry::new(Some("GitHub".to_string()), None, None));
+
+        let uuid = *database.entries()[1].uuid();
+        assert!(uuid != *database.entries()[0].uuid());
+
+        let entry = database.entry_by_uuid(&uuid).unwrap();
+        assert_eq!(entry.uuid(), &uuid);
+        assert!(database.entry_by_uuid(&Uuid::nil()).is_none());
+    }
+
+    #[test]
+    fn should_find_entries_with_tag() {
+        let mut api = DatabaseEntry::new(Some("API".to_string()), None, None);
+        api.set_tags(vec!["prod".to_string(), "backend".to_string()]);
+
+        let mut staging = DatabaseEntry::new(Some("Staging".to_string()), None, None);
+        staging.add_tag("staging");
+
+        let mut web = DatabaseEntry::new(Some("Web".to_string()), None, None);
+        web.add_tag("Prod");
+
+        let mut frontend = Group::new("Frontend");
+        frontend.add_entry(web);
+
+        let mut database = Database::new();
+        database.add(api);
+        database.add(staging);
+        database.root_mut().add_group(frontend);
+
+        let titles: Vec<_> = database.entries_with_tag("prod")
+                                     .iter()
+                                     .map(|entry| entry.title().clone().unwrap())
+                                     .collect();
+        assert_eq!(titles, vec!["API", "Web"]);
+    }
+
+    #[test]
+    fn should_inherit_auto_type_from_groups() {
+        let mut browser = DatabaseEntry::new(Some("GitHub".to_string()), None, None);
+        let association = Association::new("*Firefox", Some("{PASSWORD}{ENTER}".to_string()));
+        browser.auto_type_mut().add_association(association);
+        let browser_uuid = *browser.uuid();
+
+        let mut work = Group::new("Work");
+        work.set_default_auto_type_sequence(Some("{USERNAME}{ENTER}".to_string()));
+        work.add_entry(browser);
+
+        let mut archive = Group::new("Archive");
+        archive.set_enable_auto_type(Some(false));
+        archive.add_entry(DatabaseEntry::new(Some("Old".to_string()), None, None));
+
+        let mut database = Database::new();
+        database.add(DatabaseEntry::new(Some("Router".to_string()), None, None));
+        database.root_mut().add_group(work);
+        database.root_mut().add_group(archive);
+
+        let entry = database.entry_by_uuid(&browser_uuid).unwrap();
+        assert_eq!(database.auto_type_sequence(entry, Some("GitHub - Mozilla Firefox")).unwrap(),
+                   "{PASSWORD}{ENTER}");
+        assert_eq!(database.auto_type_sequence(entry, None).unwrap(), "{USERNAME}{ENTER}");
+
+        let entry = database.find("Router").unwrap();
+        assert_eq!(database.auto_type_sequence(entry, None).unwrap(),
+                   "{USERNAME}{TAB}{PASSWORD}{ENTER}");
+
+        let entry = database.find("Old").unwrap();
+        assert!(!database.auto_type_enabled(entry));
+        assert!(database.auto_type_sequence(entry, None).is_none());
+    }
+
+    #[test]
+    fn should_move_deleted_entries_to_recycle_bin() {
+        let mut database = Database::new();
+        database.add(DatabaseEntry::new(Some("GitHub".to_string()), None, None));
+        let uuid = *database.find("GitHub").unwrap().uuid();
+
+        assert!(database.recycle_bin().is_none());
+        database.delete_entry(&uuid).unwrap();
+
+        let bin = database.recycle_bin().unwrap();
+        assert_eq!(bin.name(), "Recycle Bin");
+        assert_eq!(bin.entries()[0].uuid(), &uuid);
+        assert!(bin.entries()[0].times().location_changed().is_some());
+        assert!(database.root().entries().is_empty());
+        assert!(database.deleted_objects().is_empty());
+
+        database.delete_entry(&uuid).unwrap();
+        assert!(database.entry_by_uuid(&uuid).is_none());
+        assert_eq!(database.deleted_objects()[0].uuid(), &uuid);
+    }
+
+    #[test]
+    fn should_delete_groups_permanently_if_recycle_bin_disabled() {
+        let mut work = Group::new("Work");
+        work.add_entry(DatabaseEntry::new(Some("GitHub".to_string()), None, None));
+        work.add_group(Group::new("Servers"));
+        let uuid = *work.uuid();
+
+        let mut database = Database::new();
+        database.meta_mut().set_recycle_bin_enabled(false);
+        database.root_mut().add_group(work);
+
+        database.delete_group(&uuid).unwrap();
+
+        assert!(database.recycle_bin().is_none());
+        assert!(database.root().groups().is_empty());
+        assert_eq!(database.deleted_objects().len(), 3);
+        assert_eq!(database.deleted_objects()[0].uuid(), &uuid);
+    }
+
+    #[test]
+    fn should_empty_recycle_bin() {
+        let mut database = Database::new();
+        database.root_mut().add_group(Group::new("Work"));
+        database.add(DatabaseEntry::new(Some("GitHub".to_string()), None, None));
+
+        let group = *database.find_group("Work").unwrap().uuid();
+        let entry = *database.find("GitHub").unwrap().uuid();
+        database.delete_group(&group).unwrap();
+        database.delete_entry(&entry).unwrap();
+        assert!(database.deleted_objects().is_empty());
+
+        database.empty_recycle_bin();
+
+        let bin = database.recycle_bin().unwrap();
+        assert!(bin.groups().is_empty() && bin.entries().is_empty());
+        let deleted: Vec<_> = database.deleted_objects().iter().map(|o| *o.uuid()).collect();
+        assert_eq!(deleted, vec![group, entry]);
+    }
+
+    #[test]
+    fn should_return_error_if_deleting_unknown_or_root_group() {
+        let mut database = Database::new();
+
+        let result = database.delete_entry(&Uuid::nil());
+        match result {
+            Err(Error::MissingEntry(uuid)) if uuid.is_nil() => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+
+        let root = *database.root().uuid();
+        let result = database.delete_group(&root);
+        match result {
+            Err(Error::CannotDeleteRootGroup) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
+    #[test]
+    fn should_add_and_update_entries() {
+        let mut database = Database::new();
+        let root = *database.root().uuid();
+        let group = database.add_group(&root, Group::new("Email")).unwrap();
+
+        let entry = DatabaseEntry::new(Some("Gmail".to_string()), None, None);
+        let uuid = database.add_entry(&group, entry).unwrap();
+
+        database.update_entry(&uuid, |entry| entry.set_password(Some("hunter2".to_string())))
+                .unwrap();
+
+        let entry = database.entry_by_uuid(&uuid).unwrap();
+        assert_eq!(entry.password(), &Some("hunter2".to_string()));
+        assert_eq!(entry.history().len(), 1);
+        assert_eq!(entry.history()[0].password(), &None);
+        assert!(entry.times().creation_time().is_some());
+        assert!(entry.times().last_modification_time() >= entry.times().creation_time());
+        assert!(database.group_by_uuid(&group).unwrap().times().creation_time().is_some());
+    }
+
+    #[test]
+    fn should_move_entries_and_groups() {
+        let mut database = Database::new();
+        let root = *database.root().uuid();
+        let email = database.add_group(&root, Group::new("Email")).unwrap();
+        let archive = database.add_group(&root, Group::new("Archive")).unwrap();
+        let uuid = database.add_entry(&root, DatabaseEntry::new(None, None, None)).unwrap();
+        database.entry_by_uuid_mut(&uuid).unwrap().times_mut().set_location_changed(None);
+
+        database.move_entry(&uuid, &email).unwrap();
+        database.move_group(&email, &archive).unwrap();
+
+        let path = database.root().entry_path(&uuid).unwrap();
+        let names: Vec<&str> = path.iter().map(|group| group.name()).collect();
+        assert_eq!(names, vec!["Root", "Archive", "Email"]);
+        assert!(database.entry_by_uuid(&uuid).unwrap().times().location_changed().is_some());
+    }
+
+    #[test]
+    fn should_return_error_if_moving_group_into_itself() {
+        let mut database = Database::new();
+        let root = *database.root().uuid();
+        let parent = database.add_group(&root, Group::new("Parent")).unwrap();
+        let child = database.add_group(&parent, Group::new("Child")).unwrap();
+
+        let result = database.move_group(&parent, &child);
+        match result {
+            Err(Error::InvalidGroupMove(uuid)) if uuid == parent => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+
+        let result = database.move_entry(&Uuid::nil(), &Uuid::new_v4());
+        match result {
+            Err(Error::MissingGroup(_)) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+        assert!(database.group_by_uuid(&child).is_some());
+    }
 }
@@ -0,0 +1,205 @@
+use {Database, DatabaseEntry, Error, Uuid};
+
+use super::entry::{NOTES, PASSWORD, TITLE, URL, USERNAME};
+
+const PREFIX: &'static str = "{REF:";
+const MAX_DEPTH: usize = 12;
+
+pub fn resolve(database: &Database,
+               value: &str,
+               visited: &mut Vec<(Uuid, String)>)
+               -> Result<String, Error> {
+    let mut result = String::new();
+    let mut rest = value;
+
+    while let Some(start) = rest.to_ascii_uppercase().find(PREFIX) {
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end + 1,
+            None => break,
+        };
+
+        let reference = &rest[start..end];
+        result.push_str(&rest[..start]);
+        match try!(resolve_reference(database, reference, visited)) {
+            Some(value) => result.push_str(&value),
+            None => result.push_str(reference),
+        }
+        rest = &rest[end..];
+    }
+
+    result.push_str(rest);
+    Ok(result)
+}
+
+pub fn resolve_field(database: &Database,
+                     entry: &DatabaseEntry,
+                     key: &str,
+                     visited: &mut Vec<(Uuid, String)>)
+                     -> Result<Option<String>, Error> {
+    let value = match entry.field(key).and_then(|field| field.value().as_ref()) {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+
+    visited.push((*entry.uuid(), key.to_string()));
+    let result = resolve(database, value, visited);
+    visited.pop();
+
+    result.map(Some)
+}
+
+fn resolve_reference(database: &Database,
+                     reference: &str,
+                     visited: &mut Vec<(Uuid, String)>)
+                     -> Result<Option<String>, Error> {
+    let code = &reference[PREFIX.len()..reference.len() - 1];
+    let bytes = code.as_bytes();
+    if bytes.len() < 4 || bytes[1] != b'@' || bytes[3] != b':' {
+        return Ok(None);
+    }
+
+    let wanted = (bytes[0] as char).to_ascii_uppercase();
+    let search_in = (bytes[2] as char).to_ascii_uppercase();
+    let entry = match find_entry(database, search_in, &code[4..]) {
+        Some(entry) => entry,
+        None => return Ok(None),
+    };
+
+    let key = match (wanted, field_key(wanted)) {
+        ('I', _) => return Ok(Some(entry.uuid().to_hex())),
+        (_, Some(key)) => key,
+        (_, None) => return Ok(None),
+    };
+
+    let target = (*entry.uuid(), key.to_string());
+    if visited.contains(&target) {
+        return Err(Error::CyclicFieldReference(reference.to_string()));
+    }
+    if visited.len() > MAX_DEPTH {
+        return Err(Error::FieldReferenceTooDeep(reference.to_string()));
+    }
+
+    let value = try!(resolve_field(database, entry, key, visited));
+    Ok(Some(value.unwrap_or_default()))
+}
+
+fn find_entry<'a>(database: &'a Database,
+                  search_in: char,
+                  text: &str)
+                  -> Option<&'a DatabaseEntry> {
+    let text = text.to_lowercase();
+    let contains = |value: &Option<String>| {
+        value.as_ref().map_or(false, |value| value.to_lowercase().contains(&text))
+    };
+
+    database.entries().into_iter().find(|entry| {
+        match search_in {
+            'I' => entry.uuid().to_hex().eq_ignore_ascii_case(text.trim()),
+            'O' => {
+                entry.fields()
+                     .iter()
+                     .any(|field| !field.is_standard() && contains(field.value()))
+            }
+            _ => {
+                field_key(search_in)
+                    .and_then(|key| entry.field(key))
+                    .map_or(false, |field| contains(field.value()))
+            }
+        }
+    })
+}
+
+fn field_key(code: char) -> Option<&'static str> {
+    match code {
+        'T' => Some(TITLE),
+        'U' => Some(USERNAME),
+        'P' => Some(PASSWORD),
+        'A' => Some(URL),
+        'N' => Some(NOTES),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Database, DatabaseEntry, Error};
+
+    fn entry(title: &str, username: &str, password: &str) -> DatabaseEntry {
+        DatabaseEntry::new(Some(title.to_string()),
+                           Some(username.to_string()),
+                           Some(password.to_string()))
+    }
+
+    #[test]
+    fn should_resolve_references() {
+        let mut database = Database::new();
+        let mut github = entry("GitHub", "joe.bloggs", "hunter2");
+        github.set_field("Recovery", Some("Codes in safe".to_string()), false);
+        let uuid = *github.uuid();
+        database.add(github);
+        let password = format!("{{REF:P@I:{}}}", uuid.to_hex());
+        database.add(entry("Mirror", "{REF:U@T:github}", &password));
+
+        let mirror = database.find("Mirror").unwrap();
+        assert_eq!(database.resolved_username(mirror).unwrap().unwrap(), "joe.bloggs");
+        assert_eq!(database.resolved_password(mirror).unwrap().unwrap(), "hunter2");
+
+        let result = database.resolve_references("id={ref:i@o:in safe}, {REF:T@U:bloggs}!");
+        assert_eq!(result.unwrap(), format!("id={}, GitHub!", uuid.to_hex()));
+    }
+
+    #[test]
+    fn should_resolve_nested_references() {
+        let mut database = Database::new();
+        database.add(entry("First", "joe.bloggs", "hunter2"));
+        database.add(entry("Second", "{REF:U@T:First}", "{REF:P@T:First}"));
+        database.add(entry("Third", "{REF:U@T:Second}", "prefix-{REF:P@T:Second}"));
+
+        let third = database.find("Third").unwrap();
+        assert_eq!(database.resolved_username(third).unwrap().unwrap(), "joe.bloggs");
+        assert_eq!(database.resolved_password(third).unwrap().unwrap(), "prefix-hunter2");
+    }
+
+    #[test]
+    fn should_keep_unresolvable_references() {
+        let mut database = Database::new();
+        database.add(entry("GitHub", "joe.bloggs", "hunter2"));
+
+        for value in &["{REF:P@T:GitLab}", "{REF:X@T:GitHub}", "{REF:P@X:GitHub}", "{REF:P}",
+                       "{REF:P@T:GitHub", "{REF:P@I:0011}"] {
+            assert_eq!(&database.resolve_references(value).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn should_return_error_if_cyclic_reference() {
+        let mut database = Database::new();
+        database.add(entry("First", "joe.bloggs", "{REF:P@T:Second}"));
+        database.add(entry("Second", "joe.bloggs", "{REF:P@T:First}"));
+
+        let result = database.resolved_password(database.find("First").unwrap());
+        match result {
+            Err(Error::CyclicFieldReference(ref reference)) if reference == "{REF:P@T:First}" => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
+    #[test]
+    fn should_return_error_if_references_too_deep() {
+        let mut database = Database::new();
+        database.add(entry("Entry 0", "joe.bloggs", "hunter2"));
+        for i in 1..20 {
+            let password = format!("{{REF:P@T:Entry {}}}", i - 1);
+            database.add(entry(&format!("Entry {}", i), "joe.bloggs", &password));
+        }
+
+        let result = database.resolved_password(database.find("Entry 12").unwrap());
+        assert_eq!(result.unwrap().unwrap(), "hunter2");
+
+        let result = database.resolved_password(database.find("Entry 19").unwrap());
+        match result {
+            Err(Error::FieldReferenceTooDeep(_)) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+}
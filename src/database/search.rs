@@ -0,0 +1,304 @@
+use {Database, DatabaseEntry, Error, Group, Timestamp, Uuid};
+
+use super::entry::PASSWORD;
+use super::group::PATH_SEPARATOR;
+
+use regex::Regex;
+
+pub struct Search {
+    text: Option<String>,
+    regex: Option<Regex>,
+    fields: Option<Vec<String>>,
+    tags: Vec<String>,
+    group_path: Vec<String>,
+    exclude_recycle_bin: bool,
+    expired: Option<bool>,
+    expires_before: Option<Timestamp>,
+    now: Option<Timestamp>,
+}
+
+pub struct SearchResult<'a> {
+    entry: &'a DatabaseEntry,
+    groups: Vec<&'a Group>,
+}
+
+impl Search {
+    pub fn new() -> Search {
+        Search {
+            text: None,
+            regex: None,
+            fields: None,
+            tags: vec![],
+            group_path: vec![],
+            exclude_recycle_bin: false,
+            expired: None,
+            expires_before: None,
+            now: None,
+        }
+    }
+
+    pub fn text(mut self, text: &str) -> Search {
+        self.text = Some(text.to_lowercase());
+        self
+    }
+
+    pub fn regex(mut self, pattern: &str) -> Result<Search, Error> {
+        self.regex = Some(try!(Regex::new(pattern).map_err(|e| Error::Regex(e))));
+        Ok(self)
+    }
+
+    pub fn fields(mut self, fields: &[&str]) -> Search {
+        self.fields = Some(fields.iter().map(|field| field.to_string()).collect());
+        self
+    }
+
+    pub fn tag(mut self, tag: &str) -> Search {
+        self.tags.push(tag.to_string());
+        self
+    }
+
+    pub fn group(mut self, path: &str) -> Search {
+        self.group_path = path.split(PATH_SEPARATOR)
+                              .filter(|name| !name.is_empty())
+                              .map(|name| name.to_string())
+                              .collect();
+        self
+    }
+
+    pub fn exclude_recycle_bin(mut self) -> Search {
+        self.exclude_recycle_bin = true;
+        self
+    }
+
+    pub fn expired(mut self, expired: bool) -> Search {
+        self.expired = Some(expired);
+        self
+    }
+
+    pub fn expires_before(mut self, time: Timestamp) -> Search {
+        self.expires_before = Some(time);
+        self
+    }
+
+    pub fn now(mut self, now: Timestamp) -> Search {
+        self.now = Some(now);
+        self
+    }
+
+    pub fn matches(&self, entry: &DatabaseEntry) -> bool {
+        self.matches_fields(entry) && self.tags.iter().all(|tag| entry.has_tag(tag)) &&
+        self.matches_expiry(entry)
+    }
+
+    fn matches_fields(&self, entry: &DatabaseEntry) -> bool {
+        let values: Vec<&String> = entry.fields()
+                                        .iter()
+                                        .filter(|field| self.includes(field.key()))
+                                        .filter_map(|field| field.value().as_ref())
+                                        .collect();
+
+        let text = self.text.as_ref().map_or(true, |text| {
+            values.iter().any(|value| value.to_lowercase().contains(text))
+        });
+        let regex = self.regex
+                        .as_ref()
+                        .map_or(true, |regex| values.iter().any(|value| regex.is_match(value)));
+
+        text && regex
+    }
+
+    fn matches_expiry(&self, entry: &DatabaseEntry) -> bool {
+        let times = entry.times();
+        let now = self.now.unwrap_or_else(Timestamp::now);
+
+        let expired = self.expired.map_or(true, |expired| times.is_expired(now) == expired);
+        let expires_before = self.expires_before.map_or(true, |before| {
+            times.expires() && times.expiry_time().map_or(false, |time| time < before)
+        });
+
+        expired && expires_before
+    }
+
+    fn includes(&self, key: &str) -> bool {
+        match self.fields {
+            Some(ref fields) => fields.iter().any(|field| field == key),
+            None => key != PASSWORD,
+        }
+    }
+
+    fn includes_path(&self, path: &[&Group]) -> bool {
+        let names = path.iter().skip(1).map(|group| group.name());
+        self.group_path.len() < path.len() &&
+        self.group_path.iter().zip(names).all(|(expected, name)| expected == name)
+    }
+}
+
+impl<'a> SearchResult<'a> {
+    pub fn entry(&self) -> &'a DatabaseEntry {
+        self.entry
+    }
+
+    pub fn groups(&self) -> &[&'a Group] {
+        &self.groups
+    }
+
+    pub fn group_path(&self) -> String {
+        let names: Vec<&str> = self.groups.iter().skip(1).map(|group| group.name()).collect();
+        names.join(&PATH_SEPARATOR.to_string())
+    }
+}
+
+pub fn search<'a>(database: &'a Database, search: &Search) -> Vec<SearchResult<'a>> {
+    let recycle_bin = if search.exclude_recycle_bin {
+        database.meta().recycle_bin_uuid()
+    } else {
+        None
+    };
+
+    let mut results = vec![];
+    collect(database.root(), &mut vec![], search, recycle_bin, &mut results);
+    results
+}
+
+fn collect<'a>(group: &'a Group,
+               path: &mut Vec<&'a Group>,
+               search: &Search,
+               recycle_bin: Option<Uuid>,
+               results: &mut Vec<SearchResult<'a>>) {
+    if recycle_bin.map_or(false, |uuid| *group.uuid() == uuid) {
+        return;
+    }
+
+    path.push(group);
+    if search.includes_path(path) {
+        for entry in group.entries().iter().filter(|entry| search.matches(entry)) {
+            results.push(SearchResult {
+                entry: entry,
+                groups: path.clone(),
+            });
+        }
+    }
+    for child in group.groups() {
+        collect(child, path, search, recycle_bin, results);
+    }
+    path.pop();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Search;
+
+    use {Database, DatabaseEntry, Error, Group, Timestamp};
+
+    fn entry(title: &str, username: &str, password: &str) -> DatabaseEntry {
+        DatabaseEntry::new(Some(title.to_string()),
+                           Some(username.to_string()),
+                           Some(password.to_string()))
+    }
+
+    fn database() -> Database {
+        let mut github = entry("GitHub", "joe.bloggs", "hunter2");
+        github.set_field("URL", Some("https://github.com".to_string()), false);
+        github.add_tag("Development");
+
+        let mut gitlab = entry("GitLab", "jbloggs", "Secret-Hub");
+        gitlab.add_tag("development");
+        gitlab.times_mut().set_expires(true);
+        gitlab.times_mut().set_expiry_time(Some(Timestamp::new(2016, 1, 1, 0, 0, 0)));
+
+        let mut work = Group::new("Work");
+        work.add_entry(github);
+        work.add_entry(gitlab);
+
+        let mut internet = Group::new("Internet");
+        internet.add_group(work);
+        internet.add_entry(entry("Webmail", "joe@example.com", "letmein"));
+
+        let mut database = Database::new();
+        database.root_mut().add_group(internet);
+        database.add(entry("Old GitHub", "joe.bloggs", "hunter1"));
+        database
+    }
+
+    fn titles(database: &Database, search: &Search) -> Vec<String> {
+        database.search(search)
+                .map(|result| result.entry().title().clone().unwrap_or_default())
+                .collect()
+    }
+
+    #[test]
+    fn should_search_text_in_fields() {
+        let database = database();
+
+        let search = Search::new().text("GITHUB");
+        assert_eq!(titles(&database, &search), vec!["Old GitHub", "GitHub"]);
+
+        let search = Search::new().text("hub");
+        assert_eq!(titles(&database, &search), vec!["Old GitHub", "GitHub"]);
+
+        let search = Search::new().text("hub").fields(&["Password"]);
+        assert_eq!(titles(&database, &search), vec!["GitLab"]);
+    }
+
+    #[test]
+    fn should_search_with_regex() {
+        let database = database();
+
+        let search = Search::new().regex(r"^joe[.@]").unwrap().fields(&["UserName"]);
+        assert_eq!(titles(&database, &search), vec!["Old GitHub", "Webmail", "GitHub"]);
+
+        let search = Search::new().regex(r"(?i)^git").unwrap().text("lab");
+        assert_eq!(titles(&database, &search), vec!["GitLab"]);
+
+        let result = Search::new().regex("git(");
+        match result {
+            Err(Error::Regex(_)) => (),
+            _ => panic!("Invalid result: {:#?}", result.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn should_filter_by_tag_and_group() {
+        let database = database();
+
+        let search = Search::new().tag("DEVELOPMENT");
+        assert_eq!(titles(&database, &search), vec!["GitHub", "GitLab"]);
+
+        let search = Search::new().group("Internet");
+        assert_eq!(titles(&database, &search), vec!["Webmail", "GitHub", "GitLab"]);
+
+        let search = Search::new().group("Internet/Work").text("github");
+        let results: Vec<_> = database.search(&search).collect();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].group_path(), "Internet/Work");
+        assert_eq!(results[0].groups()[0].name(), "Root");
+    }
+
+    #[test]
+    fn should_exclude_recycle_bin() {
+        let mut database = database();
+        let uuid = *database.find("Old GitHub").unwrap().uuid();
+        database.delete_entry(&uuid).unwrap();
+
+        let search = Search::new().text("github");
+        assert_eq!(titles(&database, &search), vec!["GitHub", "Old GitHub"]);
+
+        let search = Search::new().text("github").exclude_recycle_bin();
+        assert_eq!(titles(&database, &search), vec!["GitHub"]);
+    }
+
+    #[test]
+    fn should_filter_by_expiry() {
+        let database = database();
+        let now = Timestamp::new(2016, 8, 30, 0, 0, 0);
+
+        let search = Search::new().expired(true).now(now);
+        assert_eq!(titles(&database, &search), vec!["GitLab"]);
+
+        let search = Search::new().expired(false).now(now).group("Internet/Work");
+        assert_eq!(titles(&database, &search), vec!["GitHub"]);
+
+        let search = Search::new().expires_before(Timestamp::new(2015, 12, 1, 0, 0, 0));
+        assert!(titles(&database, &search).is_empty());
+    }
+}
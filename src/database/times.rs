@@ -0,0 +1,98 @@
+use Timestamp;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Times {
+    creation_time: Option<Timestamp>,
+    last_modification_time: Option<Timestamp>,
+    last_access_time: Option<Timestamp>,
+    expiry_time: Option<Timestamp>,
+    expires: bool,
+    usage_count: u32,
+    location_changed: Option<Timestamp>,
+}
+
+impl Times {
+    pub fn new() -> Times {
+        Times::default()
+    }
+
+    pub fn creation_time(&self) -> Option<Timestamp> {
+        self.creation_time
+    }
+
+    pub fn set_creation_time(&mut self, time: Option<Timestamp>) {
+        self.creation_time = time;
+    }
+
+    pub fn last_modification_time(&self) -> Option<Timestamp> {
+        self.last_modification_time
+    }
+
+    pub fn set_last_modification_time(&mut self, time: Option<Timestamp>) {
+        self.last_modification_time = time;
+    }
+
+    pub fn last_access_time(&self) -> Option<Timestamp> {
+        self.last_access_time
+    }
+
+    pub fn set_last_access_time(&mut self, time: Option<Timestamp>) {
+        self.last_access_time = time;
+    }
+
+    pub fn expiry_time(&self) -> Option<Timestamp> {
+        self.expiry_time
+    }
+
+    pub fn set_expiry_time(&mut self, time: Option<Timestamp>) {
+        self.expiry_time = time;
+    }
+
+    pub fn expires(&self) -> bool {
+        self.expires
+    }
+
+    pub fn set_expires(&mut self, expires: bool) {
+        self.expires = expires;
+    }
+
+    pub fn usage_count(&self) -> u32 {
+        self.usage_count
+    }
+
+    pub fn set_usage_count(&mut self, usage_count: u32) {
+        self.usage_count = usage_count;
+    }
+
+    pub fn location_changed(&self) -> Option<Timestamp> {
+        self.location_changed
+    }
+
+    pub fn set_location_changed(&mut self, time: Option<Timestamp>) {
+        self.location_changed = time;
+    }
+
+    pub fn is_expired(&self, now: Timestamp) -> bool {
+        self.expires && self.expiry_time.map_or(false, |expiry_time| expiry_time <= now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Times;
+
+    use Timestamp;
+
+    #[test]
+    fn should_only_expire_if_flag_set() {
+        let now = Timestamp::new(2016, 8, 30, 0, 0, 0);
+
+        let mut times = Times::new();
+        times.set_expiry_time(Some(Timestamp::new(2016, 1, 1, 0, 0, 0)));
+        assert!(!times.is_expired(now));
+
+        times.set_expires(true);
+        assert!(times.is_expired(now));
+        assert!(!times.is_expired(Timestamp::new(2015, 12, 31, 0, 0, 0)));
+    }
+}
@@ -0,0 +1,267 @@
+use Error;
+
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: i64 = 86400;
+const UNIX_EPOCH_SECONDS: i64 = 62135596800;
+const MIN_YEAR: i64 = 1;
+const MAX_YEAR: i64 = 9999;
+// Seconds from 0001-01-01 to 10000-01-01, the end of the range KeePass can represent.
+const MAX_SECONDS: i64 = 315537897600;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp {
+    seconds: i64,
+}
+
+impl Timestamp {
+    pub fn new(year: i64, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> Timestamp {
+        let days = days_from_civil(year, month, day);
+        let seconds = (hour * 3600 + minute * 60 + second) as i64;
+
+        Timestamp::from_unix(days * SECONDS_PER_DAY + seconds)
+    }
+
+    pub fn from_seconds(seconds: i64) -> Timestamp {
+        Timestamp { seconds: seconds }
+    }
+
+    pub fn checked_from_seconds(seconds: i64) -> Option<Timestamp> {
+        if seconds >= 0 && seconds < MAX_SECONDS {
+            Some(Timestamp::from_seconds(seconds))
+        } else {
+            None
+        }
+    }
+
+    pub fn from_unix(seconds: i64) -> Timestamp {
+        Timestamp::from_seconds(seconds + UNIX_EPOCH_SECONDS)
+    }
+
+    pub fn now() -> Timestamp {
+        let seconds = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs() as i64,
+            Err(e) => -(e.duration().as_secs() as i64),
+        };
+
+        Timestamp::from_unix(seconds)
+    }
+
+    pub fn parse(value: &str) -> Result<Timestamp, Error> {
+        parse_iso8601(value.trim()).ok_or(Error::InvalidTimestamp(value.to_string()))
+    }
+
+    pub fn seconds(&self) -> i64 {
+        self.seconds
+    }
+
+    pub fn unix(&self) -> i64 {
+        self.seconds.saturating_sub(UNIX_EPOCH_SECONDS)
+    }
+
+    pub fn date_time(&self) -> (i64, u32, u32, u32, u32, u32) {
+        let unix = self.unix();
+        let (days, seconds) = match unix % SECONDS_PER_DAY {
+            seconds if seconds < 0 => (unix / SECONDS_PER_DAY - 1, seconds + SECONDS_PER_DAY),
+            seconds => (unix / SECONDS_PER_DAY, seconds),
+        };
+        let seconds = seconds as u32;
+        let (year, month, day) = civil_from_days(days);
+
+        (year, month, day, seconds / 3600, seconds / 60 % 60, seconds % 60)
+    }
+}
+
+impl Display for Timestamp {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), fmt::Error> {
+        let (year, month, day, hour, minute, second) = self.date_time();
+        write!(formatter,
+               "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+               year,
+               month,
+               day,
+               hour,
+               minute,
+               second)
+    }
+}
+
+fn parse_iso8601(value: &str) -> Option<Timestamp> {
+    let (date, time) = match value.find('T') {
+        Some(index) => (&value[..index], &value[index + 1..]),
+        None => (value, ""),
+    };
+
+    let date: Vec<&str> = date.split('-').collect();
+    if date.len() != 3 {
+        return None;
+    }
+
+    let (time, offset) = match time.find(|c| c == 'Z' || c == '+' || c == '-') {
+        Some(index) => (&time[..index], try_opt!(parse_offset(&time[index..]))),
+        None => (time, 0),
+    };
+    let time = time.split('.').next().unwrap_or("");
+    let time: Vec<&str> = if time.is_empty() {
+        vec![]
+    } else {
+        time.split(':').collect()
+    };
+
+    let year = try_opt!(date[0].parse().ok());
+    let month = try_opt!(date[1].parse().ok());
+    let day = try_opt!(date[2].parse().ok());
+    if year < MIN_YEAR || year > MAX_YEAR || month < 1 || month > 12 || day < 1 || day > 31 {
+        return None;
+    }
+
+    let mut parts = [0; 3];
+    for (part, value) in parts.iter_mut().zip(time.iter()) {
+        *part = try_opt!(value.parse().ok());
+    }
+    if time.len() > 3 || parts[0] > 23 || parts[1] > 59 || parts[2] > 60 {
+        return None;
+    }
+
+    let timestamp = Timestamp::new(year, month, day, parts[0], parts[1], parts[2]);
+    timestamp.seconds.checked_sub(offset).map(Timestamp::from_seconds)
+}
+
+fn parse_offset(value: &str) -> Option<i64> {
+    if value == "Z" {
+        return Some(0);
+    }
+
+    let sign = if value.starts_with('-') { -1 } else { 1 };
+    let digits: String = value[1..].chars().filter(|&c| c != ':').collect();
+    if digits.len() != 4 || !digits.bytes().all(|b| b >= b'0' && b <= b'9') {
+        return None;
+    }
+
+    let hours: i64 = try_opt!(digits[..2].parse().ok());
+    let minutes: i64 = try_opt!(digits[2..].parse().ok());
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+// Howard Hinnant's algorithms for converting between proleptic Gregorian dates and days since
+// 1970-01-01.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = div_floor(year, 400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 -
+                      1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146097 + day_of_era - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = div_floor(days, 146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 -
+                       day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month + 2) / 5 + 1) as u32;
+    let month = if month < 10 { month + 3 } else { month - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+fn div_floor(value: i64, divisor: i64) -> i64 {
+    let result = value / divisor;
+    if value % divisor < 0 {
+        result - 1
+    } else {
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Timestamp;
+
+    use Error;
+
+    #[test]
+    fn should_convert_unix_epoch() {
+        let timestamp = Timestamp::new(1970, 1, 1, 0, 0, 0);
+
+        assert_eq!(timestamp.unix(), 0);
+        assert_eq!(timestamp.seconds(), 62135596800);
+        assert_eq!(Timestamp::new(1, 1, 1, 0, 0, 0).seconds(), 0);
+    }
+
+    #[test]
+    fn should_parse_iso8601() {
+        let timestamp = Timestamp::parse("2016-08-30T14:46:12Z").unwrap();
+
+        assert_eq!(timestamp.unix(), 1472568372);
+        assert_eq!(timestamp.date_time(), (2016, 8, 30, 14, 46, 12));
+        assert_eq!(timestamp.to_string(), "2016-08-30T14:46:12Z");
+    }
+
+    #[test]
+    fn should_parse_iso8601_with_offset_and_fraction() {
+        let timestamp = Timestamp::parse("2016-08-30T16:46:12.125+02:00").unwrap();
+        assert_eq!(timestamp, Timestamp::new(2016, 8, 30, 14, 46, 12));
+
+        let timestamp = Timestamp::parse("2000-02-29").unwrap();
+        assert_eq!(timestamp.date_time(), (2000, 2, 29, 0, 0, 0));
+    }
+
+    #[test]
+    fn should_return_error_if_invalid_timestamp() {
+        let result = Timestamp::parse("2016-13-30T14:46:12Z");
+
+        match result {
+            Err(Error::InvalidTimestamp(ref value)) if value == "2016-13-30T14:46:12Z" => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
+    #[test]
+    fn should_return_error_if_malformed_timestamp() {
+        for value in &["2016-01-01T00:00:00+1\u{e9}1",
+                       "9223372036854775807-01-01T00:00:00Z",
+                       "0000-12-31T00:00:00Z",
+                       "10000-01-01T00:00:00Z",
+                       "2016-01-01T00:00:00+99:00"] {
+            match Timestamp::parse(value) {
+                Err(Error::InvalidTimestamp(ref result)) if result == value => (),
+                result => panic!("Invalid result for {}: {:#?}", value, result),
+            }
+        }
+    }
+
+    #[test]
+    fn should_check_seconds_range() {
+        assert_eq!(Timestamp::checked_from_seconds(0), Some(Timestamp::new(1, 1, 1, 0, 0, 0)));
+        assert_eq!(Timestamp::checked_from_seconds(-1), None);
+        assert_eq!(Timestamp::checked_from_seconds(315537897600), None);
+        assert_eq!(Timestamp::new(9999, 12, 31, 23, 59, 59).seconds(), 315537897599);
+
+        assert_eq!(Timestamp::from_seconds(i64::min_value()).unix(), i64::min_value());
+        Timestamp::from_seconds(i64::min_value()).to_string();
+        Timestamp::from_seconds(i64::max_value()).to_string();
+    }
+
+    #[test]
+    fn should_order_timestamps() {
+        let earlier = Timestamp::new(1999, 12, 31, 23, 59, 59);
+        let later = Timestamp::new(2000, 1, 1, 0, 0, 0);
+
+        assert!(earlier < later);
+        assert_eq!(later.seconds() - earlier.seconds(), 1);
+    }
+}
@@ -0,0 +1,103 @@
+use Error;
+
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+use rand;
+use rustc_serialize::base64::{FromBase64, STANDARD, ToBase64};
+use rustc_serialize::hex::ToHex;
+
+const UUID_SIZE: usize = 16;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Uuid {
+    bytes: [u8; UUID_SIZE],
+}
+
+impl Uuid {
+    pub fn new_v4() -> Uuid {
+        let mut bytes: [u8; UUID_SIZE] = rand::random();
+        bytes[6] = (bytes[6] & 0x0F) | 0x40;
+        bytes[8] = (bytes[8] & 0x3F) | 0x80;
+
+        Uuid::from_bytes(bytes)
+    }
+
+    pub fn nil() -> Uuid {
+        Uuid::default()
+    }
+
+    pub fn from_bytes(bytes: [u8; UUID_SIZE]) -> Uuid {
+        Uuid { bytes: bytes }
+    }
+
+    pub fn from_slice(bytes: &[u8]) -> Option<Uuid> {
+        if bytes.len() != UUID_SIZE {
+            return None;
+        }
+
+        let mut uuid = Uuid::nil();
+        uuid.bytes.copy_from_slice(bytes);
+        Some(uuid)
+    }
+
+    pub fn parse(value: &str) -> Result<Uuid, Error> {
+        let bytes = try!(value.trim().from_base64().map_err(|e| Error::Base64(e)));
+        Uuid::from_slice(&bytes).ok_or(Error::InvalidUuid(value.to_string()))
+    }
+
+    pub fn as_bytes(&self) -> &[u8; UUID_SIZE] {
+        &self.bytes
+    }
+
+    pub fn is_nil(&self) -> bool {
+        self.bytes.iter().all(|&b| b == 0)
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.bytes.to_hex().to_uppercase()
+    }
+}
+
+impl Display for Uuid {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), fmt::Error> {
+        formatter.write_str(&self.bytes.to_base64(STANDARD))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Uuid;
+
+    use Error;
+
+    #[test]
+    fn should_parse_base64_uuid() {
+        let uuid = Uuid::parse("NQ7IDchX4EaBjopMEgDORA==").unwrap();
+
+        assert_eq!(uuid.as_bytes()[..4], [0x35, 0x0E, 0xC8, 0x0D]);
+        assert_eq!(uuid.to_string(), "NQ7IDchX4EaBjopMEgDORA==");
+        assert!(!uuid.is_nil());
+        assert!(Uuid::parse("AAAAAAAAAAAAAAAAAAAAAA==").unwrap().is_nil());
+        assert_eq!(uuid.to_hex(), "350EC80DC857E046818E8A4C1200CE44");
+    }
+
+    #[test]
+    fn should_return_error_if_invalid_length() {
+        let result = Uuid::parse("AAAA");
+
+        match result {
+            Err(Error::InvalidUuid(ref value)) if value == "AAAA" => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
+    #[test]
+    fn should_generate_random_uuids() {
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+
+        assert!(first != second);
+        assert_eq!(first.as_bytes()[6] >> 4, 4);
+    }
+}
@@ -0,0 +1,93 @@
+#[derive(Clone, Debug, PartialEq)]
+pub enum XmlNode {
+    Element(XmlElement),
+    Text(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct XmlElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<XmlNode>,
+}
+
+impl XmlElement {
+    pub fn new(name: &str) -> XmlElement {
+        XmlElement {
+            name: name.to_string(),
+            attributes: vec![],
+            children: vec![],
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn attributes(&self) -> &[(String, String)] {
+        &self.attributes
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|&&(ref key, _)| key == name).map(|&(_, ref value)| &value[..])
+    }
+
+    pub fn add_attribute(&mut self, name: &str, value: &str) {
+        self.attributes.push((name.to_string(), value.to_string()));
+    }
+
+    pub fn children(&self) -> &[XmlNode] {
+        &self.children
+    }
+
+    pub fn add_child(&mut self, child: XmlNode) {
+        self.children.push(child);
+    }
+
+    pub fn elements(&self) -> Vec<&XmlElement> {
+        self.children
+            .iter()
+            .filter_map(|child| {
+                match *child {
+                    XmlNode::Element(ref element) => Some(element),
+                    XmlNode::Text(_) => None,
+                }
+            })
+            .collect()
+    }
+
+    pub fn text(&self) -> String {
+        self.children
+            .iter()
+            .filter_map(|child| {
+                match *child {
+                    XmlNode::Text(ref text) => Some(&text[..]),
+                    XmlNode::Element(_) => None,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{XmlElement, XmlNode};
+
+    #[test]
+    fn should_collect_elements_and_text() {
+        let mut key = XmlElement::new("Key");
+        key.add_child(XmlNode::Text("KPXC_".to_string()));
+        key.add_child(XmlNode::Text("BROWSER".to_string()));
+
+        let mut item = XmlElement::new("Item");
+        item.add_attribute("Plugin", "KeePassXC");
+        item.add_child(XmlNode::Text("\n".to_string()));
+        item.add_child(XmlNode::Element(key));
+
+        assert_eq!(item.attribute("Plugin"), Some("KeePassXC"));
+        assert_eq!(item.attribute("Missing"), None);
+        assert_eq!(item.elements().len(), 1);
+        assert_eq!(item.elements()[0].name(), "Key");
+        assert_eq!(item.elements()[0].text(), "KPXC_BROWSER");
+    }
+}
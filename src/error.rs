@@ -1,4 +1,4 @@
-use FileType;
+use {FileType, Uuid};
 
 use std;
 use std::fmt;
@@ -7,8 +7,10 @@ use std::io::Error as IoError;
 use std::string::FromUtf8Error;
 
 use crypto::symmetriccipher::SymmetricCipherError;
+use regex::Error as RegexError;
 use rustc_serialize::base64::FromBase64Error;
 use xml::reader::Error as XmlError;
+use xml::writer::Error as XmlEmitterError;
 
 #[derive(Debug)]
 pub enum Error {
@@ -18,12 +20,19 @@ pub enum Error {
     InvalidSignature(u32),
     InvalidFileType(u32),
     UnsupportedFileType(FileType),
+    UnsupportedVersion(u32),
+    UnsupportedWriteVersion(u32),
     UnknownTlv(u8),
+    UnknownInnerTlv(u8),
     InvalidTlvSize,
+    InvalidVariantDictionaryVersion(u16),
+    UnknownVariantType(u8),
 
     UnknownCipherType(u64, u64),
     UnknownCompressionType(u32),
     UnknownInnerRandomStreamType(u32),
+    UnknownKdf(u64, u64),
+    UnknownEncryptionFlags(u32),
 
     MissingCompressionType,
     MissingCipherType,
@@ -31,21 +40,47 @@ pub enum Error {
     MissingTransformSeed,
     MissingTransformRounds,
     MissingEncryptionIv,
+    InvalidEncryptionIv,
     MissingProtectedStreamKey,
     MissingStreamStartBytes,
     MissingInnerRandomStream,
+    MissingKdfParameters,
+    InvalidKdfParameters,
 
     Cipher(SymmetricCipherError),
 
     IncorrectStartBytes,
     IncorrectBlockId,
     IncorrectBlockHash,
+    IncorrectHeaderHash,
+    IncorrectHeaderHmac,
+    IncorrectBlockHmac,
+    IncorrectContentsHash,
+    MissingBinary(String),
+    InvalidHistoryIndex(usize),
+    InvalidTimestamp(String),
+    InvalidUuid(String),
+    InvalidAutoTypeSequence(String),
+    MissingEntry(Uuid),
+    MissingGroup(Uuid),
+    CannotDeleteRootGroup,
+    InvalidGroupMove(Uuid),
+    CyclicFieldReference(String),
+    FieldReferenceTooDeep(String),
+    UnknownPlaceholder(String),
 
     Xml(XmlError),
+    XmlEmitter(XmlEmitterError),
 
     MissingKey,
+    MissingCompositeKey,
+    InvalidKeyFile,
+    IncorrectKeyFileHash,
+    ChallengeResponse(String),
+    UnsupportedChallengeResponse,
 
     Base64(FromBase64Error),
+    Regex(RegexError),
     Utf8(FromUtf8Error),
 }
 
@@ -1,5 +1,7 @@
 use super::{CipherType, CompressionType, Header, InnerRandomStreamType};
+use super::master_key::Kdf;
 use super::tlv::Tlv;
+use super::variant_dictionary::VariantDictionary;
 
 use Error;
 
@@ -10,10 +12,11 @@ pub struct HeaderBuilder {
     master_seed: Option<[u8; 32]>,
     transform_seed: Option<[u8; 32]>,
     transform_rounds: Option<u64>,
-    encryption_iv: Option<[u8; 16]>,
+    encryption_iv: Option<Vec<u8>>,
     protected_stream_key: Option<[u8; 32]>,
     stream_start_bytes: Option<[u8; 32]>,
     inner_random_stream: Option<InnerRandomStreamType>,
+    kdf_parameters: Option<VariantDictionary>,
 }
 
 impl HeaderBuilder {
@@ -29,6 +32,7 @@ impl HeaderBuilder {
             protected_stream_key: None,
             stream_start_bytes: None,
             inner_random_stream: None,
+            kdf_parameters: None,
         }
     }
 
@@ -44,43 +48,72 @@ impl HeaderBuilder {
             Tlv::ProtectedStreamKey(key) => self.protected_stream_key = Some(key),
             Tlv::StreamStartBytes(bytes) => self.stream_start_bytes = Some(bytes),
             Tlv::InnerRandomStream(stream) => self.inner_random_stream = Some(stream),
+            Tlv::KdfParameters(parameters) => self.kdf_parameters = Some(parameters),
+            Tlv::PublicCustomData(_) => {}
         }
     }
 
-    pub fn build(self) -> Result<Header, Error> {
+    pub fn build(mut self) -> Result<Header, Error> {
+        let is_kdbx4 = super::major_version(self.version) >= 4;
+
+        let kdf = if is_kdbx4 {
+            let parameters = try!(self.kdf_parameters.take().ok_or(Error::MissingKdfParameters));
+            Some(try!(Kdf::from_parameters(&parameters)))
+        } else {
+            None
+        };
+
         if self.cipher.is_none() {
             Err(Error::MissingCipherType)
         } else if self.compression.is_none() {
             Err(Error::MissingCompressionType)
         } else if self.master_seed.is_none() {
             Err(Error::MissingMasterSeed)
-        } else if self.transform_seed.is_none() {
+        } else if kdf.is_none() && self.transform_seed.is_none() {
             Err(Error::MissingTransformSeed)
-        } else if self.transform_rounds.is_none() {
+        } else if kdf.is_none() && self.transform_rounds.is_none() {
             Err(Error::MissingTransformRounds)
         } else if self.encryption_iv.is_none() {
             Err(Error::MissingEncryptionIv)
-        } else if self.protected_stream_key.is_none() {
+        } else if !self.has_valid_encryption_iv() {
+            Err(Error::InvalidEncryptionIv)
+        } else if !is_kdbx4 && self.protected_stream_key.is_none() {
             Err(Error::MissingProtectedStreamKey)
-        } else if self.stream_start_bytes.is_none() {
+        } else if !is_kdbx4 && self.stream_start_bytes.is_none() {
             Err(Error::MissingStreamStartBytes)
-        } else if self.inner_random_stream.is_none() {
+        } else if !is_kdbx4 && self.inner_random_stream.is_none() {
             Err(Error::MissingInnerRandomStream)
         } else {
+            let kdf = match kdf {
+                Some(kdf) => kdf,
+                None => {
+                    Kdf::Aes {
+                        seed: self.transform_seed.unwrap(),
+                        rounds: self.transform_rounds.unwrap(),
+                    }
+                }
+            };
+
             Ok(Header {
                 version: self.version,
                 cipher: self.cipher.unwrap(),
                 compression: self.compression.unwrap(),
                 master_seed: self.master_seed.unwrap(),
-                transform_seed: self.transform_seed.unwrap(),
-                transform_rounds: self.transform_rounds.unwrap(),
-                encryption_iv: self.encryption_iv.unwrap(),
-                protected_stream_key: self.protected_stream_key.unwrap(),
-                stream_start_bytes: self.stream_start_bytes.unwrap(),
-                inner_random_stream: self.inner_random_stream.unwrap(),
+                kdf: kdf,
+                encryption_iv: self.encryption_iv.take().unwrap(),
+                protected_stream_key: self.protected_stream_key,
+                stream_start_bytes: self.stream_start_bytes,
+                inner_random_stream: self.inner_random_stream,
             })
         }
     }
+
+    fn has_valid_encryption_iv(&self) -> bool {
+        match (&self.cipher, &self.encryption_iv) {
+            (&Some(ref cipher), &Some(ref iv)) => cipher.iv_length() == iv.len(),
+            _ => false,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -88,16 +121,18 @@ mod test {
     use super::*;
 
     use Error;
+    use header::master_key::Kdf;
     use header::{CipherType, CompressionType, InnerRandomStreamType};
     use header::tlv::Tlv;
+    use header::variant_dictionary::{Variant, VariantDictionary};
 
     #[test]
     pub fn should_build_header() {
-        let version = 0x01020304;
+        let version = 0x00030001;
         let master_seed = [1; 32];
         let transform_seed = [2; 32];
         let transform_rounds = 10000;
-        let iv = [3; 16];
+        let iv = vec![3; 16];
         let protected_stream_key = [4; 32];
         let stream_start_bytes = [5; 32];
 
@@ -107,7 +142,7 @@ mod test {
         builder.apply(Tlv::MasterSeed(master_seed));
         builder.apply(Tlv::TransformSeed(transform_seed));
         builder.apply(Tlv::TransformRounds(transform_rounds));
-        builder.apply(Tlv::EncryptionIv(iv));
+        builder.apply(Tlv::EncryptionIv(iv.clone()));
         builder.apply(Tlv::ProtectedStreamKey(protected_stream_key));
         builder.apply(Tlv::StreamStartBytes(stream_start_bytes));
         builder.apply(Tlv::InnerRandomStream(InnerRandomStreamType::Rc4));
@@ -118,17 +153,87 @@ mod test {
         assert_eq!(result.cipher, CipherType::Aes);
         assert_eq!(result.compression, CompressionType::Gzip);
         assert_eq!(result.master_seed, master_seed);
-        assert_eq!(result.transform_seed, transform_seed);
-        assert_eq!(result.transform_rounds, 10000);
+        assert_eq!(result.kdf,
+                   Kdf::Aes {
+                       seed: transform_seed,
+                       rounds: 10000,
+                   });
         assert_eq!(result.encryption_iv, iv);
-        assert_eq!(result.protected_stream_key, protected_stream_key);
-        assert_eq!(result.stream_start_bytes, stream_start_bytes);
-        assert_eq!(result.inner_random_stream, InnerRandomStreamType::Rc4);
+        assert_eq!(result.protected_stream_key, Some(protected_stream_key));
+        assert_eq!(result.stream_start_bytes, Some(stream_start_bytes));
+        assert_eq!(result.inner_random_stream, Some(InnerRandomStreamType::Rc4));
+    }
+
+    #[test]
+    pub fn should_build_kdbx4_header() {
+        let version = 0x00040000;
+        let master_seed = [1; 32];
+        let transform_seed = [2; 32];
+        let iv = vec![3; 16];
+
+        let uuid = vec![0xC9, 0xD9, 0xF3, 0x9A, 0x62, 0x8A, 0x44, 0x60, 0xBF, 0x74, 0x0D, 0x08,
+                        0xC1, 0x8A, 0x4F, 0xEA];
+        let parameters: VariantDictionary =
+            vec![("$UUID".to_string(), Variant::ByteArray(uuid)),
+                 ("R".to_string(), Variant::UInt64(10000)),
+                 ("S".to_string(), Variant::ByteArray(transform_seed.to_vec()))]
+                .into_iter()
+                .collect();
+
+        let mut builder = HeaderBuilder::new(version);
+        builder.apply(Tlv::Cipher(CipherType::Aes));
+        builder.apply(Tlv::Compression(CompressionType::Gzip));
+        builder.apply(Tlv::MasterSeed(master_seed));
+        builder.apply(Tlv::EncryptionIv(iv));
+        builder.apply(Tlv::KdfParameters(parameters));
+
+        let result = builder.build().unwrap();
+
+        assert_eq!(result.version, version);
+        assert_eq!(result.kdf,
+                   Kdf::Aes {
+                       seed: transform_seed,
+                       rounds: 10000,
+                   });
+        assert_eq!(result.protected_stream_key, None);
+        assert_eq!(result.stream_start_bytes, None);
+        assert_eq!(result.inner_random_stream, None);
+    }
+
+    #[test]
+    pub fn should_return_error_if_no_kdf_parameters() {
+        let version = 0x00040000;
+
+        let builder = HeaderBuilder::new(version);
+        let result = builder.build();
+
+        match result {
+            Err(Error::MissingKdfParameters) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
+    #[test]
+    pub fn should_return_error_if_unknown_kdf() {
+        let version = 0x00040000;
+
+        let parameters: VariantDictionary =
+            vec![("$UUID".to_string(), Variant::ByteArray(vec![1; 16]))].into_iter().collect();
+
+        let mut builder = HeaderBuilder::new(version);
+        builder.apply(Tlv::KdfParameters(parameters));
+
+        let result = builder.build();
+
+        match result {
+            Err(Error::UnknownKdf(0x0101010101010101, 0x0101010101010101)) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
     }
 
     #[test]
     pub fn should_return_error_if_no_cipher() {
-        let version = 0x01020304;
+        let version = 0x00030001;
 
         let builder = HeaderBuilder::new(version);
         let result = builder.build();
@@ -141,7 +246,7 @@ mod test {
 
     #[test]
     pub fn should_return_error_if_no_compression() {
-        let version = 0x01020304;
+        let version = 0x00030001;
 
         let mut builder = HeaderBuilder::new(version);
         builder.apply(Tlv::Cipher(CipherType::Aes));
@@ -156,7 +261,7 @@ mod test {
 
     #[test]
     pub fn should_return_error_if_no_master_seed() {
-        let version = 0x01020304;
+        let version = 0x00030001;
 
         let mut builder = HeaderBuilder::new(version);
         builder.apply(Tlv::Cipher(CipherType::Aes));
@@ -172,7 +277,7 @@ mod test {
 
     #[test]
     pub fn should_return_error_if_no_transform_seed() {
-        let version = 0x01020304;
+        let version = 0x00030001;
         let master_seed = [1; 32];
 
         let mut builder = HeaderBuilder::new(version);
@@ -190,7 +295,7 @@ mod test {
 
     #[test]
     pub fn should_return_error_if_no_transform_rounds() {
-        let version = 0x01020304;
+        let version = 0x00030001;
         let master_seed = [1; 32];
         let transform_seed = [2; 32];
 
@@ -210,7 +315,7 @@ mod test {
 
     #[test]
     pub fn should_return_error_if_no_encryption_iv() {
-        let version = 0x01020304;
+        let version = 0x00030001;
         let master_seed = [1; 32];
         let transform_seed = [2; 32];
         let transform_rounds = 10000;
@@ -230,13 +335,37 @@ mod test {
         }
     }
 
+    #[test]
+    pub fn should_return_error_if_invalid_encryption_iv() {
+        let version = 0x00030001;
+        let master_seed = [1; 32];
+        let transform_seed = [2; 32];
+        let transform_rounds = 10000;
+        let iv = vec![3; 12];
+
+        let mut builder = HeaderBuilder::new(version);
+        builder.apply(Tlv::Cipher(CipherType::Aes));
+        builder.apply(Tlv::Compression(CompressionType::Gzip));
+        builder.apply(Tlv::MasterSeed(master_seed));
+        builder.apply(Tlv::TransformSeed(transform_seed));
+        builder.apply(Tlv::TransformRounds(transform_rounds));
+        builder.apply(Tlv::EncryptionIv(iv));
+
+        let result = builder.build();
+
+        match result {
+            Err(Error::InvalidEncryptionIv) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
     #[test]
     pub fn should_return_error_if_no_protected_stream_key() {
-        let version = 0x01020304;
+        let version = 0x00030001;
         let master_seed = [1; 32];
         let transform_seed = [2; 32];
         let transform_rounds = 10000;
-        let iv = [3; 16];
+        let iv = vec![3; 16];
 
         let mut builder = HeaderBuilder::new(version);
         builder.apply(Tlv::Cipher(CipherType::Aes));
@@ -256,11 +385,11 @@ mod test {
 
     #[test]
     pub fn should_return_error_if_no_stream_start_bytes() {
-        let version = 0x01020304;
+        let version = 0x00030001;
         let master_seed = [1; 32];
         let transform_seed = [2; 32];
         let transform_rounds = 10000;
-        let iv = [3; 16];
+        let iv = vec![3; 16];
         let protected_stream_key = [4; 32];
 
         let mut builder = HeaderBuilder::new(version);
@@ -282,11 +411,11 @@ mod test {
 
     #[test]
     pub fn should_return_error_if_no_inner_random_stream() {
-        let version = 0x01020304;
+        let version = 0x00030001;
         let master_seed = [1; 32];
         let transform_seed = [2; 32];
         let transform_rounds = 10000;
-        let iv = [3; 16];
+        let iv = vec![3; 16];
         let protected_stream_key = [4; 32];
         let stream_start_bytes = [5; 32];
 
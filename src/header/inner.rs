@@ -0,0 +1,135 @@
+use bytes;
+use Error;
+
+use super::InnerRandomStreamType;
+use super::tlv;
+use protected::ProtectedStream;
+
+use std::io::Read;
+
+#[derive(Debug)]
+pub struct InnerHeader {
+    inner_random_stream: InnerRandomStreamType,
+    protected_stream_key: Vec<u8>,
+    binaries: Vec<Vec<u8>>,
+}
+
+impl InnerHeader {
+    pub fn protected_stream(&self) -> Box<ProtectedStream> {
+        self.inner_random_stream.protected_stream(&self.protected_stream_key)
+    }
+
+    pub fn binaries(&self) -> &[Vec<u8>] {
+        &self.binaries
+    }
+}
+
+pub fn read_inner_header(reader: &mut Read) -> Result<InnerHeader, Error> {
+    let mut inner_random_stream = None;
+    let mut protected_stream_key = None;
+    let mut binaries = vec![];
+
+    loop {
+        let tlv_type = try!(bytes::read_u8(reader));
+        let length = try!(bytes::read_u32(reader));
+        let value = try!(bytes::read_bytes(reader, length as usize));
+
+        match tlv_type {
+            0 => break,
+            1 => {
+                try!(tlv::check_tlv_length(length, 4));
+                let stream_id = try!(bytes::read_u32(&mut &value[..]));
+                inner_random_stream = Some(try!(tlv::match_stream_id(stream_id)));
+            }
+            2 => protected_stream_key = Some(value),
+            3 => {
+                if value.len() == 0 {
+                    return Err(Error::InvalidTlvSize);
+                }
+
+                binaries.push(value[1..].to_vec());
+            }
+            _ => return Err(Error::UnknownInnerTlv(tlv_type)),
+        }
+    }
+
+    Ok(InnerHeader {
+        inner_random_stream: try!(inner_random_stream.ok_or(Error::MissingInnerRandomStream)),
+        protected_stream_key: try!(protected_stream_key.ok_or(Error::MissingProtectedStreamKey)),
+        binaries: binaries,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use Error;
+    use header::InnerRandomStreamType;
+
+    use byteorder::{LittleEndian, WriteBytesExt};
+
+    #[test]
+    fn should_read_inner_header() {
+        let mut bytes = vec![];
+        bytes.write_u8(1).unwrap();
+        bytes.write_u32::<LittleEndian>(4).unwrap();
+        bytes.write_u32::<LittleEndian>(2).unwrap();
+
+        bytes.write_u8(2).unwrap();
+        bytes.write_u32::<LittleEndian>(32).unwrap();
+        bytes.extend([4; 32].iter().cloned());
+
+        bytes.write_u8(3).unwrap();
+        bytes.write_u32::<LittleEndian>(4).unwrap();
+        bytes.extend([1, 10, 20, 30].iter().cloned());
+
+        bytes.write_u8(0).unwrap();
+        bytes.write_u32::<LittleEndian>(0).unwrap();
+
+        bytes.write_u8(42).unwrap();
+
+        let reader = &mut &bytes[..];
+        let result = read_inner_header(reader).unwrap();
+
+        assert_eq!(result.inner_random_stream, InnerRandomStreamType::Salsa20);
+        assert_eq!(result.protected_stream_key, vec![4; 32]);
+
+        assert_eq!(result.binaries().len(), 1);
+        assert_eq!(result.binaries()[0], vec![10, 20, 30]);
+
+        assert_eq!(*reader, [42]);
+    }
+
+    #[test]
+    fn should_return_error_if_unknown_tlv() {
+        let mut bytes = vec![];
+        bytes.write_u8(4).unwrap();
+        bytes.write_u32::<LittleEndian>(0).unwrap();
+
+        let result = read_inner_header(&mut &bytes[..]);
+
+        match result {
+            Err(Error::UnknownInnerTlv(4)) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
+    #[test]
+    fn should_return_error_if_no_protected_stream_key() {
+        let mut bytes = vec![];
+        bytes.write_u8(1).unwrap();
+        bytes.write_u32::<LittleEndian>(4).unwrap();
+        bytes.write_u32::<LittleEndian>(2).unwrap();
+
+        bytes.write_u8(0).unwrap();
+        bytes.write_u32::<LittleEndian>(0).unwrap();
+
+        let result = read_inner_header(&mut &bytes[..]);
+
+        match result {
+            Err(Error::MissingProtectedStreamKey) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+}
@@ -1,6 +1,9 @@
-use Error;
+use argon2;
+use bytes;
+use {CompositeKey, Error};
 
-use util::sha256;
+use super::variant_dictionary::VariantDictionary;
+use util::{sha256, sha512};
 
 use crypto::aes;
 use crypto::aes::KeySize;
@@ -10,17 +13,107 @@ use crypto::symmetriccipher::Encryptor;
 
 use std::io::Read;
 
-pub fn key(transform_seed: &[u8; 32],
-           transform_rounds: u64,
-           master_seed: &[u8; 32],
-           passphrase: &str)
-           -> Result<[u8; 32], Error> {
-    let key = try!(transform_key(&composite_key(passphrase), transform_seed, transform_rounds));
-    Ok(make_master_key(&key, &master_seed))
+const KDF_UUID: &'static str = "$UUID";
+
+const AES_KDF_UUID_1: u64 = 0x60448A629AF3D9C9;
+const AES_KDF_UUID_2: u64 = 0xEA4F8AC1080D74BF;
+const AES_KDF_ROUNDS: &'static str = "R";
+const AES_KDF_SEED: &'static str = "S";
+
+const ARGON2D_UUID_1: u64 = 0x4B44298CDF6D63EF;
+const ARGON2D_UUID_2: u64 = 0x0C0AE303A4A9F791;
+const ARGON2ID_UUID_1: u64 = 0x0A4CDB60198B299E;
+const ARGON2ID_UUID_2: u64 = 0x8D3C9F1DF1C94D89;
+const ARGON2_SALT: &'static str = "S";
+const ARGON2_PARALLELISM: &'static str = "P";
+const ARGON2_MEMORY: &'static str = "M";
+const ARGON2_ITERATIONS: &'static str = "I";
+const ARGON2_VERSION: &'static str = "V";
+const ARGON2_SECRET_KEY: &'static str = "K";
+const ARGON2_ASSOCIATED_DATA: &'static str = "A";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Kdf {
+    Aes { seed: [u8; 32], rounds: u64 },
+    Argon2(argon2::Parameters),
+}
+
+impl Kdf {
+    pub fn from_parameters(parameters: &VariantDictionary) -> Result<Kdf, Error> {
+        let uuid = &mut try!(parameters.get_bytes(KDF_UUID).ok_or(Error::MissingKdfParameters));
+        let uuid1 = try!(bytes::read_u64(uuid));
+        let uuid2 = try!(bytes::read_u64(uuid));
+
+        match (uuid1, uuid2) {
+            (AES_KDF_UUID_1, AES_KDF_UUID_2) => aes_kdf(parameters),
+            (ARGON2D_UUID_1, ARGON2D_UUID_2) => argon2_kdf(parameters, argon2::Variant::Argon2d),
+            (ARGON2ID_UUID_1, ARGON2ID_UUID_2) => {
+                argon2_kdf(parameters, argon2::Variant::Argon2id)
+            }
+            _ => Err(Error::UnknownKdf(uuid1, uuid2)),
+        }
+    }
+
+    pub fn transform_key(&self, key: &[u8; 32]) -> Result<[u8; 32], Error> {
+        match *self {
+            Kdf::Aes { ref seed, rounds } => transform_key(key, seed, rounds),
+            Kdf::Argon2(ref parameters) => {
+                let result = try!(argon2::hash(parameters, key, 32));
+                read_array!(&mut &result[..], 32)
+            }
+        }
+    }
 }
 
-fn composite_key(passphrase: &str) -> [u8; 32] {
-    sha256(&sha256(&passphrase.as_bytes()))
+pub fn key(kdf: &Kdf, master_seed: &[u8; 32], key: &CompositeKey) -> Result<[u8; 32], Error> {
+    keys(kdf, master_seed, key).map(|(key, _)| key)
+}
+
+pub fn keys(kdf: &Kdf,
+            master_seed: &[u8; 32],
+            key: &CompositeKey)
+            -> Result<([u8; 32], [u8; 64]), Error> {
+    let response = try!(key.challenge(master_seed));
+    let key = try!(kdf.transform_key(&try!(key.hash())));
+    Ok((make_master_key(&key, &master_seed, &response), make_hmac_key(&key, &master_seed)))
+}
+
+fn aes_kdf(parameters: &VariantDictionary) -> Result<Kdf, Error> {
+    let rounds = try!(parameters.get_u64(AES_KDF_ROUNDS).ok_or(Error::MissingTransformRounds));
+    let seed = match parameters.get_bytes(AES_KDF_SEED) {
+        Some(seed) if seed.len() == 32 => try!(read_array!(&mut &seed[..], 32)),
+        _ => return Err(Error::MissingTransformSeed),
+    };
+
+    Ok(Kdf::Aes {
+        seed: seed,
+        rounds: rounds,
+    })
+}
+
+fn argon2_kdf(parameters: &VariantDictionary, variant: argon2::Variant) -> Result<Kdf, Error> {
+    let salt = try!(parameters.get_bytes(ARGON2_SALT).ok_or(Error::MissingKdfParameters));
+    let parallelism = try!(parameters.get_u32(ARGON2_PARALLELISM)
+                                     .ok_or(Error::MissingKdfParameters));
+    let memory = try!(parameters.get_u64(ARGON2_MEMORY).ok_or(Error::MissingKdfParameters));
+    let iterations = try!(parameters.get_u64(ARGON2_ITERATIONS)
+                                    .ok_or(Error::MissingKdfParameters));
+    let version = try!(parameters.get_u32(ARGON2_VERSION).ok_or(Error::MissingKdfParameters));
+
+    if memory / 1024 > argon2::MAX_MEMORY as u64 || iterations > u32::max_value() as u64 {
+        return Err(Error::InvalidKdfParameters);
+    }
+
+    Ok(Kdf::Argon2(argon2::Parameters {
+        variant: variant,
+        version: version,
+        memory: (memory / 1024) as u32,
+        iterations: iterations as u32,
+        parallelism: parallelism,
+        salt: salt.to_vec(),
+        secret: parameters.get_bytes(ARGON2_SECRET_KEY).unwrap_or(&[]).to_vec(),
+        associated_data: parameters.get_bytes(ARGON2_ASSOCIATED_DATA).unwrap_or(&[]).to_vec(),
+    }))
 }
 
 fn transform_key(key: &[u8; 32], seed: &[u8; 32], rounds: u64) -> Result<[u8; 32], Error> {
@@ -45,26 +138,64 @@ fn encrypt(key: &[u8; 32], seed: &[u8; 32]) -> Result<[u8; 32], Error> {
     read_array!(write_buffer.take_read_buffer().take_remaining(), 32)
 }
 
-fn make_master_key(key: &[u8; 32], master_seed: &[u8; 32]) -> [u8; 32] {
+fn make_master_key(key: &[u8; 32],
+                   master_seed: &[u8; 32],
+                   response: &Option<[u8; 32]>)
+                   -> [u8; 32] {
     let mut buffer = vec![];
     buffer.extend(master_seed.iter().cloned());
+    if let Some(ref response) = *response {
+        buffer.extend(response.iter().cloned());
+    }
     buffer.extend(key.iter().cloned());
 
     sha256(&buffer)
 }
 
+fn make_hmac_key(key: &[u8; 32], master_seed: &[u8; 32]) -> [u8; 64] {
+    let mut buffer = vec![];
+    buffer.extend(master_seed.iter().cloned());
+    buffer.extend(key.iter().cloned());
+    buffer.push(1);
+
+    sha512(&buffer)
+}
+
 #[cfg(test)]
 mod tests {
-    #[test]
-    fn should_generate_composite_key() {
-        let composite_key = super::composite_key("hunter2");
-        assert_eq!(&composite_key[0..8],
-                   &[0xa3, 0xe2, 0x7a, 0xb2, 0x94, 0x8b, 0x68, 0x0e]);
+    use super::Kdf;
+
+    use {ChallengeResponseProvider, CompositeKey, Error, HmacSha1Provider};
+    use header::variant_dictionary::{Variant, VariantDictionary};
+    use util::sha256;
+
+    fn composite_key() -> [u8; 32] {
+        CompositeKey::new().password("hunter2").hash().unwrap()
+    }
+
+    fn aes_kdf() -> Kdf {
+        Kdf::Aes {
+            seed: [1; 32],
+            rounds: 6000,
+        }
+    }
+
+    fn argon2id_parameters(extra: Vec<(&str, Variant)>) -> VariantDictionary {
+        let uuid = vec![0x9E, 0x29, 0x8B, 0x19, 0x60, 0xDB, 0x4C, 0x0A, 0x89, 0x4D, 0xC9, 0xF1,
+                        0x1D, 0x9F, 0x3C, 0x8D];
+        let parameters = vec![("$UUID", Variant::ByteArray(uuid)),
+                              ("S", Variant::ByteArray(vec![1; 32])),
+                              ("P", Variant::UInt32(2)),
+                              ("M", Variant::UInt64(65536)),
+                              ("I", Variant::UInt64(2)),
+                              ("V", Variant::UInt32(0x13))];
+
+        parameters.into_iter().chain(extra).map(|(key, value)| (key.to_string(), value)).collect()
     }
 
     #[test]
     fn should_transform_key() {
-        let composite_key = super::composite_key("hunter2");
+        let composite_key = composite_key();
         let seed = [1; 32];
         let transformed_key = super::transform_key(&composite_key, &seed, 6000).unwrap();
 
@@ -72,10 +203,99 @@ mod tests {
                    &[0xf3, 0x62, 0x30, 0x40, 0x15, 0xd8, 0xd1, 0x69]);
     }
 
+    #[test]
+    fn should_transform_key_with_argon2id() {
+        let kdf = Kdf::from_parameters(&argon2id_parameters(vec![])).unwrap();
+        let transformed_key = kdf.transform_key(&composite_key()).unwrap();
+
+        assert_eq!(&transformed_key[0..8],
+                   &[0x58, 0xcc, 0xcf, 0x38, 0xbf, 0x1b, 0x4e, 0x5c]);
+    }
+
+    #[test]
+    fn should_read_aes_kdf_parameters() {
+        let uuid = vec![0xC9, 0xD9, 0xF3, 0x9A, 0x62, 0x8A, 0x44, 0x60, 0xBF, 0x74, 0x0D, 0x08,
+                        0xC1, 0x8A, 0x4F, 0xEA];
+        let parameters: VariantDictionary = vec![("$UUID".to_string(), Variant::ByteArray(uuid)),
+                                                 ("R".to_string(), Variant::UInt64(6000)),
+                                                 ("S".to_string(), Variant::ByteArray(vec![1; 32]))]
+                                                .into_iter()
+                                                .collect();
+
+        let result = Kdf::from_parameters(&parameters).unwrap();
+
+        assert_eq!(result, aes_kdf());
+    }
+
+    #[test]
+    fn should_return_error_if_missing_argon2_parameter() {
+        let parameters = argon2id_parameters(vec![("P", Variant::UInt64(2))]);
+
+        let result = Kdf::from_parameters(&parameters);
+
+        match result {
+            Err(Error::MissingKdfParameters) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
+    #[test]
+    fn should_return_error_if_argon2_memory_too_large() {
+        let parameters = argon2id_parameters(vec![("M", Variant::UInt64(1 << 42))]);
+
+        let result = Kdf::from_parameters(&parameters);
+
+        match result {
+            Err(Error::InvalidKdfParameters) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
     #[test]
     fn should_generate_master_key() {
-        let master_key = super::key(&[1; 32], 6000, &[2; 32], "hunter2").unwrap();
+        let key = CompositeKey::new().password("hunter2");
+        let master_key = super::key(&aes_kdf(), &[2; 32], &key).unwrap();
         assert_eq!(&master_key[0..8],
                    &[0x4e, 0x39, 0xfb, 0xa3, 0xda, 0xd7, 0xc4, 0xde]);
     }
+
+    #[test]
+    fn should_generate_hmac_key() {
+        let key = CompositeKey::new().password("hunter2");
+        let (master_key, hmac_key) = super::keys(&aes_kdf(), &[2; 32], &key).unwrap();
+        assert_eq!(&master_key[0..8],
+                   &[0x4e, 0x39, 0xfb, 0xa3, 0xda, 0xd7, 0xc4, 0xde]);
+        assert_eq!(&hmac_key[0..8],
+                   &[0x32, 0x42, 0x54, 0x00, 0x12, 0x84, 0xc8, 0xb4]);
+    }
+
+    #[test]
+    fn should_mix_challenge_response_into_master_key() {
+        let key = CompositeKey::new()
+                      .password("hunter2")
+                      .challenge_response(HmacSha1Provider::new(b"secret"));
+        let master_key = super::key(&aes_kdf(), &[2; 32], &key).unwrap();
+
+        let response = HmacSha1Provider::new(b"secret").challenge(&[2; 32]).unwrap();
+        let transformed_key = aes_kdf().transform_key(&composite_key()).unwrap();
+
+        let mut expected = vec![2; 32];
+        expected.extend(sha256(&response).iter().cloned());
+        expected.extend(transformed_key.iter().cloned());
+
+        assert_eq!(master_key, sha256(&expected));
+    }
+
+    #[test]
+    fn should_not_mix_challenge_response_into_hmac_key() {
+        let key = CompositeKey::new()
+                      .password("hunter2")
+                      .challenge_response(HmacSha1Provider::new(b"secret"));
+        let (_, hmac_key) = super::keys(&aes_kdf(), &[2; 32], &key).unwrap();
+
+        let key = CompositeKey::new().password("hunter2");
+        let (_, expected) = super::keys(&aes_kdf(), &[2; 32], &key).unwrap();
+
+        assert_eq!(&hmac_key[..], &expected[..]);
+    }
 }
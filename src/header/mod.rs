@@ -1,32 +1,48 @@
 mod builder;
+mod inner;
 mod master_key;
 mod tlv;
+mod variant_dictionary;
 
 use bytes;
+use compress;
 use decompress;
-use {Error, FileType};
+use util;
+use {CompositeKey, Error, FileType};
+use database::{DEFAULT_TRANSFORM_ROUNDS, KDBX_VERSION};
 
 use self::builder::HeaderBuilder;
+use self::tlv::Tlv;
 use protected::ProtectedStream;
+use read;
+use write;
 
-use std::io::Read;
+use std::io::{Read, Write};
 
-#[derive(Debug, PartialEq)]
-enum CipherType {
+pub use self::inner::read_inner_header;
+pub use self::master_key::Kdf;
+
+const MAX_MAJOR_VERSION: u16 = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CipherType {
     Aes,
+    Twofish,
+    ChaCha20,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 enum CompressionType {
     None,
     Gzip,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 enum InnerRandomStreamType {
     None,
     Rc4,
     Salsa20,
+    ChaCha20,
 }
 
 #[derive(Debug)]
@@ -35,28 +51,116 @@ pub struct Header {
     cipher: CipherType,
     compression: CompressionType,
     master_seed: [u8; 32],
-    transform_seed: [u8; 32],
-    transform_rounds: u64,
-    encryption_iv: [u8; 16],
-    protected_stream_key: [u8; 32],
-    stream_start_bytes: [u8; 32],
-    inner_random_stream: InnerRandomStreamType,
+    kdf: Kdf,
+    encryption_iv: Vec<u8>,
+    protected_stream_key: Option<[u8; 32]>,
+    stream_start_bytes: Option<[u8; 32]>,
+    inner_random_stream: Option<InnerRandomStreamType>,
+}
+
+impl CipherType {
+    fn iv_length(&self) -> usize {
+        match *self {
+            CipherType::Aes => 16,
+            CipherType::Twofish => 16,
+            CipherType::ChaCha20 => 12,
+        }
+    }
+}
+
+impl InnerRandomStreamType {
+    fn protected_stream(&self, key: &[u8]) -> Box<ProtectedStream> {
+        match *self {
+            InnerRandomStreamType::None => ProtectedStream::none(),
+            InnerRandomStreamType::Rc4 => ProtectedStream::rc4(key),
+            InnerRandomStreamType::Salsa20 => ProtectedStream::salsa20(key),
+            InnerRandomStreamType::ChaCha20 => ProtectedStream::chacha20(key),
+        }
+    }
 }
 
 impl Header {
-    pub fn master_key(&self, passphrase: &str) -> Result<[u8; 32], Error> {
-        master_key::key(&self.transform_seed,
-                        self.transform_rounds,
-                        &self.master_seed,
-                        passphrase)
+    pub fn generate(cipher: CipherType, transform_rounds: u64) -> Result<Header, Error> {
+        let mut header = Header {
+            version: KDBX_VERSION,
+            cipher: cipher,
+            compression: CompressionType::Gzip,
+            master_seed: [0; 32],
+            kdf: Kdf::Aes {
+                seed: [0; 32],
+                rounds: DEFAULT_TRANSFORM_ROUNDS,
+            },
+            encryption_iv: vec![0; cipher.iv_length()],
+            protected_stream_key: None,
+            stream_start_bytes: None,
+            inner_random_stream: Some(InnerRandomStreamType::Salsa20),
+        };
+
+        let mut transform_seed = [0; 32];
+        let mut protected_stream_key = [0; 32];
+        let mut stream_start_bytes = [0; 32];
+
+        try!(util::random_bytes(&mut header.master_seed));
+        try!(util::random_bytes(&mut transform_seed));
+        try!(util::random_bytes(&mut header.encryption_iv));
+        try!(util::random_bytes(&mut protected_stream_key));
+        try!(util::random_bytes(&mut stream_start_bytes));
+
+        header.kdf = Kdf::Aes {
+            seed: transform_seed,
+            rounds: transform_rounds,
+        };
+        header.protected_stream_key = Some(protected_stream_key);
+        header.stream_start_bytes = Some(stream_start_bytes);
+
+        Ok(header)
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn cipher(&self) -> CipherType {
+        self.cipher
+    }
+
+    pub fn transform_rounds(&self) -> Option<u64> {
+        match self.kdf {
+            Kdf::Aes { rounds, .. } => Some(rounds),
+            Kdf::Argon2(_) => None,
+        }
+    }
+
+    pub fn major_version(&self) -> u16 {
+        major_version(self.version)
     }
 
-    pub fn encryption_iv(&self) -> [u8; 16] {
-        self.encryption_iv
+    pub fn master_key(&self, key: &CompositeKey) -> Result<[u8; 32], Error> {
+        master_key::key(&self.kdf, &self.master_seed, key)
     }
 
-    pub fn stream_start_bytes(&self) -> [u8; 32] {
-        self.stream_start_bytes
+    pub fn keys(&self, key: &CompositeKey) -> Result<([u8; 32], [u8; 64]), Error> {
+        master_key::keys(&self.kdf, &self.master_seed, key)
+    }
+
+    pub fn stream_start_bytes(&self) -> Result<[u8; 32], Error> {
+        self.stream_start_bytes.ok_or(Error::MissingStreamStartBytes)
+    }
+
+    pub fn decrypt(&self, reader: &mut Read, key: &[u8; 32]) -> Result<Vec<u8>, Error> {
+        match self.cipher {
+            CipherType::Aes => read::aes::decrypt(reader, key, &self.encryption_iv),
+            CipherType::Twofish => read::twofish::decrypt(reader, key, &self.encryption_iv),
+            CipherType::ChaCha20 => read::chacha20::decrypt(reader, key, &self.encryption_iv),
+        }
+    }
+
+    pub fn encrypt(&self, data: &[u8], key: &[u8; 32]) -> Result<Vec<u8>, Error> {
+        match self.cipher {
+            CipherType::Aes => write::aes::encrypt(data, key, &self.encryption_iv),
+            CipherType::Twofish => write::twofish::encrypt(data, key, &self.encryption_iv),
+            CipherType::ChaCha20 => write::chacha20::encrypt(data, key, &self.encryption_iv),
+        }
     }
 
     pub fn decompress(&self, read: Box<Read>) -> Result<Box<Read>, Error> {
@@ -66,22 +170,54 @@ impl Header {
         }
     }
 
-    pub fn protected_stream(&self) -> Box<ProtectedStream> {
-        match self.inner_random_stream {
-            InnerRandomStreamType::None => ProtectedStream::none(),
-            InnerRandomStreamType::Rc4 => ProtectedStream::rc4(&self.protected_stream_key),
-            InnerRandomStreamType::Salsa20 => ProtectedStream::salsa20(&self.protected_stream_key),
+    pub fn compress(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        match self.compression {
+            CompressionType::None => compress::none(data),
+            CompressionType::Gzip => compress::gzip(data),
         }
     }
+
+    pub fn protected_stream(&self) -> Result<Box<ProtectedStream>, Error> {
+        let key = try!(self.protected_stream_key.ok_or(Error::MissingProtectedStreamKey));
+        let stream = try!(self.inner_random_stream.as_ref().ok_or(Error::MissingInnerRandomStream));
+
+        Ok(stream.protected_stream(&key))
+    }
 }
 
 pub fn read_header(file_type: FileType, reader: &mut Read) -> Result<Header, Error> {
     try!(check_file_type(file_type));
 
     let version = try!(read_version(reader));
+    try!(check_version(version));
+
     handle_tlvs(reader, version)
 }
 
+pub fn write_header(writer: &mut Write, header: &Header) -> Result<(), Error> {
+    try!(write_version(writer, header.version));
+
+    let (transform_seed, transform_rounds) = match header.kdf {
+        Kdf::Aes { seed, rounds } => (seed, rounds),
+        Kdf::Argon2(_) => return Err(Error::UnsupportedWriteVersion(header.version)),
+    };
+
+    let tlvs = vec![Tlv::Cipher(header.cipher.clone()),
+                    Tlv::Compression(header.compression.clone()),
+                    Tlv::MasterSeed(header.master_seed),
+                    Tlv::TransformSeed(transform_seed),
+                    Tlv::TransformRounds(transform_rounds),
+                    Tlv::EncryptionIv(header.encryption_iv.clone()),
+                    Tlv::ProtectedStreamKey(try!(header.protected_stream_key
+                                                     .ok_or(Error::MissingProtectedStreamKey))),
+                    Tlv::StreamStartBytes(try!(header.stream_start_bytes())),
+                    Tlv::InnerRandomStream(try!(header.inner_random_stream
+                                                    .clone()
+                                                    .ok_or(Error::MissingInnerRandomStream)))];
+
+    tlv::write_tlvs(writer, &tlvs)
+}
+
 fn check_file_type(file_type: FileType) -> Result<(), Error> {
     match file_type {
         FileType::KeePass2 => Ok(()),
@@ -89,14 +225,30 @@ fn check_file_type(file_type: FileType) -> Result<(), Error> {
     }
 }
 
+fn check_version(version: u32) -> Result<(), Error> {
+    if major_version(version) <= MAX_MAJOR_VERSION {
+        Ok(())
+    } else {
+        Err(Error::UnsupportedVersion(version))
+    }
+}
+
+fn major_version(version: u32) -> u16 {
+    (version >> 16) as u16
+}
+
 fn read_version(reader: &mut Read) -> Result<u32, Error> {
     bytes::read_u32(reader)
 }
 
+fn write_version(writer: &mut Write, version: u32) -> Result<(), Error> {
+    bytes::write_u32(writer, version)
+}
+
 fn handle_tlvs(reader: &mut Read, version: u32) -> Result<Header, Error> {
     let mut builder = HeaderBuilder::new(version);
 
-    for tlv in tlv::tlvs(reader) {
+    for tlv in tlv::tlvs(reader, version) {
         match tlv {
             Ok(t) => builder.apply(t),
             Err(e) => return Err(e),
@@ -108,7 +260,7 @@ fn handle_tlvs(reader: &mut Read, version: u32) -> Result<Header, Error> {
 
 #[cfg(test)]
 mod test {
-    use {Error, FileType};
+    use {CipherType, Error, FileType};
 
     #[test]
     pub fn should_return_error_if_wrong_file_type() {
@@ -119,4 +271,35 @@ mod test {
             _ => panic!("Invalid result: {:#?}", result),
         }
     }
+
+    #[test]
+    pub fn should_return_error_if_unsupported_version() {
+        let bytes = vec![0x00, 0x00, 0x05, 0x00];
+        let result = super::read_header(FileType::KeePass2, &mut &bytes[..]);
+
+        match result {
+            Err(Error::UnsupportedVersion(0x00050000)) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
+    #[test]
+    pub fn should_write_and_read_header() {
+        let header = super::Header::generate(CipherType::Twofish, 60000).unwrap();
+
+        let mut bytes = vec![];
+        super::write_header(&mut bytes, &header).unwrap();
+
+        let result = super::read_header(FileType::KeePass2, &mut &bytes[..]).unwrap();
+
+        assert_eq!(result.version, header.version);
+        assert_eq!(result.cipher, header.cipher);
+        assert_eq!(result.compression, header.compression);
+        assert_eq!(result.master_seed, header.master_seed);
+        assert_eq!(result.kdf, header.kdf);
+        assert_eq!(result.encryption_iv, header.encryption_iv);
+        assert_eq!(result.protected_stream_key, header.protected_stream_key);
+        assert_eq!(result.stream_start_bytes, header.stream_start_bytes);
+        assert_eq!(result.inner_random_stream, header.inner_random_stream);
+    }
 }
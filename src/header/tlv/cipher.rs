@@ -4,12 +4,16 @@ use Error;
 use header::CipherType;
 use header::tlv::Tlv;
 
-use std::io::Read;
+use std::io::{Read, Write};
 
 const AES_UUID_1: u64 = 0x504371BFE6F2C131;
 const AES_UUID_2: u64 = 0xFF5AFC6A210558BE;
+const TWOFISH_UUID_1: u64 = 0xB94B6F579FF268AD;
+const TWOFISH_UUID_2: u64 = 0x6C3465F97AD46AA3;
+const CHACHA20_UUID_1: u64 = 0xB54C6F8B2B8A03D6;
+const CHACHA20_UUID_2: u64 = 0x9AB5DB319A3324A5;
 
-pub fn read_tlv(reader: &mut Read, length: u16) -> Result<Tlv, Error> {
+pub fn read_tlv(reader: &mut Read, length: u32) -> Result<Tlv, Error> {
     try!(super::check_tlv_length(length, 16));
 
     let uuid1 = try!(bytes::read_u64(reader));
@@ -22,10 +26,27 @@ pub fn read_tlv(reader: &mut Read, length: u16) -> Result<Tlv, Error> {
 fn match_cipher_type(uuid1: u64, uuid2: u64) -> Result<CipherType, Error> {
     match (uuid1, uuid2) {
         (AES_UUID_1, AES_UUID_2) => Ok(CipherType::Aes),
+        (TWOFISH_UUID_1, TWOFISH_UUID_2) => Ok(CipherType::Twofish),
+        (CHACHA20_UUID_1, CHACHA20_UUID_2) => Ok(CipherType::ChaCha20),
         _ => Err(Error::UnknownCipherType(uuid1, uuid2)),
     }
 }
 
+fn cipher_type_uuid(cipher_type: &CipherType) -> (u64, u64) {
+    match *cipher_type {
+        CipherType::Aes => (AES_UUID_1, AES_UUID_2),
+        CipherType::Twofish => (TWOFISH_UUID_1, TWOFISH_UUID_2),
+        CipherType::ChaCha20 => (CHACHA20_UUID_1, CHACHA20_UUID_2),
+    }
+}
+
+pub fn write_tlv(writer: &mut Write, cipher_type: &CipherType) -> Result<(), Error> {
+    let (uuid1, uuid2) = cipher_type_uuid(cipher_type);
+
+    try!(bytes::write_u64(writer, uuid1));
+    bytes::write_u64(writer, uuid2)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -50,6 +71,32 @@ mod test {
         }
     }
 
+    #[test]
+    fn should_read_twofish() {
+        let bytes = vec![0xAD, 0x68, 0xF2, 0x9F, 0x57, 0x6F, 0x4B, 0xB9, 0xA3, 0x6A, 0xD4, 0x7A,
+                         0xF9, 0x65, 0x34, 0x6C];
+
+        let result = read_tlv(&mut &bytes[..], 16);
+
+        match result {
+            Ok(Tlv::Cipher(CipherType::Twofish)) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
+    #[test]
+    fn should_read_chacha20() {
+        let bytes = vec![0xD6, 0x03, 0x8A, 0x2B, 0x8B, 0x6F, 0x4C, 0xB5, 0xA5, 0x24, 0x33, 0x9A,
+                         0x31, 0xDB, 0xB5, 0x9A];
+
+        let result = read_tlv(&mut &bytes[..], 16);
+
+        match result {
+            Ok(Tlv::Cipher(CipherType::ChaCha20)) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
     #[test]
     fn should_return_error_if_wrong_length() {
         let bytes = vec![];
@@ -81,4 +128,17 @@ mod test {
             _ => panic!("Invalid result: {:#?}", result),
         }
     }
+
+    #[test]
+    fn should_write_tlv() {
+        let mut bytes = vec![];
+        write_tlv(&mut bytes, &CipherType::Aes).unwrap();
+
+        let result = read_tlv(&mut &bytes[..], 16);
+
+        match result {
+            Ok(Tlv::Cipher(CipherType::Aes)) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
 }
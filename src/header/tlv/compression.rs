@@ -4,9 +4,9 @@ use Error;
 use header::CompressionType;
 use header::tlv::Tlv;
 
-use std::io::Read;
+use std::io::{Read, Write};
 
-pub fn read_tlv(reader: &mut Read, length: u16) -> Result<Tlv, Error> {
+pub fn read_tlv(reader: &mut Read, length: u32) -> Result<Tlv, Error> {
     try!(super::check_tlv_length(length, 4));
 
     let flags = try!(bytes::read_u32(reader));
@@ -23,6 +23,15 @@ fn match_compression_flags(flags: u32) -> Result<CompressionType, Error> {
     }
 }
 
+pub fn write_tlv(writer: &mut Write, compression_type: &CompressionType) -> Result<(), Error> {
+    let flags = match *compression_type {
+        CompressionType::None => 0,
+        CompressionType::Gzip => 1,
+    };
+
+    bytes::write_u32(writer, flags)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -70,4 +79,12 @@ mod test {
             _ => panic!("Invalid result: {:#?}", result),
         }
     }
+
+    #[test]
+    fn should_write_tlv() {
+        let mut bytes = vec![];
+        write_tlv(&mut bytes, &CompressionType::Gzip).unwrap();
+
+        assert_eq!(bytes, vec![1, 0, 0, 0]);
+    }
 }
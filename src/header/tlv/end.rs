@@ -1,14 +1,20 @@
+use bytes;
 use Error;
 
 use header::tlv::Tlv;
 
-use std::io::Read;
+use std::io::{Read, Write};
 
 const END_OF_HEADER_LENGTH: usize = 4;
+const END_OF_HEADER: [u8; END_OF_HEADER_LENGTH] = [0x0D, 0x0A, 0x0D, 0x0A];
 
-pub fn read_tlv(reader: &mut Read, length: u16) -> Result<Tlv, Error> {
-    try!(super::check_tlv_length(length, END_OF_HEADER_LENGTH as u16));
+pub fn read_tlv(reader: &mut Read, length: u32) -> Result<Tlv, Error> {
+    try!(super::check_tlv_length(length, END_OF_HEADER_LENGTH as u32));
     try!(read_array!(reader, END_OF_HEADER_LENGTH));
 
     Ok(Tlv::EndOfHeader)
 }
+
+pub fn write_tlv(writer: &mut Write) -> Result<(), Error> {
+    bytes::write_all(writer, &END_OF_HEADER)
+}
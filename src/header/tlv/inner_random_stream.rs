@@ -4,9 +4,9 @@ use Error;
 use header::InnerRandomStreamType;
 use header::tlv::Tlv;
 
-use std::io::Read;
+use std::io::{Read, Write};
 
-pub fn read_tlv(reader: &mut Read, length: u16) -> Result<Tlv, Error> {
+pub fn read_tlv(reader: &mut Read, length: u32) -> Result<Tlv, Error> {
     try!(super::check_tlv_length(length, 4));
 
     let stream_id = try!(bytes::read_u32(reader));
@@ -15,15 +15,27 @@ pub fn read_tlv(reader: &mut Read, length: u16) -> Result<Tlv, Error> {
     Ok(Tlv::InnerRandomStream(stream_type))
 }
 
-fn match_stream_id(stream_id: u32) -> Result<InnerRandomStreamType, Error> {
+pub fn match_stream_id(stream_id: u32) -> Result<InnerRandomStreamType, Error> {
     match stream_id {
         0 => Ok(InnerRandomStreamType::None),
         1 => Ok(InnerRandomStreamType::Rc4),
         2 => Ok(InnerRandomStreamType::Salsa20),
+        3 => Ok(InnerRandomStreamType::ChaCha20),
         _ => Err(Error::UnknownInnerRandomStreamType(stream_id)),
     }
 }
 
+pub fn write_tlv(writer: &mut Write, stream_type: &InnerRandomStreamType) -> Result<(), Error> {
+    let stream_id = match *stream_type {
+        InnerRandomStreamType::None => 0,
+        InnerRandomStreamType::Rc4 => 1,
+        InnerRandomStreamType::Salsa20 => 2,
+        InnerRandomStreamType::ChaCha20 => 3,
+    };
+
+    bytes::write_u32(writer, stream_id)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -62,13 +74,21 @@ mod test {
     #[test]
     fn should_return_error_if_unknown_compression_type() {
         let mut bytes = vec![];
-        bytes.write_u32::<LittleEndian>(3).unwrap();
+        bytes.write_u32::<LittleEndian>(4).unwrap();
 
         let result = read_tlv(&mut &bytes[..], 4);
 
         match result {
-            Err(Error::UnknownInnerRandomStreamType(3)) => (),
+            Err(Error::UnknownInnerRandomStreamType(4)) => (),
             _ => panic!("Invalid result: {:#?}", result),
         }
     }
+
+    #[test]
+    fn should_write_tlv() {
+        let mut bytes = vec![];
+        write_tlv(&mut bytes, &InnerRandomStreamType::Salsa20).unwrap();
+
+        assert_eq!(bytes, vec![2, 0, 0, 0]);
+    }
 }
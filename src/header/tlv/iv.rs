@@ -1,18 +1,26 @@
+use bytes;
 use Error;
 
 use header::tlv::Tlv;
 
-use std::io::Read;
+use std::io::{Read, Write};
 
-const IV_LENGTH: usize = 16;
+const AES_IV_LENGTH: u32 = 16;
+const CHACHA20_IV_LENGTH: u32 = 12;
 
-pub fn read_tlv(reader: &mut Read, length: u16) -> Result<Tlv, Error> {
-    try!(super::check_tlv_length(length, IV_LENGTH as u16));
+pub fn read_tlv(reader: &mut Read, length: u32) -> Result<Tlv, Error> {
+    if length != CHACHA20_IV_LENGTH {
+        try!(super::check_tlv_length(length, AES_IV_LENGTH));
+    }
 
-    let iv = try!(read_array!(reader, IV_LENGTH));
+    let iv = try!(bytes::read_bytes(reader, length as usize));
     Ok(Tlv::EncryptionIv(iv))
 }
 
+pub fn write_tlv(writer: &mut Write, iv: &[u8]) -> Result<(), Error> {
+    bytes::write_all(writer, iv)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -31,6 +39,17 @@ mod test {
         }
     }
 
+    #[test]
+    fn should_read_chacha20_tlv() {
+        let bytes = [1; 12];
+        let result = read_tlv(&mut &bytes[..], 12);
+
+        match result {
+            Ok(Tlv::EncryptionIv(iv)) => assert_eq!(iv, bytes),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
     #[test]
     fn should_return_error_if_wrong_length() {
         let bytes = vec![];
@@ -41,4 +60,12 @@ mod test {
             _ => panic!("Invalid result: {:#?}", result),
         }
     }
+
+    #[test]
+    fn should_write_tlv() {
+        let mut bytes = vec![];
+        write_tlv(&mut bytes, &[1; 16]).unwrap();
+
+        assert_eq!(bytes, vec![1; 16]);
+    }
 }
@@ -0,0 +1,57 @@
+use bytes;
+use Error;
+
+use header::tlv::Tlv;
+use header::variant_dictionary;
+use header::variant_dictionary::VariantDictionary;
+
+use std::io::{Read, Write};
+
+pub fn read_tlv(reader: &mut Read, length: u32) -> Result<Tlv, Error> {
+    let value = try!(bytes::read_bytes(reader, length as usize));
+    let parameters = try!(variant_dictionary::read(&mut &value[..]));
+
+    Ok(Tlv::KdfParameters(parameters))
+}
+
+pub fn write_tlv(writer: &mut Write, parameters: &VariantDictionary) -> Result<(), Error> {
+    variant_dictionary::write(writer, parameters)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use Error;
+    use header::tlv::Tlv;
+    use header::variant_dictionary::{Variant, VariantDictionary};
+
+    #[test]
+    fn should_read_tlv() {
+        let parameters: VariantDictionary = vec![("R".to_string(), Variant::UInt64(6000))]
+                                                .into_iter()
+                                                .collect();
+
+        let mut bytes = vec![];
+        write_tlv(&mut bytes, &parameters).unwrap();
+
+        let result = read_tlv(&mut &bytes[..], bytes.len() as u32);
+
+        match result {
+            Ok(Tlv::KdfParameters(p)) => assert_eq!(p.get_u64("R"), Some(6000)),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
+    #[test]
+    fn should_return_error_if_too_short() {
+        let bytes = vec![0x00, 0x01];
+
+        let result = read_tlv(&mut &bytes[..], 3);
+
+        match result {
+            Err(Error::UnexpectedEOF) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+}
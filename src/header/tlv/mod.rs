@@ -3,8 +3,10 @@ mod compression;
 mod end;
 mod inner_random_stream;
 mod iv;
+mod kdf_parameters;
 mod master_seed;
 mod protected_stream_key;
+mod public_custom_data;
 mod stream_start_bytes;
 mod transform_rounds;
 mod transform_seed;
@@ -13,8 +15,11 @@ use bytes;
 use Error;
 
 use header::{CipherType, CompressionType, InnerRandomStreamType};
+use header::variant_dictionary::VariantDictionary;
 
-use std::io::Read;
+use std::io::{Read, Write};
+
+pub use self::inner_random_stream::match_stream_id;
 
 #[derive(Debug)]
 pub enum Tlv {
@@ -24,20 +29,24 @@ pub enum Tlv {
     MasterSeed([u8; 32]),
     TransformSeed([u8; 32]),
     TransformRounds(u64),
-    EncryptionIv([u8; 16]),
+    EncryptionIv(Vec<u8>),
     ProtectedStreamKey([u8; 32]),
     StreamStartBytes([u8; 32]),
     InnerRandomStream(InnerRandomStreamType),
+    KdfParameters(VariantDictionary),
+    PublicCustomData(VariantDictionary),
 }
 
 pub struct HeaderReader<'a> {
     reader: &'a mut Read,
+    version: u32,
     errored: bool,
 }
 
-pub fn tlvs<'a>(reader: &'a mut Read) -> HeaderReader<'a> {
+pub fn tlvs<'a>(reader: &'a mut Read, version: u32) -> HeaderReader<'a> {
     HeaderReader {
         reader: reader,
+        version: version,
         errored: false,
     }
 }
@@ -49,7 +58,7 @@ impl <'a> Iterator for HeaderReader<'a> {
         if self.errored {
             None
         } else {
-            let result = read_type_length(self.reader).and_then(|(tlv_type, length)| {
+            let result = read_type_length(self.reader, self.version).and_then(|(tlv_type, length)| {
                 read_tlv(self.reader, tlv_type, length)
             });
 
@@ -65,14 +74,18 @@ impl <'a> Iterator for HeaderReader<'a> {
     }
 }
 
-fn read_type_length(reader: &mut Read) -> Result<(u8, u16), Error> {
+fn read_type_length(reader: &mut Read, version: u32) -> Result<(u8, u32), Error> {
     let tlv_type = try!(bytes::read_u8(reader));
-    let length = try!(bytes::read_u16(reader));
+    let length = if super::major_version(version) >= 4 {
+        try!(bytes::read_u32(reader))
+    } else {
+        try!(bytes::read_u16(reader)) as u32
+    };
 
     Ok((tlv_type, length))
 }
 
-fn read_tlv(reader: &mut Read, tlv_type: u8, length: u16) -> Result<Tlv, Error> {
+fn read_tlv(reader: &mut Read, tlv_type: u8, length: u32) -> Result<Tlv, Error> {
     match tlv_type {
         0 => end::read_tlv(reader, length),
         2 => cipher::read_tlv(reader, length),
@@ -84,11 +97,64 @@ fn read_tlv(reader: &mut Read, tlv_type: u8, length: u16) -> Result<Tlv, Error>
         8 => protected_stream_key::read_tlv(reader, length),
         9 => stream_start_bytes::read_tlv(reader, length),
         10 => inner_random_stream::read_tlv(reader, length),
+        11 => kdf_parameters::read_tlv(reader, length),
+        12 => public_custom_data::read_tlv(reader, length),
         _ => Err(Error::UnknownTlv(tlv_type)),
     }
 }
 
-fn check_tlv_length(length: u16, expected: u16) -> Result<(), Error> {
+pub fn write_tlvs(writer: &mut Write, tlvs: &[Tlv]) -> Result<(), Error> {
+    for tlv in tlvs {
+        try!(write_tlv(writer, tlv));
+    }
+
+    write_tlv(writer, &Tlv::EndOfHeader)
+}
+
+fn write_tlv(writer: &mut Write, tlv: &Tlv) -> Result<(), Error> {
+    let mut value = vec![];
+    try!(write_value(&mut value, tlv));
+
+    try!(bytes::write_u8(writer, tlv_type(tlv)));
+    try!(bytes::write_u16(writer, value.len() as u16));
+    bytes::write_all(writer, &value)
+}
+
+fn tlv_type(tlv: &Tlv) -> u8 {
+    match *tlv {
+        Tlv::EndOfHeader => 0,
+        Tlv::Cipher(_) => 2,
+        Tlv::Compression(_) => 3,
+        Tlv::MasterSeed(_) => 4,
+        Tlv::TransformSeed(_) => 5,
+        Tlv::TransformRounds(_) => 6,
+        Tlv::EncryptionIv(_) => 7,
+        Tlv::ProtectedStreamKey(_) => 8,
+        Tlv::StreamStartBytes(_) => 9,
+        Tlv::InnerRandomStream(_) => 10,
+        Tlv::KdfParameters(_) => 11,
+        Tlv::PublicCustomData(_) => 12,
+    }
+}
+
+fn write_value(writer: &mut Write, tlv: &Tlv) -> Result<(), Error> {
+    match *tlv {
+        Tlv::EndOfHeader => end::write_tlv(writer),
+        Tlv::Cipher(ref cipher) => cipher::write_tlv(writer, cipher),
+        Tlv::Compression(ref compression) => compression::write_tlv(writer, compression),
+        Tlv::MasterSeed(ref seed) => master_seed::write_tlv(writer, seed),
+        Tlv::TransformSeed(ref seed) => transform_seed::write_tlv(writer, seed),
+        Tlv::TransformRounds(rounds) => transform_rounds::write_tlv(writer, rounds),
+        Tlv::EncryptionIv(ref iv) => iv::write_tlv(writer, iv),
+        Tlv::ProtectedStreamKey(ref key) => protected_stream_key::write_tlv(writer, key),
+        Tlv::StreamStartBytes(ref bytes) => stream_start_bytes::write_tlv(writer, bytes),
+        Tlv::InnerRandomStream(ref stream) => inner_random_stream::write_tlv(writer, stream),
+        Tlv::KdfParameters(ref parameters) => kdf_parameters::write_tlv(writer, parameters),
+        Tlv::PublicCustomData(ref data) => public_custom_data::write_tlv(writer, data),
+    }
+}
+
+pub fn check_tlv_length(length: u32, expected: u32) -> Result<(), Error> {
     if length == expected {
         Ok(())
     } else {
@@ -109,12 +175,22 @@ mod test {
     pub fn should_read_type_and_length() {
         let bytes = vec![10, 20, 30];
 
-        let (tlv_type, length) = super::read_type_length(&mut &bytes[..]).unwrap();
+        let (tlv_type, length) = super::read_type_length(&mut &bytes[..], 0x00030001).unwrap();
 
         assert_eq!(tlv_type, 10);
         assert_eq!(length, 0x1E14);
     }
 
+    #[test]
+    pub fn should_read_type_and_length_for_kdbx4() {
+        let bytes = vec![10, 20, 30, 40, 50];
+
+        let (tlv_type, length) = super::read_type_length(&mut &bytes[..], 0x00040000).unwrap();
+
+        assert_eq!(tlv_type, 10);
+        assert_eq!(length, 0x32281E14);
+    }
+
     #[test]
     pub fn should_iterate_through_tlvs() {
         let mut bytes = vec![];
@@ -133,7 +209,7 @@ mod test {
 
         let reader = &mut &bytes[..];
 
-        let tlvs = super::tlvs(reader).collect::<Vec<_>>();
+        let tlvs = super::tlvs(reader, 0x00030001).collect::<Vec<_>>();
         assert_eq!(tlvs.len(), 2);
 
         match tlvs[0] {
@@ -146,4 +222,29 @@ mod test {
             _ => panic!("Invalid result: {:#?}", tlvs[0]),
         }
     }
+
+    #[test]
+    pub fn should_write_tlvs() {
+        let tlvs = vec![Tlv::Compression(CompressionType::Gzip), Tlv::Cipher(CipherType::Aes)];
+
+        let mut bytes = vec![];
+        super::write_tlvs(&mut bytes, &tlvs).unwrap();
+
+        let reader = &mut &bytes[..];
+
+        let result = super::tlvs(reader, 0x00030001).collect::<Vec<_>>();
+        assert_eq!(result.len(), 2);
+
+        match result[0] {
+            Ok(Tlv::Compression(CompressionType::Gzip)) => (),
+            _ => panic!("Invalid result: {:#?}", result[0]),
+        }
+
+        match result[1] {
+            Ok(Tlv::Cipher(CipherType::Aes)) => (),
+            _ => panic!("Invalid result: {:#?}", result[1]),
+        }
+
+        assert_eq!(reader.len(), 0);
+    }
 }
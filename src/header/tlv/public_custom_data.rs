@@ -0,0 +1,19 @@
+use bytes;
+use Error;
+
+use header::tlv::Tlv;
+use header::variant_dictionary;
+use header::variant_dictionary::VariantDictionary;
+
+use std::io::{Read, Write};
+
+pub fn read_tlv(reader: &mut Read, length: u32) -> Result<Tlv, Error> {
+    let value = try!(bytes::read_bytes(reader, length as usize));
+    let data = try!(variant_dictionary::read(&mut &value[..]));
+
+    Ok(Tlv::PublicCustomData(data))
+}
+
+pub fn write_tlv(writer: &mut Write, data: &VariantDictionary) -> Result<(), Error> {
+    variant_dictionary::write(writer, data)
+}
@@ -1,18 +1,23 @@
+use bytes;
 use Error;
 
 use header::tlv::Tlv;
 
-use std::io::Read;
+use std::io::{Read, Write};
 
 const STREAM_START_BYTES_LENGTH: usize = 32;
 
-pub fn read_tlv(reader: &mut Read, length: u16) -> Result<Tlv, Error> {
-    try!(super::check_tlv_length(length, STREAM_START_BYTES_LENGTH as u16));
+pub fn read_tlv(reader: &mut Read, length: u32) -> Result<Tlv, Error> {
+    try!(super::check_tlv_length(length, STREAM_START_BYTES_LENGTH as u32));
 
     let seed = try!(read_array!(reader, STREAM_START_BYTES_LENGTH));
     Ok(Tlv::StreamStartBytes(seed))
 }
 
+pub fn write_tlv(writer: &mut Write, bytes: &[u8; 32]) -> Result<(), Error> {
+    bytes::write_all(writer, bytes)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -41,4 +46,12 @@ mod test {
             _ => panic!("Invalid result: {:#?}", result),
         }
     }
+
+    #[test]
+    fn should_write_tlv() {
+        let mut bytes = vec![];
+        write_tlv(&mut bytes, &[1; 32]).unwrap();
+
+        assert_eq!(bytes, vec![1; 32]);
+    }
 }
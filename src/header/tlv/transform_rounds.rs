@@ -3,15 +3,19 @@ use Error;
 
 use header::tlv::Tlv;
 
-use std::io::Read;
+use std::io::{Read, Write};
 
-pub fn read_tlv(reader: &mut Read, length: u16) -> Result<Tlv, Error> {
+pub fn read_tlv(reader: &mut Read, length: u32) -> Result<Tlv, Error> {
     try!(super::check_tlv_length(length, 8));
 
     let rounds = try!(bytes::read_u64(reader));
     Ok(Tlv::TransformRounds(rounds))
 }
 
+pub fn write_tlv(writer: &mut Write, rounds: u64) -> Result<(), Error> {
+    bytes::write_u64(writer, rounds)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -45,4 +49,17 @@ mod test {
             _ => panic!("Invalid result: {:#?}", result),
         }
     }
+
+    #[test]
+    fn should_write_tlv() {
+        let mut bytes = vec![];
+        write_tlv(&mut bytes, 1234).unwrap();
+
+        let result = read_tlv(&mut &bytes[..], 8);
+
+        match result {
+            Ok(Tlv::TransformRounds(rounds)) => assert_eq!(rounds, 1234),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
 }
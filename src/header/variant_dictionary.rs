@@ -0,0 +1,199 @@
+use bytes;
+use Error;
+
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::iter::FromIterator;
+
+const VERSION: u16 = 0x0100;
+const VERSION_CRITICAL_MASK: u16 = 0xFF00;
+
+const TYPE_END: u8 = 0x00;
+const TYPE_UINT32: u8 = 0x04;
+const TYPE_UINT64: u8 = 0x05;
+const TYPE_BOOL: u8 = 0x08;
+const TYPE_INT32: u8 = 0x0C;
+const TYPE_INT64: u8 = 0x0D;
+const TYPE_STRING: u8 = 0x18;
+const TYPE_BYTE_ARRAY: u8 = 0x42;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Variant {
+    UInt32(u32),
+    UInt64(u64),
+    Bool(bool),
+    Int32(i32),
+    Int64(i64),
+    String(String),
+    ByteArray(Vec<u8>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct VariantDictionary {
+    items: BTreeMap<String, Variant>,
+}
+
+impl VariantDictionary {
+    pub fn new() -> VariantDictionary {
+        VariantDictionary { items: BTreeMap::new() }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Variant> {
+        self.items.get(key)
+    }
+
+    pub fn get_u32(&self, key: &str) -> Option<u32> {
+        match self.get(key) {
+            Some(&Variant::UInt32(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn get_u64(&self, key: &str) -> Option<u64> {
+        match self.get(key) {
+            Some(&Variant::UInt64(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn get_bytes(&self, key: &str) -> Option<&[u8]> {
+        match self.get(key) {
+            Some(&Variant::ByteArray(ref value)) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl FromIterator<(String, Variant)> for VariantDictionary {
+    fn from_iter<I: IntoIterator<Item = (String, Variant)>>(items: I) -> VariantDictionary {
+        VariantDictionary { items: items.into_iter().collect() }
+    }
+}
+
+pub fn read(reader: &mut Read) -> Result<VariantDictionary, Error> {
+    let version = try!(bytes::read_u16(reader));
+    if version & VERSION_CRITICAL_MASK != VERSION & VERSION_CRITICAL_MASK {
+        return Err(Error::InvalidVariantDictionaryVersion(version));
+    }
+
+    let mut dictionary = VariantDictionary::new();
+
+    loop {
+        let item_type = try!(bytes::read_u8(reader));
+        if item_type == TYPE_END {
+            break;
+        }
+
+        let key_length = try!(bytes::read_u32(reader));
+        let key = try!(String::from_utf8(try!(bytes::read_bytes(reader, key_length as usize)))
+                           .map_err(|e| Error::Utf8(e)));
+
+        let value_length = try!(bytes::read_u32(reader));
+        let value = try!(bytes::read_bytes(reader, value_length as usize));
+
+        dictionary.items.insert(key, try!(read_variant(item_type, value)));
+    }
+
+    Ok(dictionary)
+}
+
+pub fn write(writer: &mut Write, dictionary: &VariantDictionary) -> Result<(), Error> {
+    try!(bytes::write_u16(writer, VERSION));
+
+    for (key, value) in &dictionary.items {
+        let mut buf = vec![];
+        let item_type = try!(write_variant(&mut buf, value));
+
+        try!(bytes::write_u8(writer, item_type));
+        try!(bytes::write_u32(writer, key.len() as u32));
+        try!(bytes::write_all(writer, key.as_bytes()));
+        try!(bytes::write_u32(writer, buf.len() as u32));
+        try!(bytes::write_all(writer, &buf));
+    }
+
+    bytes::write_u8(writer, TYPE_END)
+}
+
+fn read_variant(item_type: u8, value: Vec<u8>) -> Result<Variant, Error> {
+    let reader = &mut &value[..];
+
+    match item_type {
+        TYPE_UINT32 => bytes::read_u32(reader).map(|v| Variant::UInt32(v)),
+        TYPE_UINT64 => bytes::read_u64(reader).map(|v| Variant::UInt64(v)),
+        TYPE_BOOL => bytes::read_u8(reader).map(|v| Variant::Bool(v != 0)),
+        TYPE_INT32 => bytes::read_u32(reader).map(|v| Variant::Int32(v as i32)),
+        TYPE_INT64 => bytes::read_u64(reader).map(|v| Variant::Int64(v as i64)),
+        TYPE_STRING => {
+            String::from_utf8(reader.to_vec())
+                .map(|v| Variant::String(v))
+                .map_err(|e| Error::Utf8(e))
+        }
+        TYPE_BYTE_ARRAY => Ok(Variant::ByteArray(reader.to_vec())),
+        _ => Err(Error::UnknownVariantType(item_type)),
+    }
+}
+
+fn write_variant(writer: &mut Write, value: &Variant) -> Result<u8, Error> {
+    match *value {
+        Variant::UInt32(v) => bytes::write_u32(writer, v).map(|_| TYPE_UINT32),
+        Variant::UInt64(v) => bytes::write_u64(writer, v).map(|_| TYPE_UINT64),
+        Variant::Bool(v) => bytes::write_u8(writer, v as u8).map(|_| TYPE_BOOL),
+        Variant::Int32(v) => bytes::write_u32(writer, v as u32).map(|_| TYPE_INT32),
+        Variant::Int64(v) => bytes::write_u64(writer, v as u64).map(|_| TYPE_INT64),
+        Variant::String(ref v) => bytes::write_all(writer, v.as_bytes()).map(|_| TYPE_STRING),
+        Variant::ByteArray(ref v) => bytes::write_all(writer, v).map(|_| TYPE_BYTE_ARRAY),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use Error;
+
+    #[test]
+    fn should_read_and_write_dictionary() {
+        let dictionary: VariantDictionary = vec![("R".to_string(), Variant::UInt64(6000)),
+                                                 ("V".to_string(), Variant::UInt32(0x13)),
+                                                 ("S".to_string(), Variant::ByteArray(vec![1; 32])),
+                                                 ("B".to_string(), Variant::Bool(true)),
+                                                 ("I".to_string(), Variant::Int64(-1)),
+                                                 ("T".to_string(),
+                                                  Variant::String("hunter2".to_string()))]
+                                                .into_iter()
+                                                .collect();
+
+        let mut bytes = vec![];
+        write(&mut bytes, &dictionary).unwrap();
+
+        let result = read(&mut &bytes[..]).unwrap();
+
+        assert_eq!(result, dictionary);
+        assert_eq!(result.get_u64("R"), Some(6000));
+        assert_eq!(result.get_u32("V"), Some(0x13));
+        assert_eq!(result.get_bytes("S"), Some(&[1; 32][..]));
+        assert_eq!(result.get_u64("S"), None);
+    }
+
+    #[test]
+    fn should_return_error_if_wrong_version() {
+        let bytes = vec![0x00, 0x02, 0x00];
+        let result = read(&mut &bytes[..]);
+
+        match result {
+            Err(Error::InvalidVariantDictionaryVersion(0x0200)) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
+    #[test]
+    fn should_return_error_if_unknown_type() {
+        let bytes = vec![0x00, 0x01, 0x01, 0x01, 0x00, 0x00, 0x00, 0x41, 0x00, 0x00, 0x00, 0x00];
+        let result = read(&mut &bytes[..]);
+
+        match result {
+            Err(Error::UnknownVariantType(0x01)) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use bytes;
+use Error;
+
+use std::io::Read;
+
+const VERSION: u32 = 0x00030004;
+const VERSION_MASK: u32 = 0xFFFFFF00;
+
+const FLAG_RIJNDAEL: u32 = 0x02;
+const FLAG_TWOFISH: u32 = 0x08;
+
+#[derive(Debug, PartialEq)]
+pub enum CipherType {
+    Aes,
+    Twofish,
+}
+
+#[derive(Debug)]
+pub struct Header {
+    pub cipher: CipherType,
+    pub master_seed: [u8; 16],
+    pub encryption_iv: [u8; 16],
+    pub groups: u32,
+    pub entries: u32,
+    pub contents_hash: [u8; 32],
+    pub transform_seed: [u8; 32],
+    pub transform_rounds: u32,
+}
+
+pub fn read_header(reader: &mut Read) -> Result<Header, Error> {
+    let flags = try!(bytes::read_u32(reader));
+    let version = try!(bytes::read_u32(reader));
+    try!(check_version(version));
+
+    Ok(Header {
+        cipher: try!(match_cipher_type(flags)),
+        master_seed: try!(read_array!(reader, 16)),
+        encryption_iv: try!(read_array!(reader, 16)),
+        groups: try!(bytes::read_u32(reader)),
+        entries: try!(bytes::read_u32(reader)),
+        contents_hash: try!(read_array!(reader, 32)),
+        transform_seed: try!(read_array!(reader, 32)),
+        transform_rounds: try!(bytes::read_u32(reader)),
+    })
+}
+
+fn check_version(version: u32) -> Result<(), Error> {
+    if version & VERSION_MASK == VERSION & VERSION_MASK {
+        Ok(())
+    } else {
+        Err(Error::UnsupportedVersion(version))
+    }
+}
+
+fn match_cipher_type(flags: u32) -> Result<CipherType, Error> {
+    if flags & FLAG_RIJNDAEL != 0 {
+        Ok(CipherType::Aes)
+    } else if flags & FLAG_TWOFISH != 0 {
+        Ok(CipherType::Twofish)
+    } else {
+        Err(Error::UnknownEncryptionFlags(flags))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use Error;
+
+    use byteorder::{LittleEndian, WriteBytesExt};
+
+    fn header_bytes(flags: u32, version: u32) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.write_u32::<LittleEndian>(flags).unwrap();
+        bytes.write_u32::<LittleEndian>(version).unwrap();
+        bytes.extend(vec![1; 16]);
+        bytes.extend(vec![2; 16]);
+        bytes.write_u32::<LittleEndian>(3).unwrap();
+        bytes.write_u32::<LittleEndian>(4).unwrap();
+        bytes.extend(vec![5; 32]);
+        bytes.extend(vec![6; 32]);
+        bytes.write_u32::<LittleEndian>(6000).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn should_read_header() {
+        let bytes = header_bytes(0x09, 0x00030002);
+        let result = read_header(&mut &bytes[..]).unwrap();
+
+        assert_eq!(result.cipher, CipherType::Twofish);
+        assert_eq!(result.master_seed, [1; 16]);
+        assert_eq!(result.encryption_iv, [2; 16]);
+        assert_eq!(result.groups, 3);
+        assert_eq!(result.entries, 4);
+        assert_eq!(result.contents_hash, [5; 32]);
+        assert_eq!(result.transform_seed, [6; 32]);
+        assert_eq!(result.transform_rounds, 6000);
+    }
+
+    #[test]
+    fn should_return_error_if_unsupported_version() {
+        let bytes = header_bytes(0x03, 0x00020000);
+        let result = read_header(&mut &bytes[..]);
+
+        match result {
+            Err(Error::UnsupportedVersion(0x00020000)) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
+    #[test]
+    fn should_return_error_if_unknown_cipher() {
+        let bytes = header_bytes(0x05, 0x00030004);
+        let result = read_header(&mut &bytes[..]);
+
+        match result {
+            Err(Error::UnknownEncryptionFlags(0x05)) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+}
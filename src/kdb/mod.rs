@@ -0,0 +1,121 @@
+mod header;
+mod record;
+
+use {CompositeKey, Database, DatabaseEntry, Error, Group};
+use header::Kdf;
+use read::{aes, twofish};
+use util::sha256;
+
+use std::io::Read;
+use std::iter::Peekable;
+use std::vec::IntoIter;
+
+use self::header::{CipherType, Header};
+
+pub fn read(reader: &mut Read, key: &CompositeKey) -> Result<Database, Error> {
+    let header = try!(header::read_header(reader));
+    let master_key = try!(master_key(&header, key));
+
+    let contents = try!(match header.cipher {
+        CipherType::Aes => aes::decrypt(reader, &master_key, &header.encryption_iv),
+        CipherType::Twofish => twofish::decrypt(reader, &master_key, &header.encryption_iv),
+    });
+    try!(check_contents_hash(&contents, &header.contents_hash));
+
+    let mut reader = &contents[..];
+    let mut groups = vec![];
+    for _ in 0..header.groups {
+        groups.push(try!(record::read_group(&mut reader)));
+    }
+
+    let mut entries = vec![];
+    for _ in 0..header.entries {
+        let entry = try!(record::read_entry(&mut reader));
+        if !entry.is_meta_stream() {
+            entries.push(entry);
+        }
+    }
+
+    let group_ids: Vec<u32> = groups.iter().map(|group| group.id).collect();
+    let (orphans, mut entries): (Vec<_>, Vec<_>) =
+        entries.into_iter().partition(|entry| !group_ids.contains(&entry.group_id));
+
+    let mut database = Database::new();
+    database.set_cipher(match header.cipher {
+        CipherType::Aes => ::CipherType::Aes,
+        CipherType::Twofish => ::CipherType::Twofish,
+    });
+    database.set_transform_rounds(header.transform_rounds as u64);
+    for group in build_groups(&mut groups.into_iter().peekable(), 0, &mut entries) {
+        database.root_mut().add_group(group);
+    }
+    for entry in orphans {
+        database.add(make_entry(entry));
+    }
+
+    Ok(database)
+}
+
+fn build_groups(groups: &mut Peekable<IntoIter<record::Group>>,
+                level: u16,
+                entries: &mut Vec<record::Entry>)
+                -> Vec<Group> {
+    let mut result = vec![];
+
+    while groups.peek().map_or(false, |group| group.level >= level) {
+        let record = groups.next().unwrap();
+
+        let (own, rest): (Vec<_>, Vec<_>) =
+            entries.drain(..).partition(|entry| entry.group_id == record.id);
+        *entries = rest;
+
+        let mut group = Group::new(&record.name.unwrap_or_default());
+        group.set_icon_id(record.icon_id);
+        *group.times_mut() = record.times;
+        for entry in own {
+            group.add_entry(make_entry(entry));
+        }
+
+        for child in build_groups(groups, record.level + 1, entries) {
+            group.add_group(child);
+        }
+
+        result.push(group);
+    }
+
+    result
+}
+
+fn make_entry(entry: record::Entry) -> DatabaseEntry {
+    let mut result = DatabaseEntry::new(entry.title, entry.username, entry.password);
+    if let Some(uuid) = entry.uuid {
+        result.set_uuid(uuid);
+    }
+    result.set_icon_id(entry.icon_id);
+    result.set_field("URL", entry.url, false);
+    result.set_field("Notes", entry.notes, false);
+    *result.times_mut() = entry.times;
+    result
+}
+
+fn master_key(header: &Header, key: &CompositeKey) -> Result<[u8; 32], Error> {
+    let kdf = Kdf::Aes {
+        seed: header.transform_seed,
+        rounds: header.transform_rounds as u64,
+    };
+    let transformed_key = try!(kdf.transform_key(&try!(key.kdb_hash())));
+
+    let mut buffer = vec![];
+    buffer.extend(header.master_seed.iter().cloned());
+    buffer.extend(transformed_key.iter().cloned());
+
+    Ok(sha256(&buffer))
+}
+
+fn check_contents_hash(contents: &[u8], expected: &[u8; 32]) -> Result<(), Error> {
+    if sha256(contents) == *expected {
+        Ok(())
+    } else {
+        Err(Error::IncorrectContentsHash)
+    }
+}
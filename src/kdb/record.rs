@@ -0,0 +1,276 @@
+use bytes;
+use {Error, Times, Timestamp, Uuid};
+
+use std::io::Read;
+
+const FIELD_END: u16 = 0xFFFF;
+
+const GROUP_ID: u16 = 0x0001;
+const GROUP_NAME: u16 = 0x0002;
+const GROUP_CREATION_TIME: u16 = 0x0003;
+const GROUP_LAST_MODIFICATION_TIME: u16 = 0x0004;
+const GROUP_LAST_ACCESS_TIME: u16 = 0x0005;
+const GROUP_EXPIRY_TIME: u16 = 0x0006;
+const GROUP_ICON_ID: u16 = 0x0007;
+const GROUP_LEVEL: u16 = 0x0008;
+
+const ENTRY_UUID: u16 = 0x0001;
+const ENTRY_GROUP_ID: u16 = 0x0002;
+const ENTRY_ICON_ID: u16 = 0x0003;
+const ENTRY_TITLE: u16 = 0x0004;
+const ENTRY_URL: u16 = 0x0005;
+const ENTRY_USERNAME: u16 = 0x0006;
+const ENTRY_PASSWORD: u16 = 0x0007;
+const ENTRY_NOTES: u16 = 0x0008;
+const ENTRY_CREATION_TIME: u16 = 0x0009;
+const ENTRY_LAST_MODIFICATION_TIME: u16 = 0x000A;
+const ENTRY_LAST_ACCESS_TIME: u16 = 0x000B;
+const ENTRY_EXPIRY_TIME: u16 = 0x000C;
+const ENTRY_BINARY_DESCRIPTION: u16 = 0x000D;
+
+const TIME_SIZE: usize = 5;
+const NEVER_EXPIRES: (i64, u32, u32, u32, u32, u32) = (2999, 12, 28, 23, 59, 59);
+
+const META_STREAM_TITLE: &'static str = "Meta-Info";
+const META_STREAM_USERNAME: &'static str = "SYSTEM";
+const META_STREAM_URL: &'static str = "$";
+const META_STREAM_BINARY_DESCRIPTION: &'static str = "bin-stream";
+
+#[derive(Debug, Default)]
+pub struct Group {
+    pub id: u32,
+    pub name: Option<String>,
+    pub icon_id: Option<u32>,
+    pub level: u16,
+    pub times: Times,
+}
+
+#[derive(Debug, Default)]
+pub struct Entry {
+    pub uuid: Option<Uuid>,
+    pub group_id: u32,
+    pub icon_id: Option<u32>,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub notes: Option<String>,
+    pub binary_description: Option<String>,
+    pub times: Times,
+}
+
+impl Entry {
+    pub fn is_meta_stream(&self) -> bool {
+        self.notes.is_some() && self.title.as_ref().map_or(false, |v| v == META_STREAM_TITLE) &&
+        self.username.as_ref().map_or(false, |v| v == META_STREAM_USERNAME) &&
+        self.url.as_ref().map_or(false, |v| v == META_STREAM_URL) &&
+        self.binary_description.as_ref().map_or(false, |v| v == META_STREAM_BINARY_DESCRIPTION)
+    }
+}
+
+pub fn read_group(reader: &mut Read) -> Result<Group, Error> {
+    let mut group = Group::default();
+
+    loop {
+        let (field_type, data) = try!(read_field(reader));
+        match field_type {
+            FIELD_END => return Ok(group),
+            GROUP_ID => group.id = try!(bytes::read_u32(&mut &data[..])),
+            GROUP_NAME => group.name = try!(read_string(data)),
+            GROUP_CREATION_TIME => group.times.set_creation_time(Some(try!(read_time(&data)))),
+            GROUP_LAST_MODIFICATION_TIME => {
+                group.times.set_last_modification_time(Some(try!(read_time(&data))))
+            }
+            GROUP_LAST_ACCESS_TIME => {
+                group.times.set_last_access_time(Some(try!(read_time(&data))))
+            }
+            GROUP_EXPIRY_TIME => try!(read_expiry_time(&data, &mut group.times)),
+            GROUP_ICON_ID => group.icon_id = Some(try!(bytes::read_u32(&mut &data[..]))),
+            GROUP_LEVEL => group.level = try!(bytes::read_u16(&mut &data[..])),
+            _ => {}
+        }
+    }
+}
+
+pub fn read_entry(reader: &mut Read) -> Result<Entry, Error> {
+    let mut entry = Entry::default();
+
+    loop {
+        let (field_type, data) = try!(read_field(reader));
+        match field_type {
+            FIELD_END => return Ok(entry),
+            ENTRY_UUID => entry.uuid = Uuid::from_slice(&data),
+            ENTRY_GROUP_ID => entry.group_id = try!(bytes::read_u32(&mut &data[..])),
+            ENTRY_ICON_ID => entry.icon_id = Some(try!(bytes::read_u32(&mut &data[..]))),
+            ENTRY_TITLE => entry.title = try!(read_string(data)),
+            ENTRY_URL => entry.url = try!(read_string(data)),
+            ENTRY_USERNAME => entry.username = try!(read_string(data)),
+            ENTRY_PASSWORD => entry.password = try!(read_string(data)),
+            ENTRY_NOTES => entry.notes = try!(read_string(data)),
+            ENTRY_CREATION_TIME => entry.times.set_creation_time(Some(try!(read_time(&data)))),
+            ENTRY_LAST_MODIFICATION_TIME => {
+                entry.times.set_last_modification_time(Some(try!(read_time(&data))))
+            }
+            ENTRY_LAST_ACCESS_TIME => {
+                entry.times.set_last_access_time(Some(try!(read_time(&data))))
+            }
+            ENTRY_EXPIRY_TIME => try!(read_expiry_time(&data, &mut entry.times)),
+            ENTRY_BINARY_DESCRIPTION => entry.binary_description = try!(read_string(data)),
+            _ => {}
+        }
+    }
+}
+
+fn read_field(reader: &mut Read) -> Result<(u16, Vec<u8>), Error> {
+    let field_type = try!(bytes::read_u16(reader));
+    let length = try!(bytes::read_u32(reader));
+    let data = try!(bytes::read_bytes(reader, length as usize));
+
+    Ok((field_type, data))
+}
+
+// KDB packs times into 5 bytes: 14 bits year, 4 bits month, 5 bits day, 5 bits hour, 6 bits
+// minute and 6 bits second.
+fn read_time(data: &[u8]) -> Result<Timestamp, Error> {
+    if data.len() < TIME_SIZE {
+        return Err(Error::UnexpectedEOF);
+    }
+
+    let data: Vec<u32> = data[..TIME_SIZE].iter().map(|&b| b as u32).collect();
+    let year = (data[0] << 6) | (data[1] >> 2);
+    let month = ((data[1] & 0x03) << 2) | (data[2] >> 6);
+    let day = (data[2] >> 1) & 0x1F;
+    let hour = ((data[2] & 0x01) << 4) | (data[3] >> 4);
+    let minute = ((data[3] & 0x0F) << 2) | (data[4] >> 6);
+    let second = data[4] & 0x3F;
+
+    Ok(Timestamp::new(year as i64, month, day, hour, minute, second))
+}
+
+fn read_expiry_time(data: &[u8], times: &mut Times) -> Result<(), Error> {
+    let expiry_time = try!(read_time(data));
+    if expiry_time.date_time() != NEVER_EXPIRES {
+        times.set_expiry_time(Some(expiry_time));
+        times.set_expires(true);
+    }
+
+    Ok(())
+}
+
+fn read_string(mut data: Vec<u8>) -> Result<Option<String>, Error> {
+    while data.last() == Some(&0) {
+        data.pop();
+    }
+
+    if data.is_empty() {
+        Ok(None)
+    } else {
+        String::from_utf8(data).map(Some).map_err(|e| Error::Utf8(e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use {Timestamp, Uuid};
+
+    use byteorder::{LittleEndian, WriteBytesExt};
+
+    fn write_field(bytes: &mut Vec<u8>, field_type: u16, data: &[u8]) {
+        bytes.write_u16::<LittleEndian>(field_type).unwrap();
+        bytes.write_u32::<LittleEndian>(data.len() as u32).unwrap();
+        bytes.extend(data.iter().cloned());
+    }
+
+    #[test]
+    fn should_read_entry() {
+        let mut bytes = vec![];
+        write_field(&mut bytes, 0x0001, &[1; 16]);
+        write_field(&mut bytes, 0x0002, &[7, 0, 0, 0]);
+        write_field(&mut bytes, 0x0003, &[42, 0, 0, 0]);
+        write_field(&mut bytes, 0x0004, b"http://example.com\0");
+        write_field(&mut bytes, 0x0006, b"joe.bloggs\0");
+        write_field(&mut bytes, 0x0007, b"hunter2\0");
+        write_field(&mut bytes, 0x0008, b"\0");
+        write_field(&mut bytes, 0xFFFF, &[]);
+
+        let result = read_entry(&mut &bytes[..]).unwrap();
+
+        assert_eq!(result.uuid, Some(Uuid::from_bytes([1; 16])));
+        assert_eq!(result.group_id, 7);
+        assert_eq!(result.icon_id, Some(42));
+        assert_eq!(result.title, Some("http://example.com".to_string()));
+        assert_eq!(result.username, Some("joe.bloggs".to_string()));
+        assert_eq!(result.password, Some("hunter2".to_string()));
+        assert_eq!(result.notes, None);
+        assert!(!result.is_meta_stream());
+    }
+
+    #[test]
+    fn should_detect_meta_stream() {
+        let mut bytes = vec![];
+        write_field(&mut bytes, 0x0004, b"Meta-Info\0");
+        write_field(&mut bytes, 0x0005, b"$\0");
+        write_field(&mut bytes, 0x0006, b"SYSTEM\0");
+        write_field(&mut bytes, 0x0008, b"Simple UI State\0");
+        write_field(&mut bytes, 0x000D, b"bin-stream\0");
+        write_field(&mut bytes, 0xFFFF, &[]);
+
+        let result = read_entry(&mut &bytes[..]).unwrap();
+
+        assert!(result.is_meta_stream());
+    }
+
+    #[test]
+    fn should_read_group() {
+        let mut bytes = vec![];
+        write_field(&mut bytes, 0x0001, &[1, 0, 0, 0]);
+        write_field(&mut bytes, 0x0002, b"General\0");
+        write_field(&mut bytes, 0x0007, &[48, 0, 0, 0]);
+        write_field(&mut bytes, 0x0008, &[2, 0]);
+        write_field(&mut bytes, 0xFFFF, &[]);
+        bytes.push(42);
+
+        let mut reader = &bytes[..];
+        let result = read_group(&mut reader).unwrap();
+
+        assert_eq!(result.id, 1);
+        assert_eq!(result.name, Some("General".to_string()));
+        assert_eq!(result.icon_id, Some(48));
+        assert_eq!(result.level, 2);
+        assert_eq!(reader, &[42]);
+    }
+
+    #[test]
+    fn should_read_times() {
+        let mut bytes = vec![];
+        write_field(&mut bytes, 0x0004, b"GitHub\0");
+        write_field(&mut bytes, 0x0009, &[0x1F, 0x82, 0x3C, 0xEB, 0x8C]);
+        write_field(&mut bytes, 0x000A, &[0x1F, 0x82, 0x3C, 0xEB, 0x8C]);
+        write_field(&mut bytes, 0x000C, &[0x2E, 0xDF, 0x39, 0x7E, 0xFB]);
+        write_field(&mut bytes, 0xFFFF, &[]);
+
+        let result = read_entry(&mut &bytes[..]).unwrap();
+
+        let created = Timestamp::new(2016, 8, 30, 14, 46, 12);
+        assert_eq!(result.times.creation_time(), Some(created));
+        assert_eq!(result.times.last_modification_time(), Some(created));
+        assert_eq!(result.times.last_access_time(), None);
+        assert_eq!(result.times.expiry_time(), None);
+        assert!(!result.times.expires());
+    }
+
+    #[test]
+    fn should_read_expiry_time() {
+        let mut bytes = vec![];
+        write_field(&mut bytes, 0x0002, b"General\0");
+        write_field(&mut bytes, 0x0006, &[0x1F, 0x84, 0x42, 0x00, 0x00]);
+        write_field(&mut bytes, 0xFFFF, &[]);
+
+        let result = read_group(&mut &bytes[..]).unwrap();
+
+        assert_eq!(result.times.expiry_time(), Some(Timestamp::new(2017, 1, 1, 0, 0, 0)));
+        assert!(result.times.expires());
+    }
+}
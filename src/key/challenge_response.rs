@@ -0,0 +1,43 @@
+use Error;
+
+use crypto::hmac::Hmac;
+use crypto::mac::Mac;
+use crypto::sha1::Sha1;
+
+pub trait ChallengeResponseProvider {
+    fn challenge(&self, challenge: &[u8]) -> Result<Vec<u8>, Error>;
+}
+
+pub struct HmacSha1Provider {
+    secret: Vec<u8>,
+}
+
+impl HmacSha1Provider {
+    pub fn new(secret: &[u8]) -> HmacSha1Provider {
+        HmacSha1Provider { secret: secret.to_vec() }
+    }
+}
+
+impl ChallengeResponseProvider for HmacSha1Provider {
+    fn challenge(&self, challenge: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut hmac = Hmac::new(Sha1::new(), &self.secret);
+        hmac.input(challenge);
+
+        Ok(hmac.result().code().to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChallengeResponseProvider, HmacSha1Provider};
+
+    #[test]
+    fn should_respond_with_hmac_sha1() {
+        let provider = HmacSha1Provider::new(&[0x0b; 20]);
+
+        let result = provider.challenge(b"Hi There").unwrap();
+        assert_eq!(result,
+                   vec![0xb6, 0x17, 0x31, 0x86, 0x55, 0x05, 0x72, 0x64, 0xe2, 0x8b, 0xc0, 0xb6,
+                        0xfb, 0x37, 0x8c, 0x8e, 0xf1, 0x46, 0xbe, 0x00]);
+    }
+}
@@ -0,0 +1,216 @@
+use Error;
+use read::xml::read_chars;
+use util::sha256;
+use write::xml::{end_element, start_element, write_chars, write_event};
+
+use std::io::Write;
+use std::str;
+
+use rustc_serialize::base64::FromBase64;
+use rustc_serialize::hex::{FromHex, ToHex};
+use xml::common::XmlVersion;
+use xml::reader::{EventReader, XmlEvent};
+use xml::writer::{self, EmitterConfig};
+
+const KEY_LENGTH: usize = 32;
+const HASH_LENGTH: usize = 4;
+const GROUP_LENGTH: usize = 4;
+const VERSION: &'static str = "2.0";
+
+pub fn read(data: &[u8]) -> Result<[u8; 32], Error> {
+    if let Some(key) = try!(read_xml(data)) {
+        return Ok(key);
+    }
+
+    if data.len() == KEY_LENGTH {
+        return read_array!(&mut &data[..], KEY_LENGTH);
+    }
+
+    if data.len() == 2 * KEY_LENGTH {
+        if let Some(key) = str::from_utf8(data).ok().and_then(|hex| hex.from_hex().ok()) {
+            return read_array!(&mut &key[..], KEY_LENGTH);
+        }
+    }
+
+    Ok(sha256(data))
+}
+
+pub fn write(writer: &mut Write, key: &[u8; 32]) -> Result<(), Error> {
+    let mut event_writer = EmitterConfig::new().perform_indent(true).create_writer(writer);
+
+    try!(write_event(&mut event_writer,
+                     writer::XmlEvent::StartDocument {
+                         version: XmlVersion::Version10,
+                         encoding: Some("utf-8"),
+                         standalone: None,
+                     }));
+    try!(start_element(&mut event_writer, "KeyFile"));
+
+    try!(start_element(&mut event_writer, "Meta"));
+    try!(write_chars(&mut event_writer, "Version", VERSION));
+    try!(end_element(&mut event_writer));
+
+    let hash = sha256(key)[..HASH_LENGTH].to_hex().to_uppercase();
+    let data = key.chunks(GROUP_LENGTH)
+                  .map(|group| group.to_hex().to_uppercase())
+                  .collect::<Vec<_>>()
+                  .join(" ");
+
+    try!(start_element(&mut event_writer, "Key"));
+    try!(write_event(&mut event_writer,
+                     writer::XmlEvent::start_element("Data").attr("Hash", &hash).into()));
+    try!(write_event(&mut event_writer, writer::XmlEvent::characters(&data)));
+    try!(end_element(&mut event_writer));
+    try!(end_element(&mut event_writer));
+
+    end_element(&mut event_writer)
+}
+
+fn read_xml(data: &[u8]) -> Result<Option<[u8; 32]>, Error> {
+    let event_reader = EventReader::new(data);
+    let mut iterator = event_reader.into_iter().map(|result| result.map_err(|e| Error::Xml(e)));
+
+    let mut is_key_file = false;
+    let mut version = None;
+    let mut key_data = None;
+    let mut hash = None;
+
+    loop {
+        match iterator.next() {
+            Some(Ok(XmlEvent::StartElement { name, attributes, .. })) => {
+                match &name.local_name[..] {
+                    "KeyFile" => is_key_file = true,
+                    _ if !is_key_file => return Ok(None),
+                    "Version" => version = try!(read_chars(&mut iterator, "Version")),
+                    "Data" => {
+                        hash = attributes.iter()
+                                         .find(|a| a.name.local_name == "Hash")
+                                         .map(|a| a.value.clone());
+                        key_data = try!(read_chars(&mut iterator, "Data"));
+                    }
+                    _ => {}
+                }
+            }
+
+            Some(Err(_)) if !is_key_file => return Ok(None),
+            Some(Err(e)) => return Err(e),
+            None => break,
+
+            _ => {}
+        }
+    }
+
+    if !is_key_file {
+        return Ok(None);
+    }
+
+    let key_data = try!(key_data.ok_or(Error::InvalidKeyFile));
+    let key = match version.as_ref().map(|v| &v[..]) {
+        Some("1.0") | Some("1.00") => try!(key_data.from_base64().map_err(|e| Error::Base64(e))),
+        Some("2.0") | Some("2.00") => try!(read_hex_key(&key_data, hash)),
+        _ => return Err(Error::InvalidKeyFile),
+    };
+
+    if key.len() == KEY_LENGTH {
+        Ok(Some(try!(read_array!(&mut &key[..], KEY_LENGTH))))
+    } else {
+        Err(Error::InvalidKeyFile)
+    }
+}
+
+fn read_hex_key(key_data: &str, hash: Option<String>) -> Result<Vec<u8>, Error> {
+    let hex: String = key_data.chars().filter(|c| !c.is_whitespace()).collect();
+    let key = try!(hex.from_hex().map_err(|_| Error::InvalidKeyFile));
+
+    if let Some(hash) = hash {
+        if !sha256(&key)[..HASH_LENGTH].to_hex().eq_ignore_ascii_case(&hash) {
+            return Err(Error::IncorrectKeyFileHash);
+        }
+    }
+
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use Error;
+    use util::sha256;
+
+    #[test]
+    fn should_read_xml_v1_key_file() {
+        let data = b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<KeyFile><Meta><Version>1.00</Version></Meta><Key>
+<Data>NXyYiJMHg3ls+eBmjbAjWec9lcOToJiofbhNiFMTJMw=</Data></Key></KeyFile>";
+
+        let result = super::read(data).unwrap();
+        assert_eq!(&result[0..8],
+                   &[0x35, 0x7c, 0x98, 0x88, 0x93, 0x07, 0x83, 0x79]);
+    }
+
+    #[test]
+    fn should_read_xml_v2_key_file() {
+        let data = b"<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<KeyFile>
+    <Meta>
+        <Version>2.0</Version>
+    </Meta>
+    <Key>
+        <Data Hash=\"72CD6E84\">
+            0101010101010101 0101010101010101 0101010101010101 0101010101010101
+        </Data>
+    </Key>
+</KeyFile>";
+
+        let result = super::read(data).unwrap();
+        assert_eq!(result, [1; 32]);
+    }
+
+    #[test]
+    fn should_return_error_if_incorrect_xml_v2_hash() {
+        let data = b"<KeyFile><Meta><Version>2.0</Version></Meta><Key>
+<Data Hash=\"00000000\">0101010101010101010101010101010101010101010101010101010101010101</Data>
+</Key></KeyFile>";
+
+        let result = super::read(data);
+
+        match result {
+            Err(Error::IncorrectKeyFileHash) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
+    #[test]
+    fn should_write_and_read_xml_v2_key_file() {
+        let mut bytes = vec![];
+        super::write(&mut bytes, &[4; 32]).unwrap();
+
+        let data = String::from_utf8(bytes.clone()).unwrap();
+        assert!(data.contains("<Version>2.0</Version>"));
+        assert!(data.contains("04040404 04040404 04040404 04040404"));
+
+        let result = super::read(&bytes).unwrap();
+        assert_eq!(result, [4; 32]);
+    }
+
+    #[test]
+    fn should_read_raw_key_file() {
+        let result = super::read(&[2; 32]).unwrap();
+        assert_eq!(result, [2; 32]);
+    }
+
+    #[test]
+    fn should_read_hex_key_file() {
+        let data = b"0303030303030303030303030303030303030303030303030303030303030303";
+
+        let result = super::read(data).unwrap();
+        assert_eq!(result, [3; 32]);
+    }
+
+    #[test]
+    fn should_hash_other_key_file() {
+        let data = b"correct horse battery staple";
+
+        let result = super::read(data).unwrap();
+        assert_eq!(result, sha256(data));
+    }
+}
@@ -0,0 +1,181 @@
+mod challenge_response;
+mod key_file;
+
+use Error;
+use util::{self, sha256};
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::Path;
+
+pub use self::challenge_response::{ChallengeResponseProvider, HmacSha1Provider};
+
+pub struct CompositeKey {
+    password: Option<[u8; 32]>,
+    key_file: Option<[u8; 32]>,
+    challenge_response: Option<Box<ChallengeResponseProvider>>,
+}
+
+impl CompositeKey {
+    pub fn new() -> CompositeKey {
+        CompositeKey {
+            password: None,
+            key_file: None,
+            challenge_response: None,
+        }
+    }
+
+    pub fn password(mut self, password: &str) -> CompositeKey {
+        self.password = Some(sha256(password.as_bytes()));
+        self
+    }
+
+    pub fn key_file<P: AsRef<Path>>(self, path: P) -> Result<CompositeKey, Error> {
+        let mut file = try!(File::open(path).map_err(|e| Error::Io(e)));
+
+        let mut data = vec![];
+        try!(file.read_to_end(&mut data).map_err(|e| Error::Io(e)));
+
+        self.key_file_data(&data)
+    }
+
+    pub fn key_file_data(mut self, data: &[u8]) -> Result<CompositeKey, Error> {
+        self.key_file = Some(try!(key_file::read(data)));
+        Ok(self)
+    }
+
+    pub fn challenge_response<C>(mut self, provider: C) -> CompositeKey
+        where C: ChallengeResponseProvider + 'static
+    {
+        self.challenge_response = Some(Box::new(provider));
+        self
+    }
+
+    pub fn hash(&self) -> Result<[u8; 32], Error> {
+        if self.password.is_none() && self.key_file.is_none() &&
+           self.challenge_response.is_none() {
+            return Err(Error::MissingCompositeKey);
+        }
+
+        let mut buffer = vec![];
+        for component in self.password.iter().chain(self.key_file.iter()) {
+            buffer.extend(component.iter().cloned());
+        }
+
+        Ok(sha256(&buffer))
+    }
+
+    pub fn challenge(&self, seed: &[u8]) -> Result<Option<[u8; 32]>, Error> {
+        match self.challenge_response {
+            Some(ref provider) => Ok(Some(sha256(&try!(provider.challenge(seed))))),
+            None => Ok(None),
+        }
+    }
+
+    pub fn kdb_hash(&self) -> Result<[u8; 32], Error> {
+        if self.challenge_response.is_some() {
+            return Err(Error::UnsupportedChallengeResponse);
+        }
+
+        match (self.password, self.key_file) {
+            (Some(password), None) => Ok(password),
+            (None, Some(key_file)) => Ok(key_file),
+            (Some(_), Some(_)) => self.hash(),
+            (None, None) => Err(Error::MissingCompositeKey),
+        }
+    }
+}
+
+pub fn generate_key_file<P: AsRef<Path>>(path: P) -> Result<(), Error> {
+    let mut file = try!(OpenOptions::new()
+                            .write(true)
+                            .create_new(true)
+                            .open(path)
+                            .map_err(|e| Error::Io(e)));
+    write_key_file(&mut file)
+}
+
+pub fn write_key_file(writer: &mut Write) -> Result<(), Error> {
+    let mut key = [0; 32];
+    try!(util::random_bytes(&mut key));
+
+    key_file::write(writer, &key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompositeKey, HmacSha1Provider};
+
+    use Error;
+    use util::sha256;
+
+    #[test]
+    fn should_hash_password() {
+        let result = CompositeKey::new().password("hunter2").hash().unwrap();
+        assert_eq!(&result[0..8],
+                   &[0xa3, 0xe2, 0x7a, 0xb2, 0x94, 0x8b, 0x68, 0x0e]);
+    }
+
+    #[test]
+    fn should_hash_password_and_key_file() {
+        let key = CompositeKey::new().password("hunter2").key_file_data(&[1; 32]).unwrap();
+
+        let mut expected = sha256(b"hunter2").to_vec();
+        expected.extend(vec![1; 32]);
+
+        assert_eq!(key.hash().unwrap(), sha256(&expected));
+        assert_eq!(key.kdb_hash().unwrap(), sha256(&expected));
+    }
+
+    #[test]
+    fn should_hash_password_for_kdb() {
+        let result = CompositeKey::new().password("hunter2").kdb_hash().unwrap();
+        assert_eq!(result, sha256(b"hunter2"));
+    }
+
+    #[test]
+    fn should_read_generated_key_file() {
+        let mut first = vec![];
+        super::write_key_file(&mut first).unwrap();
+
+        let mut second = vec![];
+        super::write_key_file(&mut second).unwrap();
+
+        let first = CompositeKey::new().key_file_data(&first).unwrap();
+        let second = CompositeKey::new().key_file_data(&second).unwrap();
+
+        assert!(first.hash().unwrap() != second.hash().unwrap());
+    }
+
+    #[test]
+    fn should_hash_challenge_response() {
+        let key = CompositeKey::new().challenge_response(HmacSha1Provider::new(b"secret"));
+
+        let result = key.challenge(&[1; 32]).unwrap();
+        assert!(result.is_some());
+        assert!(result != key.challenge(&[2; 32]).unwrap());
+    }
+
+    #[test]
+    fn should_return_error_if_challenge_response_for_kdb() {
+        let key = CompositeKey::new()
+                      .password("hunter2")
+                      .challenge_response(HmacSha1Provider::new(b"secret"));
+        let result = key.kdb_hash();
+
+        match result {
+            Err(Error::UnsupportedChallengeResponse) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
+    #[test]
+    fn should_return_error_if_empty() {
+        let result = CompositeKey::new().hash();
+
+        match result {
+            Err(Error::MissingCompositeKey) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+}
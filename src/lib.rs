@@ -1,26 +1,45 @@
 extern crate byteorder;
 extern crate crypto;
 extern crate flate2;
+extern crate rand;
+extern crate regex;
 extern crate rustc_serialize;
 extern crate xml;
 
 #[macro_use]mod macros;
 
+mod argon2;
 mod bytes;
+mod compress;
 mod database;
 mod decompress;
 mod error;
 mod header;
+mod kdb;
+mod key;
 mod protected;
 mod read;
 mod signature;
+mod twofish;
 mod util;
+mod write;
 
-use std::fs::File;
+use std::ffi::OsString;
+use std::fs;
+use std::fs::{File, OpenOptions};
+use std::io::{Error as IoError, ErrorKind, Read, Write};
 use std::path::Path;
 
-pub use database::{Database, DatabaseEntry};
+use header::Header;
+
+pub use database::{Association, Attachment, AutoType, CustomIcon, Database, DatabaseEntry,
+                   DeletedObject, Field, Group, KDBX_VERSION, KeyAction, MemoryProtection, Meta,
+                   Modifier, Obfuscation, PlaceholderProvider, Placeholders, Search,
+                   SearchResult, Times, Timestamp, Uuid, XmlElement, XmlNode};
 pub use error::Error;
+pub use header::CipherType;
+pub use key::{ChallengeResponseProvider, CompositeKey, HmacSha1Provider, generate_key_file,
+              write_key_file};
 
 #[derive(Debug)]
 pub enum FileType {
@@ -29,11 +48,72 @@ pub enum FileType {
     KeePass2,
 }
 
-pub fn read<P: AsRef<Path>>(path: P, passphrase: &str) -> Result<Database, Error> {
+pub fn read<P: AsRef<Path>>(path: P, key: &CompositeKey) -> Result<Database, Error> {
     let mut file = try!(File::open(path).map_err(|e| Error::Io(e)));
 
-    let file_type = try!(signature::read_file_type(&mut file));
-    let header = try!(header::read_header(file_type, &mut file));
+    let mut data = vec![];
+    try!(file.read_to_end(&mut data).map_err(|e| Error::Io(e)));
+
+    let mut reader = &data[..];
+    let file_type = try!(signature::read_file_type(&mut reader));
+    if let FileType::KeePass1 = file_type {
+        return kdb::read(&mut reader, key);
+    }
+
+    let header = try!(header::read_header(file_type, &mut reader));
+    let header_bytes = &data[..data.len() - reader.len()];
+
+    let mut database = try!(read::read(&mut reader, &header, header_bytes, key));
+    database.set_version(header.version());
+    database.set_cipher(header.cipher());
+    if let Some(rounds) = header.transform_rounds() {
+        database.set_transform_rounds(rounds);
+    }
+    Ok(database)
+}
+
+pub fn write<P: AsRef<Path>>(path: P,
+                             database: &Database,
+                             key: &CompositeKey)
+                             -> Result<(), Error> {
+    if database.version() > KDBX_VERSION {
+        return Err(Error::UnsupportedWriteVersion(database.version()));
+    }
+
+    let header = try!(Header::generate(database.cipher(), database.transform_rounds()));
+    let mut data = vec![];
+    try!(signature::write_file_type(&mut data, &FileType::KeePass2));
+    try!(header::write_header(&mut data, &header));
+
+    let header_hash = util::sha256(&data);
+    try!(write::write(&mut data, &header, &header_hash, database, key));
+
+    write_file(path.as_ref(), &data)
+}
+
+fn write_file(path: &Path, data: &[u8]) -> Result<(), Error> {
+    let file_name = try!(path.file_name().ok_or(Error::Io(IoError::new(ErrorKind::InvalidInput,
+                                                                       "missing file name"))));
+    let mut suffix = [0; 8];
+    try!(util::random_bytes(&mut suffix));
+    let suffix: String = suffix.iter().map(|byte| format!("{:02x}", byte)).collect();
+
+    let mut temp_name = OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(format!(".{}.tmp", suffix));
+    let temp_path = path.with_file_name(temp_name);
+
+    let mut file = try!(OpenOptions::new()
+                            .write(true)
+                            .create_new(true)
+                            .open(&temp_path)
+                            .map_err(|e| Error::Io(e)));
+    let result = file.write_all(data)
+                     .and_then(|_| file.sync_all())
+                     .and_then(|_| fs::rename(&temp_path, path));
 
-    read::read(&mut file, &header, passphrase)
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result.map_err(|e| Error::Io(e))
 }
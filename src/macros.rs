@@ -12,6 +12,13 @@ macro_rules! read_array {
     });
 }
 
+macro_rules! try_opt {
+    ($expr:expr) => (match $expr {
+        Some(value) => value,
+        None => return None,
+    });
+}
+
 #[cfg(test)]
 mod test {
     use Error;
@@ -0,0 +1,58 @@
+use Error;
+
+use super::ProtectedStream;
+use util::{decrypt, encrypt, sha512};
+
+use crypto::chacha20::ChaCha20 as ChaChaCipher;
+use rustc_serialize::base64::{FromBase64, STANDARD, ToBase64};
+
+pub struct ChaCha20 {
+    cipher: ChaChaCipher,
+}
+
+impl ChaCha20 {
+    pub fn new(key: &[u8]) -> ChaCha20 {
+        let hash = sha512(key);
+        ChaCha20 { cipher: ChaChaCipher::new(&hash[0..32], &hash[32..44]) }
+    }
+}
+
+impl ProtectedStream for ChaCha20 {
+    fn decrypt(&mut self, value: &str) -> Result<String, Error> {
+        let in_buffer = try!(value.from_base64().map_err(|e| Error::Base64(e)));
+        let result = try!(self.decrypt_bytes(&in_buffer));
+
+        Ok(try!(String::from_utf8(result).map_err(|e| Error::Utf8(e))))
+    }
+
+    fn decrypt_bytes(&mut self, value: &[u8]) -> Result<Vec<u8>, Error> {
+        decrypt(&mut self.cipher, value)
+    }
+
+    fn encrypt(&mut self, value: &str) -> Result<String, Error> {
+        let result = try!(encrypt(&mut self.cipher, value.as_bytes()));
+        Ok(result.to_base64(STANDARD))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChaCha20;
+    use protected::ProtectedStream;
+
+    const KEY: [u8; 64] = [4; 64];
+
+    #[test]
+    fn should_decrypt_passwords() {
+        let mut chacha20 = ChaCha20::new(&KEY);
+        assert_eq!(chacha20.decrypt("t9ivblF+ig==").unwrap(), "hunter2");
+        assert_eq!(chacha20.decrypt("a+pzIVFOaTkRdNvGgQ==").unwrap(), "correct horse");
+    }
+
+    #[test]
+    fn should_encrypt_password() {
+        let mut chacha20 = ChaCha20::new(&KEY);
+        let result = chacha20.encrypt("hunter2").unwrap();
+        assert_eq!(result, "t9ivblF+ig==");
+    }
+}
@@ -1,13 +1,19 @@
+mod chacha20;
 mod none;
+mod rc4;
 mod salsa20;
 
 use Error;
 
+use self::chacha20::ChaCha20;
 use self::none::None;
+use self::rc4::Rc4;
 use self::salsa20::Salsa20;
 
 pub trait ProtectedStream {
     fn decrypt(&mut self, value: &str) -> Result<String, Error>;
+    fn decrypt_bytes(&mut self, value: &[u8]) -> Result<Vec<u8>, Error>;
+    fn encrypt(&mut self, value: &str) -> Result<String, Error>;
 }
 
 impl ProtectedStream {
@@ -15,11 +21,15 @@ impl ProtectedStream {
         Box::new(None)
     }
 
-    pub fn rc4(_key: &[u8; 32]) -> Box<ProtectedStream> {
-        panic!("Not implemented");
+    pub fn rc4(key: &[u8]) -> Box<ProtectedStream> {
+        Box::new(Rc4::new(key))
     }
 
-    pub fn salsa20(key: &[u8; 32]) -> Box<ProtectedStream> {
+    pub fn salsa20(key: &[u8]) -> Box<ProtectedStream> {
         Box::new(Salsa20::new(key))
     }
+
+    pub fn chacha20(key: &[u8]) -> Box<ProtectedStream> {
+        Box::new(ChaCha20::new(key))
+    }
 }
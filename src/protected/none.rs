@@ -8,4 +8,12 @@ impl ProtectedStream for None {
     fn decrypt(&mut self, value: &str) -> Result<String, Error> {
         Ok(value.to_string())
     }
+
+    fn decrypt_bytes(&mut self, value: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(value.to_vec())
+    }
+
+    fn encrypt(&mut self, value: &str) -> Result<String, Error> {
+        Ok(value.to_string())
+    }
 }
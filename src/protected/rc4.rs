@@ -0,0 +1,89 @@
+use Error;
+
+use super::ProtectedStream;
+
+use rustc_serialize::base64::{FromBase64, STANDARD, ToBase64};
+
+const DROP_LENGTH: usize = 512;
+
+pub struct Rc4 {
+    state: [u8; 256],
+    i: u8,
+    j: u8,
+}
+
+impl Rc4 {
+    pub fn new(key: &[u8]) -> Rc4 {
+        let mut state = [0; 256];
+        for (index, value) in state.iter_mut().enumerate() {
+            *value = index as u8;
+        }
+
+        // KeePass swaps with the first entry instead of the current one in its key setup.
+        let mut j: u8 = 0;
+        for (index, &byte) in (0..256).zip(key.iter().cycle()) {
+            j = j.wrapping_add(state[index]).wrapping_add(byte);
+            state.swap(0, j as usize);
+        }
+
+        let mut rc4 = Rc4 {
+            state: state,
+            i: 0,
+            j: 0,
+        };
+        rc4.process(&[0; DROP_LENGTH]);
+        rc4
+    }
+
+    fn process(&mut self, value: &[u8]) -> Vec<u8> {
+        value.iter()
+             .map(|&byte| {
+                 self.i = self.i.wrapping_add(1);
+                 self.j = self.j.wrapping_add(self.state[self.i as usize]);
+                 self.state.swap(self.i as usize, self.j as usize);
+
+                 let index = self.state[self.i as usize].wrapping_add(self.state[self.j as usize]);
+                 byte ^ self.state[index as usize]
+             })
+             .collect()
+    }
+}
+
+impl ProtectedStream for Rc4 {
+    fn decrypt(&mut self, value: &str) -> Result<String, Error> {
+        let in_buffer = try!(value.from_base64().map_err(|e| Error::Base64(e)));
+        let result = try!(self.decrypt_bytes(&in_buffer));
+
+        Ok(try!(String::from_utf8(result).map_err(|e| Error::Utf8(e))))
+    }
+
+    fn decrypt_bytes(&mut self, value: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(self.process(value))
+    }
+
+    fn encrypt(&mut self, value: &str) -> Result<String, Error> {
+        Ok(self.process(value.as_bytes()).to_base64(STANDARD))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rc4;
+    use protected::ProtectedStream;
+
+    const KEY: [u8; 32] = [4; 32];
+
+    #[test]
+    fn should_decrypt_passwords() {
+        let mut rc4 = Rc4::new(&KEY);
+        assert_eq!(rc4.decrypt("mTHadqawRA==").unwrap(), "hunter2");
+        assert_eq!(rc4.decrypt("8D60V6sfAYzvlX8u7w==").unwrap(), "correct horse");
+    }
+
+    #[test]
+    fn should_encrypt_password() {
+        let mut rc4 = Rc4::new(&KEY);
+        let result = rc4.encrypt("hunter2").unwrap();
+        assert_eq!(result, "mTHadqawRA==");
+    }
+}
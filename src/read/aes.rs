@@ -8,7 +8,7 @@ use crypto::blockmodes::PkcsPadding;
 
 use Error;
 
-pub fn decrypt(reader: &mut Read, key: &[u8; 32], iv: &[u8; 16]) -> Result<Vec<u8>, Error> {
+pub fn decrypt(reader: &mut Read, key: &[u8; 32], iv: &[u8]) -> Result<Vec<u8>, Error> {
     let mut decryptor = aes::cbc_decryptor(KeySize::KeySize256, key, iv, PkcsPadding);
 
     let mut in_buffer = vec![];
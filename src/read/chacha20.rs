@@ -0,0 +1,28 @@
+use util;
+
+use std::io::Read;
+
+use crypto::chacha20::ChaCha20;
+
+use Error;
+
+pub fn decrypt(reader: &mut Read, key: &[u8; 32], iv: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut decryptor = ChaCha20::new(key, iv);
+
+    let mut in_buffer = vec![];
+    try!(reader.read_to_end(&mut in_buffer).map_err(|e| Error::Io(e)));
+
+    let result = try!(util::decrypt(&mut decryptor, &in_buffer));
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn should_decrypt() {
+        let encrypted = [0x6e, 0x94, 0x96, 0xa2, 0x0f, 0xb7, 0xf5];
+
+        let result = super::decrypt(&mut &encrypted[..], &[1; 32], &[2; 12]).unwrap();
+        assert_eq!(&result[..], b"hunter2");
+    }
+}
@@ -0,0 +1,131 @@
+use std::io;
+use std::io::{Cursor, Read};
+
+use bytes;
+use util::{hmac_sha256, sha512};
+use Error;
+
+use byteorder::{ByteOrder, LittleEndian};
+
+pub struct HmacBlockReader<'a> {
+    delegate: &'a mut Read,
+    hmac_key: [u8; 64],
+    next_block_index: u64,
+    block: Cursor<Vec<u8>>,
+}
+
+impl<'a> HmacBlockReader<'a> {
+    pub fn new(delegate: &'a mut Read, hmac_key: &[u8; 64]) -> HmacBlockReader<'a> {
+        HmacBlockReader {
+            delegate: delegate,
+            hmac_key: *hmac_key,
+            next_block_index: 0,
+            block: Cursor::new(vec![]),
+        }
+    }
+
+    fn read_next_block(&mut self) -> Result<(), Error> {
+        let hmac = try!(read_array!(self.delegate, 32));
+        let size = try!(bytes::read_u32(self.delegate));
+        let block = try!(bytes::read_bytes(self.delegate, size as usize));
+
+        try!(check_block(self.next_block_index, &block, &hmac, &self.hmac_key));
+
+        self.block = Cursor::new(block);
+        self.next_block_index += 1;
+
+        Ok(())
+    }
+}
+
+impl<'a> Read for HmacBlockReader<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let result = try!(self.block.read(buf));
+
+        if result > 0 {
+            Ok(result)
+        } else {
+            try!(self.read_next_block().map_err(|e| io::Error::new(io::ErrorKind::Other, e)));
+            self.block.read(buf)
+        }
+    }
+}
+
+pub fn block_key(index: u64, hmac_key: &[u8; 64]) -> [u8; 64] {
+    let mut buffer = vec![0; 8];
+    LittleEndian::write_u64(&mut buffer, index);
+    buffer.extend(hmac_key.iter().cloned());
+
+    sha512(&buffer)
+}
+
+fn check_block(index: u64,
+               block: &[u8],
+               hmac: &[u8; 32],
+               hmac_key: &[u8; 64])
+               -> Result<(), Error> {
+    let mut buffer = vec![0; 12];
+    LittleEndian::write_u64(&mut buffer[0..8], index);
+    LittleEndian::write_u32(&mut buffer[8..12], block.len() as u32);
+    buffer.extend(block.iter().cloned());
+
+    if hmac_sha256(&block_key(index, hmac_key), &buffer) == *hmac {
+        Ok(())
+    } else {
+        Err(Error::IncorrectBlockHmac)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HmacBlockReader;
+
+    use Error;
+    use util::hmac_sha256;
+
+    use std::io::Read;
+
+    use byteorder::{LittleEndian, WriteBytesExt};
+
+    const HMAC_KEY: [u8; 64] = [7; 64];
+
+    fn write_block(bytes: &mut Vec<u8>, index: u64, block: &[u8]) {
+        let mut buffer = vec![];
+        buffer.write_u64::<LittleEndian>(index).unwrap();
+        buffer.write_u32::<LittleEndian>(block.len() as u32).unwrap();
+        buffer.extend(block.iter().cloned());
+
+        bytes.extend(hmac_sha256(&super::block_key(index, &HMAC_KEY), &buffer).iter().cloned());
+        bytes.write_u32::<LittleEndian>(block.len() as u32).unwrap();
+        bytes.extend(block.iter().cloned());
+    }
+
+    #[test]
+    fn should_read_blocks() {
+        let mut bytes = vec![];
+        write_block(&mut bytes, 0, b"hunter");
+        write_block(&mut bytes, 1, b"2");
+        write_block(&mut bytes, 2, b"");
+
+        let reader = &mut &bytes[..];
+        let mut result = vec![];
+        HmacBlockReader::new(reader, &HMAC_KEY).read_to_end(&mut result).unwrap();
+
+        assert_eq!(&result[..], b"hunter2");
+    }
+
+    #[test]
+    fn should_return_error_if_block_out_of_order() {
+        let mut bytes = vec![];
+        write_block(&mut bytes, 1, b"hunter2");
+
+        let reader = &mut &bytes[..];
+        let mut result = vec![];
+        let error = HmacBlockReader::new(reader, &HMAC_KEY).read_to_end(&mut result).unwrap_err();
+
+        match error.into_inner().unwrap().downcast::<Error>().map(|e| *e) {
+            Ok(Error::IncorrectBlockHmac) => (),
+            e => panic!("Invalid result: {:#?}", e),
+        }
+    }
+}
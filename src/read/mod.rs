@@ -1,24 +1,68 @@
-mod aes;
-mod block;
-mod xml;
+pub mod aes;
+pub mod block;
+pub mod chacha20;
+pub mod twofish;
+mod hmac_block;
+pub mod xml;
 
-use {Database, Error};
+use {CompositeKey, Database, Error};
+use header;
 use header::Header;
+use util::{hmac_sha256, sha256};
 
 use std::io::{Cursor, Read};
 
 use self::block::BlockReader;
+use self::hmac_block::HmacBlockReader;
 
-pub fn read(reader: &mut Read, header: &Header, passphrase: &str) -> Result<Database, Error> {
-    let key = try!(header.master_key(passphrase));
-    let mut stream = Cursor::new(try!(aes::decrypt(reader, &key, &header.encryption_iv())));
+pub fn read(reader: &mut Read,
+            header: &Header,
+            header_bytes: &[u8],
+            key: &CompositeKey)
+            -> Result<Database, Error> {
+    if header.major_version() >= 4 {
+        read_kdbx4(reader, header, header_bytes, key)
+    } else {
+        read_kdbx3(reader, header, key)
+    }
+}
+
+fn read_kdbx3(reader: &mut Read, header: &Header, key: &CompositeKey) -> Result<Database, Error> {
+    let master_key = try!(header.master_key(key));
+    let mut stream = Cursor::new(try!(header.decrypt(reader, &master_key)));
 
     let result = try!(read_array!(&mut stream, 32));
-    try!(check_key(&result, &header.stream_start_bytes()));
+    try!(check_key(&result, &try!(header.stream_start_bytes())));
 
     let block_reader = BlockReader::new(Box::new(stream));
     let mut decompressed = try!(header.decompress(Box::new(block_reader)));
-    xml::read(&mut decompressed, &mut *header.protected_stream())
+    xml::read(&mut decompressed, &mut *try!(header.protected_stream()), vec![])
+}
+
+fn read_kdbx4(reader: &mut Read,
+              header: &Header,
+              header_bytes: &[u8],
+              key: &CompositeKey)
+              -> Result<Database, Error> {
+    let hash = try!(read_array!(reader, 32));
+    try!(check_header_hash(header_bytes, &hash));
+
+    let (master_key, hmac_key) = try!(header.keys(key));
+
+    let hmac = try!(read_array!(reader, 32));
+    try!(check_header_hmac(header_bytes, &hmac, &hmac_key));
+
+    let mut encrypted = vec![];
+    try!(HmacBlockReader::new(reader, &hmac_key)
+             .read_to_end(&mut encrypted)
+             .map_err(|e| Error::Io(e)));
+
+    let stream = Cursor::new(try!(header.decrypt(&mut &encrypted[..], &master_key)));
+    let mut decompressed = try!(header.decompress(Box::new(stream)));
+
+    let inner_header = try!(header::read_inner_header(&mut decompressed));
+    let binaries = inner_header.binaries().to_vec();
+    xml::read(&mut decompressed, &mut *inner_header.protected_stream(), binaries)
 }
 
 fn check_key(result: &[u8; 32], expected: &[u8; 32]) -> Result<(), Error> {
@@ -28,3 +72,24 @@ fn check_key(result: &[u8; 32], expected: &[u8; 32]) -> Result<(), Error> {
         Err(Error::IncorrectStartBytes)
     }
 }
+
+fn check_header_hash(header_bytes: &[u8], expected: &[u8; 32]) -> Result<(), Error> {
+    if sha256(header_bytes) == *expected {
+        Ok(())
+    } else {
+        Err(Error::IncorrectHeaderHash)
+    }
+}
+
+fn check_header_hmac(header_bytes: &[u8],
+                     expected: &[u8; 32],
+                     hmac_key: &[u8; 64])
+                     -> Result<(), Error> {
+    let key = hmac_block::block_key(u64::max_value(), hmac_key);
+
+    if hmac_sha256(&key, header_bytes) == *expected {
+        Ok(())
+    } else {
+        Err(Error::IncorrectHeaderHmac)
+    }
+}
@@ -0,0 +1,18 @@
+use twofish::Twofish;
+use util;
+
+use std::io::Read;
+
+use crypto::blockmodes::{CbcDecryptor, PkcsPadding};
+
+use Error;
+
+pub fn decrypt(reader: &mut Read, key: &[u8; 32], iv: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut decryptor = CbcDecryptor::new(Twofish::new(key), PkcsPadding, iv.to_vec());
+
+    let mut in_buffer = vec![];
+    try!(reader.read_to_end(&mut in_buffer).map_err(|e| Error::Io(e)));
+
+    let result = try!(util::decrypt(&mut decryptor, &in_buffer));
+    Ok(result)
+}
@@ -0,0 +1,102 @@
+use {Association, AutoType, Error, Obfuscation};
+
+use xml::reader::XmlEvent;
+
+pub fn read(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>) -> Result<AutoType, Error> {
+    let mut auto_type = AutoType::new();
+
+    loop {
+        match iterator.next() {
+            Some(Ok(XmlEvent::StartElement { name, .. })) => {
+                match &name.local_name[..] {
+                    "Enabled" => {
+                        if let Some(enabled) = try!(super::read_bool(iterator, "Enabled")) {
+                            auto_type.set_enabled(enabled);
+                        }
+                    }
+                    "DataTransferObfuscation" => {
+                        let value = try!(super::read_number(iterator, "DataTransferObfuscation"));
+                        if let Some(obfuscation) = value.and_then(Obfuscation::from_u32) {
+                            auto_type.set_obfuscation(obfuscation);
+                        }
+                    }
+                    "DefaultSequence" => {
+                        let sequence = try!(super::read_chars(iterator, "DefaultSequence"));
+                        auto_type.set_default_sequence(sequence);
+                    }
+                    "Association" => auto_type.add_association(try!(read_association(iterator))),
+                    _ => {}
+                }
+            }
+            Some(Ok(XmlEvent::EndElement { name, .. })) => {
+                if name.local_name == "AutoType" {
+                    break;
+                }
+            }
+
+            Some(Err(e)) => return Err(e),
+            None => break,
+            _ => {}
+        }
+    }
+
+    Ok(auto_type)
+}
+
+fn read_association(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>)
+                    -> Result<Association, Error> {
+    let mut window = None;
+    let mut sequence = None;
+
+    loop {
+        match iterator.next() {
+            Some(Ok(XmlEvent::StartElement { name, .. })) => {
+                match &name.local_name[..] {
+                    "Window" => window = try!(super::read_chars(iterator, "Window")),
+                    "KeystrokeSequence" => {
+                        sequence = try!(super::read_chars(iterator, "KeystrokeSequence"))
+                    }
+                    _ => {}
+                }
+            }
+            Some(Ok(XmlEvent::EndElement { name, .. })) => {
+                if name.local_name == "Association" {
+                    break;
+                }
+            }
+
+            Some(Err(e)) => return Err(e),
+            None => break,
+            _ => {}
+        }
+    }
+
+    Ok(Association::new(&window.unwrap_or_default(), sequence))
+}
+
+#[cfg(test)]
+mod tests {
+    use {Error, Obfuscation};
+
+    use std::fs::File;
+    use xml::reader::EventReader;
+
+    #[test]
+    fn should_read_auto_type() {
+        let file = File::open("data/xml/auto_type/valid.xml").unwrap();
+        let event_reader = EventReader::new(file);
+        let mut iterator = event_reader.into_iter().map(|result| result.map_err(|e| Error::Xml(e)));
+        let auto_type = super::read(&mut iterator).unwrap();
+
+        assert!(!auto_type.enabled());
+        assert_eq!(auto_type.obfuscation(), Obfuscation::UseClipboard);
+        assert_eq!(auto_type.default_sequence().as_ref().unwrap(),
+                   "{USERNAME}{TAB}{PASSWORD}{ENTER}{DELAY 200}");
+
+        assert_eq!(auto_type.associations().len(), 2);
+        assert_eq!(auto_type.associations()[0].window(), "*Firefox");
+        assert_eq!(auto_type.associations()[0].sequence().as_ref().unwrap(),
+                   "{PASSWORD}{ENTER}");
+        assert!(auto_type.associations()[1].sequence().is_none());
+    }
+}
@@ -0,0 +1,105 @@
+use Error;
+use decompress;
+use protected::ProtectedStream;
+
+use std::io::{Cursor, Read};
+
+use rustc_serialize::base64::FromBase64;
+use xml::attribute::OwnedAttribute;
+use xml::reader::XmlEvent;
+
+pub fn read_pool(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
+                 protected: &mut ProtectedStream,
+                 binaries: &mut Vec<Vec<u8>>)
+                 -> Result<(), Error> {
+    loop {
+        match iterator.next() {
+            Some(Ok(XmlEvent::StartElement { name, attributes, .. })) => {
+                if name.local_name == "Binary" {
+                    let id = attribute(&attributes, "ID").and_then(|id| id.parse().ok());
+                    let data = try!(read_value(iterator, protected, &attributes, "Binary"));
+
+                    match id {
+                        Some(id) => {
+                            if binaries.len() <= id {
+                                binaries.resize(id + 1, vec![]);
+                            }
+                            binaries[id] = data;
+                        }
+                        None => binaries.push(data),
+                    }
+                }
+            }
+            Some(Ok(XmlEvent::EndElement { name, .. })) => {
+                if name.local_name == "Binaries" {
+                    break;
+                }
+            }
+
+            Some(Err(e)) => return Err(e),
+            None => break,
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+pub fn read_value(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
+                  protected: &mut ProtectedStream,
+                  attributes: &[OwnedAttribute],
+                  element: &str)
+                  -> Result<Vec<u8>, Error> {
+    let value = try!(super::read_chars(iterator, element)).unwrap_or_default();
+    let mut data = try!(value.trim().from_base64().map_err(|e| Error::Base64(e)));
+
+    if attribute(attributes, "Protected") == Some("True") {
+        data = try!(protected.decrypt_bytes(&data));
+    }
+
+    if attribute(attributes, "Compressed") == Some("True") {
+        let mut decompressed = vec![];
+        try!(try!(decompress::gzip(Box::new(Cursor::new(data))))
+                 .read_to_end(&mut decompressed)
+                 .map_err(|e| Error::Io(e)));
+        data = decompressed;
+    }
+
+    Ok(data)
+}
+
+pub fn attribute<'a>(attributes: &'a [OwnedAttribute], name: &str) -> Option<&'a str> {
+    attributes.iter()
+              .find(|attribute| attribute.name.local_name == name)
+              .map(|attribute| &attribute.value[..])
+}
+
+#[cfg(test)]
+mod tests {
+    use protected::ProtectedStream;
+
+    use Error;
+
+    use std::fs::File;
+    use xml::reader::EventReader;
+
+    const KEY: [u8; 32] = [0xE4, 0x70, 0xC4, 0xEF, 0x95, 0x61, 0x22, 0xDF, 0x2C, 0x0D, 0xD1, 0x42,
+                           0x4A, 0x24, 0xE6, 0x87, 0x79, 0x29, 0xB9, 0xAD, 0x47, 0x9C, 0x0E, 0xA5,
+                           0xA0, 0x5D, 0xB1, 0x27, 0x7A, 0xDF, 0xBD, 0xCD];
+
+    #[test]
+    fn should_read_binary_pool() {
+        let file = File::open("data/xml/binary/pool.xml").unwrap();
+        let event_reader = EventReader::new(file);
+        let mut iterator = event_reader.into_iter().map(|result| result.map_err(|e| Error::Xml(e)));
+
+        let mut protected = ProtectedStream::salsa20(&KEY);
+        let mut binaries = vec![];
+        super::read_pool(&mut iterator, &mut *protected, &mut binaries).unwrap();
+
+        assert_eq!(binaries.len(), 3);
+        assert_eq!(binaries[0], b"Hello, world!\n");
+        assert_eq!(binaries[1], b"-----BEGIN CERTIFICATE-----\n");
+        assert_eq!(binaries[2], b"hunter2");
+    }
+}
@@ -0,0 +1,64 @@
+use {DeletedObject, Error};
+
+use xml::reader::XmlEvent;
+
+pub fn read(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>)
+            -> Result<Vec<DeletedObject>, Error> {
+    let mut deleted_objects = vec![];
+    let mut uuid = None;
+    let mut deletion_time = None;
+
+    loop {
+        match iterator.next() {
+            Some(Ok(XmlEvent::StartElement { name, .. })) => {
+                match &name.local_name[..] {
+                    "UUID" => uuid = try!(super::read_uuid(iterator, "UUID")),
+                    "DeletionTime" => {
+                        deletion_time = try!(super::times::read_timestamp(iterator,
+                                                                          "DeletionTime"))
+                    }
+                    _ => {}
+                }
+            }
+            Some(Ok(XmlEvent::EndElement { name, .. })) => {
+                match &name.local_name[..] {
+                    "DeletedObject" => {
+                        if let Some(uuid) = uuid.take() {
+                            deleted_objects.push(DeletedObject::new(uuid, deletion_time.take()));
+                        }
+                    }
+                    "DeletedObjects" => break,
+                    _ => {}
+                }
+            }
+
+            Some(Err(e)) => return Err(e),
+            None => break,
+            _ => {}
+        }
+    }
+
+    Ok(deleted_objects)
+}
+
+#[cfg(test)]
+mod tests {
+    use {Error, Timestamp};
+
+    use std::fs::File;
+    use xml::reader::EventReader;
+
+    #[test]
+    fn should_read_deleted_objects() {
+        let file = File::open("data/xml/deleted_objects/valid.xml").unwrap();
+        let event_reader = EventReader::new(file);
+        let mut iterator = event_reader.into_iter().map(|result| result.map_err(|e| Error::Xml(e)));
+        let deleted_objects = super::read(&mut iterator).unwrap();
+
+        assert_eq!(deleted_objects.len(), 2);
+        assert_eq!(deleted_objects[0].uuid().to_string(), "BoUeiK+XGUeIQisekLa2uw==");
+        assert_eq!(deleted_objects[0].deletion_time(),
+                   Some(Timestamp::new(2016, 8, 30, 14, 46, 12)));
+        assert!(deleted_objects[1].deletion_time().is_none());
+    }
+}
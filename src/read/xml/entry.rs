@@ -1,27 +1,71 @@
-use {DatabaseEntry, Error};
+use {Attachment, DatabaseEntry, Error, Uuid};
 use protected::ProtectedStream;
 
 use xml::reader::XmlEvent;
 
 pub fn read(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
-            protected: &mut ProtectedStream)
+            protected: &mut ProtectedStream,
+            binaries: &[Vec<u8>])
             -> Result<DatabaseEntry, Error> {
-    let mut title = None;
-    let mut username = None;
-    let mut password = None;
+    read_entry(iterator, protected, binaries, false)
+}
+
+fn read_entry(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
+              protected: &mut ProtectedStream,
+              binaries: &[Vec<u8>],
+              is_history: bool)
+              -> Result<DatabaseEntry, Error> {
+    let mut entry = DatabaseEntry::default();
+    let mut history = vec![];
+    let mut last = None;
 
     loop {
         match iterator.next() {
-            Some(Ok(XmlEvent::StartElement { name, .. })) => {
-                if name.local_name == "String" {
-                    let kv = try!(super::kv::read(iterator, protected));
-                    match &kv.key[..] {
-                        "Title" => title = kv.value,
-                        "UserName" => username = kv.value,
-                        "Password" => password = kv.value,
-                        _ => {}
+            Some(Ok(XmlEvent::StartElement { name, attributes, namespace })) => {
+                match &name.local_name[..] {
+                    "UUID" => {
+                        if let Some(uuid) = try!(super::read_uuid(iterator, "UUID")) {
+                            entry.set_uuid(uuid);
+                        }
+                    }
+                    "IconID" => entry.set_icon_id(try!(super::read_number(iterator, "IconID"))),
+                    "CustomIconUUID" => {
+                        entry.set_custom_icon_uuid(try!(super::read_uuid(iterator,
+                                                                         "CustomIconUUID")))
+                    }
+                    "ForegroundColor" => {
+                        let color = try!(super::read_chars(iterator, "ForegroundColor"));
+                        entry.set_foreground_color(color);
+                    }
+                    "BackgroundColor" => {
+                        let color = try!(super::read_chars(iterator, "BackgroundColor"));
+                        entry.set_background_color(color);
+                    }
+                    "Tags" => entry.set_tags(try!(super::read_tags(iterator, "Tags"))),
+                    "String" => {
+                        let kv = try!(super::kv::read(iterator, protected));
+                        entry.set_field(&kv.key, kv.value, kv.protected);
+                    }
+                    "Binary" => {
+                        entry.add_attachment(try!(read_attachment(iterator, protected, binaries)))
+                    }
+                    "Times" => *entry.times_mut() = try!(super::times::read(iterator)),
+                    "AutoType" => {
+                        *entry.auto_type_mut() = try!(super::auto_type::read(iterator))
+                    }
+                    "History" => {
+                        history.extend(try!(read_history(iterator, protected, binaries)))
+                    }
+                    _ => {
+                        let element = try!(super::read_element(iterator,
+                                                               &name,
+                                                               attributes,
+                                                               &namespace));
+                        entry.add_unknown_element(element, last.clone());
+                        continue;
                     }
                 }
+                last = Some(name.local_name);
             }
             Some(Ok(XmlEvent::EndElement { name, .. })) => {
                 if name.local_name == "Entry" {
@@ -35,7 +79,93 @@ pub fn read(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
         }
     }
 
-    Ok(DatabaseEntry::new(title, username, password))
+    if entry.uuid().is_nil() && !is_history {
+        entry.set_uuid(Uuid::new_v4());
+    }
+    for mut past in history {
+        if past.uuid().is_nil() {
+            past.set_uuid(*entry.uuid());
+        }
+        entry.add_history(past);
+    }
+
+    Ok(entry)
+}
+
+fn read_history(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
+                protected: &mut ProtectedStream,
+                binaries: &[Vec<u8>])
+                -> Result<Vec<DatabaseEntry>, Error> {
+    let mut history = vec![];
+
+    loop {
+        match iterator.next() {
+            Some(Ok(XmlEvent::StartElement { name, .. })) => {
+                if name.local_name == "Entry" {
+                    history.push(try!(read_entry(iterator, protected, binaries, true)));
+                }
+            }
+            Some(Ok(XmlEvent::EndElement { name, .. })) => {
+                if name.local_name == "History" {
+                    break;
+                }
+            }
+
+            Some(Err(e)) => return Err(e),
+            None => break,
+            _ => {}
+        }
+    }
+
+    Ok(history)
+}
+
+fn read_attachment(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
+                   protected: &mut ProtectedStream,
+                   binaries: &[Vec<u8>])
+                   -> Result<Attachment, Error> {
+    let mut key = None;
+    let mut data = None;
+
+    loop {
+        match iterator.next() {
+            Some(Ok(XmlEvent::StartElement { name, attributes, .. })) => {
+                match &name.local_name[..] {
+                    "Key" => key = try!(super::read_chars(iterator, "Key")),
+                    "Value" => {
+                        match super::binary::attribute(&attributes, "Ref") {
+                            Some(reference) => {
+                                let binary = reference.parse()
+                                                      .ok()
+                                                      .and_then(|id: usize| binaries.get(id));
+                                let missing = Error::MissingBinary(reference.to_string());
+                                data = Some(try!(binary.cloned().ok_or(missing)));
+                                try!(super::read_chars(iterator, "Value"));
+                            }
+                            None => {
+                                data = Some(try!(super::binary::read_value(iterator,
+                                                                           protected,
+                                                                           &attributes,
+                                                                           "Value")))
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Some(Ok(XmlEvent::EndElement { name, .. })) => {
+                if name.local_name == "Binary" {
+                    break;
+                }
+            }
+
+            Some(Err(e)) => return Err(e),
+            None => break,
+            _ => {}
+        }
+    }
+
+    Ok(Attachment::new(&try!(key.ok_or(Error::MissingKey)), data.unwrap_or_default()))
 }
 
 #[cfg(test)]
@@ -53,8 +183,9 @@ mod tests {
         let file = File::open("data/xml/entry/valid.xml").unwrap();
         let event_reader = EventReader::new(file);
         let mut iterator = event_reader.into_iter().map(|result| result.map_err(|e| Error::Xml(e)));
+        iterator.by_ref().take(2).count(); // Skip StartDocument and <Entry>
         let mut protected = ProtectedStream::none();
-        let entry = super::read(&mut iterator, &mut *protected).unwrap();
+        let entry = super::read(&mut iterator, &mut *protected, &[]).unwrap();
 
         assert!(entry.title().is_some());
         assert_eq!(entry.title().as_ref().unwrap(), "http://example.com");
@@ -66,16 +197,132 @@ mod tests {
         assert_eq!(entry.password().as_ref().unwrap(), "9crW5hp7SQ==");
     }
 
+    #[test]
+    fn should_read_appearance() {
+        let file = File::open("data/xml/entry/appearance.xml").unwrap();
+        let event_reader = EventReader::new(file);
+        let mut iterator = event_reader.into_iter().map(|result| result.map_err(|e| Error::Xml(e)));
+        iterator.by_ref().take(2).count(); // Skip StartDocument and <Entry>
+        let mut protected = ProtectedStream::none();
+        let entry = super::read(&mut iterator, &mut *protected, &[]).unwrap();
+
+        assert_eq!(entry.icon_id(), Some(12));
+        assert_eq!(entry.custom_icon_uuid().unwrap().to_string(), "7n1R2qLNQkmRQyMQbD5aEw==");
+        assert_eq!(entry.foreground_color().as_ref().unwrap(), "#FF0000");
+        assert_eq!(entry.background_color().as_ref().unwrap(), "#FFFFE0");
+        assert_eq!(entry.tags(), ["prod", "backend", "eu-west"]);
+    }
+
+    #[test]
+    fn should_read_uuid() {
+        let file = File::open("data/xml/entry/history.xml").unwrap();
+        let event_reader = EventReader::new(file);
+        let mut iterator = event_reader.into_iter().map(|result| result.map_err(|e| Error::Xml(e)));
+        iterator.by_ref().take(2).count(); // Skip StartDocument and <Entry>
+        let mut protected = ProtectedStream::none();
+        let entry = super::read(&mut iterator, &mut *protected, &[]).unwrap();
+
+        assert_eq!(entry.uuid().to_string(), "vO3ZvhvC9k2ZPvbq5QKfxw==");
+        assert_eq!(entry.history()[0].uuid(), entry.uuid());
+    }
+
+    #[test]
+    fn should_assign_uuid_if_missing() {
+        let file = File::open("data/xml/entry/no_uuid.xml").unwrap();
+        let event_reader = EventReader::new(file);
+        let mut iterator = event_reader.into_iter().map(|result| result.map_err(|e| Error::Xml(e)));
+        iterator.by_ref().take(2).count(); // Skip StartDocument and <Entry>
+        let mut protected = ProtectedStream::none();
+        let entry = super::read(&mut iterator, &mut *protected, &[]).unwrap();
+
+        assert!(!entry.uuid().is_nil());
+        assert_eq!(entry.history()[0].uuid(), entry.uuid());
+    }
+
     #[test]
     fn should_handle_missing_values() {
         let file = File::open("data/xml/entry/no_values.xml").unwrap();
         let event_reader = EventReader::new(file);
         let mut iterator = event_reader.into_iter().map(|result| result.map_err(|e| Error::Xml(e)));
+        iterator.by_ref().take(2).count(); // Skip StartDocument and <Entry>
         let mut protected = ProtectedStream::none();
-        let entry = super::read(&mut iterator, &mut *protected).unwrap();
+        let entry = super::read(&mut iterator, &mut *protected, &[]).unwrap();
 
         assert!(entry.title().is_none());
         assert!(entry.username().is_none());
         assert!(entry.password().is_none());
     }
+
+    #[test]
+    fn should_read_custom_fields() {
+        let file = File::open("data/xml/entry/custom_fields.xml").unwrap();
+        let event_reader = EventReader::new(file);
+        let mut iterator = event_reader.into_iter().map(|result| result.map_err(|e| Error::Xml(e)));
+        iterator.by_ref().take(2).count(); // Skip StartDocument and <Entry>
+        let mut protected = ProtectedStream::none();
+        let entry = super::read(&mut iterator, &mut *protected, &[]).unwrap();
+
+        assert_eq!(entry.url().as_ref().unwrap(), "https://github.com");
+        assert_eq!(entry.notes().as_ref().unwrap(), "Deploy token");
+        assert_eq!(entry.get("API_KEY"), Some("c2VjcmV0"));
+        assert!(entry.field("API_KEY").unwrap().is_protected());
+
+        let keys: Vec<&str> = entry.custom_fields().map(|field| field.key()).collect();
+        assert_eq!(keys, vec!["API_KEY", "Region"]);
+
+        let keys: Vec<&str> = entry.fields().iter().map(|field| field.key()).collect();
+        assert_eq!(keys,
+                   vec!["API_KEY", "Notes", "Password", "Region", "Title", "URL", "UserName"]);
+    }
+
+    #[test]
+    fn should_read_attachments() {
+        let file = File::open("data/xml/entry/attachments.xml").unwrap();
+        let event_reader = EventReader::new(file);
+        let mut iterator = event_reader.into_iter().map(|result| result.map_err(|e| Error::Xml(e)));
+        iterator.by_ref().take(2).count(); // Skip StartDocument and <Entry>
+        let mut protected = ProtectedStream::none();
+        let binaries = vec![b"ssh-ed25519 AAAA".to_vec(), b"-----BEGIN CERTIFICATE-----".to_vec()];
+        let entry = super::read(&mut iterator, &mut *protected, &binaries).unwrap();
+
+        assert_eq!(entry.attachments().len(), 2);
+        assert_eq!(entry.attachments()[0].name(), "id_ed25519.pub");
+        assert_eq!(entry.attachments()[0].data(), b"ssh-ed25519 AAAA");
+        assert_eq!(entry.attachment("server.crt").unwrap().data(),
+                   b"-----BEGIN CERTIFICATE-----");
+    }
+
+    #[test]
+    fn should_return_error_if_missing_binary() {
+        let file = File::open("data/xml/entry/attachments.xml").unwrap();
+        let event_reader = EventReader::new(file);
+        let mut iterator = event_reader.into_iter().map(|result| result.map_err(|e| Error::Xml(e)));
+        iterator.by_ref().take(2).count(); // Skip StartDocument and <Entry>
+        let mut protected = ProtectedStream::none();
+        let result = super::read(&mut iterator, &mut *protected, &[]);
+
+        match result {
+            Err(Error::MissingBinary(ref id)) if id == "0" => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
+
+    #[test]
+    fn should_read_history() {
+        let file = File::open("data/xml/entry/history.xml").unwrap();
+        let event_reader = EventReader::new(file);
+        let mut iterator = event_reader.into_iter().map(|result| result.map_err(|e| Error::Xml(e)));
+        iterator.by_ref().take(2).count(); // Skip StartDocument and <Entry>
+        let mut protected = ProtectedStream::none();
+        let entry = super::read(&mut iterator, &mut *protected, &[]).unwrap();
+
+        assert_eq!(entry.title().as_ref().unwrap(), "GitHub");
+        assert_eq!(entry.password().as_ref().unwrap(), "hunter3");
+        assert_eq!(entry.url().as_ref().unwrap(), "https://github.com");
+
+        assert_eq!(entry.history().len(), 2);
+        assert_eq!(entry.history()[0].password().as_ref().unwrap(), "hunter1");
+        assert_eq!(entry.history()[1].password().as_ref().unwrap(), "hunter2");
+        assert!(entry.history()[0].url().is_none());
+    }
 }
@@ -0,0 +1,121 @@
+use {Error, Group};
+use protected::ProtectedStream;
+
+use xml::reader::XmlEvent;
+
+pub fn read(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
+            protected: &mut ProtectedStream,
+            binaries: &[Vec<u8>])
+            -> Result<Group, Error> {
+    let mut group = Group::new("");
+    let mut last = None;
+
+    loop {
+        match iterator.next() {
+            Some(Ok(XmlEvent::StartElement { name, attributes, namespace })) => {
+                match &name.local_name[..] {
+                    "UUID" => {
+                        if let Some(uuid) = try!(super::read_uuid(iterator, "UUID")) {
+                            group.set_uuid(uuid);
+                        }
+                    }
+                    "Name" => {
+                        let name = try!(super::read_chars(iterator, "Name"));
+                        group.set_name(&name.unwrap_or_default());
+                    }
+                    "Notes" => group.set_notes(try!(super::read_chars(iterator, "Notes"))),
+                    "IconID" => group.set_icon_id(try!(super::read_number(iterator, "IconID"))),
+                    "CustomIconUUID" => {
+                        group.set_custom_icon_uuid(try!(super::read_uuid(iterator,
+                                                                         "CustomIconUUID")))
+                    }
+                    "Times" => *group.times_mut() = try!(super::times::read(iterator)),
+                    "IsExpanded" => {
+                        group.set_expanded(try!(super::read_bool(iterator, "IsExpanded")))
+                    }
+                    "DefaultAutoTypeSequence" => {
+                        let sequence = try!(super::read_chars(iterator, "DefaultAutoTypeSequence"));
+                        group.set_default_auto_type_sequence(sequence);
+                    }
+                    "EnableAutoType" => {
+                        let enable_auto_type = try!(super::read_bool(iterator, "EnableAutoType"));
+                        group.set_enable_auto_type(enable_auto_type);
+                    }
+                    "EnableSearching" => {
+                        let enable_searching = try!(super::read_bool(iterator, "EnableSearching"));
+                        group.set_enable_searching(enable_searching);
+                    }
+                    "LastTopVisibleEntry" => {
+                        let uuid = try!(super::read_uuid(iterator, "LastTopVisibleEntry"));
+                        group.set_last_top_visible_entry(uuid);
+                    }
+                    "Tags" => group.set_tags(try!(super::read_tags(iterator, "Tags"))),
+                    "Entry" => {
+                        group.add_entry(try!(super::entry::read(iterator, protected, binaries)))
+                    }
+                    "Group" => group.add_group(try!(read(iterator, protected, binaries))),
+                    _ => {
+                        let element = try!(super::read_element(iterator,
+                                                               &name,
+                                                               attributes,
+                                                               &namespace));
+                        group.add_unknown_element(element, last.clone());
+                        continue;
+                    }
+                }
+                last = Some(name.local_name);
+            }
+            Some(Ok(XmlEvent::EndElement { name, .. })) => {
+                if name.local_name == "Group" {
+                    break;
+                }
+            }
+
+            Some(Err(e)) => return Err(e),
+            None => break,
+            _ => {}
+        }
+    }
+
+    Ok(group)
+}
+
+#[cfg(test)]
+mod tests {
+    use protected::ProtectedStream;
+
+    use Error;
+
+    use std::fs::File;
+    use xml::reader::EventReader;
+
+    #[test]
+    fn should_read_nested_groups() {
+        let file = File::open("data/xml/group/nested.xml").unwrap();
+        let event_reader = EventReader::new(file);
+        let mut iterator = event_reader.into_iter().map(|result| result.map_err(|e| Error::Xml(e)));
+        iterator.by_ref().take(2).count(); // Skip StartDocument and the outermost <Group>
+
+        let mut protected = ProtectedStream::none();
+        let group = super::read(&mut iterator, &mut *protected, &[]).unwrap();
+
+        assert_eq!(group.name(), "Root");
+        assert_eq!(group.uuid().to_string(), "NQ7IDchX4EaBjopMEgDORA==");
+        assert_eq!(group.entries().len(), 1);
+        assert_eq!(group.groups().len(), 2);
+
+        let internet = group.group("Internet").unwrap();
+        assert_eq!(internet.notes().as_ref().unwrap(), "Websites");
+        assert_eq!(internet.icon_id(), Some(1));
+        assert_eq!(internet.is_expanded(), Some(true));
+        assert_eq!(internet.enable_auto_type(), None);
+        assert_eq!(internet.enable_searching(), Some(false));
+        assert_eq!(internet.default_auto_type_sequence().as_ref().unwrap(),
+                   "{USERNAME}{TAB}{PASSWORD}{ENTER}");
+
+        let entry = group.find_entry("Internet/Work/GitHub").unwrap();
+        assert_eq!(entry.username().as_ref().unwrap(), "joe.bloggs");
+
+        assert!(group.group("Email").unwrap().entries().is_empty());
+    }
+}
@@ -8,6 +8,7 @@ use xml::reader::XmlEvent;
 pub struct KeyValue {
     pub key: String,
     pub value: Option<String>,
+    pub protected: bool,
 }
 
 pub fn read(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
@@ -15,13 +16,17 @@ pub fn read(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
             -> Result<KeyValue, Error> {
     let mut key = None;
     let mut value = None;
+    let mut is_protected = false;
 
     loop {
         match iterator.next() {
             Some(Ok(XmlEvent::StartElement { name, attributes, .. })) => {
                 match &name.local_name[..] {
                     "Key" => key = try!(super::read_chars(iterator, "Key")),
-                    "Value" => value = try!(read_value(iterator, protected, &attributes[..])),
+                    "Value" => {
+                        is_protected = is_protected_value(&attributes[..]);
+                        value = try!(read_value(iterator, protected, is_protected));
+                    }
                     _ => {}
                 }
             }
@@ -40,21 +45,20 @@ pub fn read(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
     Ok(KeyValue {
         key: try!(key.ok_or(Error::MissingKey)),
         value: value,
+        protected: is_protected,
+    })
+}
+
+fn is_protected_value(attributes: &[OwnedAttribute]) -> bool {
+    attributes.iter().any(|attribute| {
+        attribute.name.local_name == "Protected" && attribute.value == "True"
     })
 }
 
 fn read_value(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
               protected: &mut ProtectedStream,
-              attributes: &[OwnedAttribute])
+              is_protected: bool)
               -> Result<Option<String>, Error> {
-    let mut is_protected = false;
-
-    for attribute in attributes {
-        if attribute.name.local_name == "Protected" && attribute.value == "True" {
-            is_protected = true;
-        }
-    }
-
     match try!(super::read_chars(iterator, "Value")) {
         Some(value) => {
             if is_protected {
@@ -135,6 +139,7 @@ mod tests {
         let kv = super::read(&mut iterator, &mut *protected).unwrap();
 
         assert_eq!(kv.key, "Password");
+        assert!(kv.protected);
 
         assert!(kv.value.is_some());
         assert_eq!(kv.value.unwrap(), "hunter2");
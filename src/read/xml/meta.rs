@@ -0,0 +1,255 @@
+use {CustomIcon, Error, MemoryProtection, Meta, Uuid};
+use protected::ProtectedStream;
+
+use rustc_serialize::base64::FromBase64;
+use xml::reader::XmlEvent;
+
+pub fn read(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
+            protected: &mut ProtectedStream,
+            binaries: &mut Vec<Vec<u8>>)
+            -> Result<Meta, Error> {
+    let mut meta = Meta::new();
+    let mut last = None;
+
+    loop {
+        match iterator.next() {
+            Some(Ok(XmlEvent::StartElement { name, attributes, namespace })) => {
+                match &name.local_name[..] {
+                    "Generator" => {
+                        meta.set_generator(try!(super::read_chars(iterator, "Generator")))
+                    }
+                    "DatabaseName" => {
+                        meta.set_database_name(try!(super::read_chars(iterator, "DatabaseName")))
+                    }
+                    "DatabaseNameChanged" => {
+                        let time = try!(super::times::read_timestamp(iterator,
+                                                                     "DatabaseNameChanged"));
+                        meta.set_database_name_changed(time);
+                    }
+                    "DatabaseDescription" => {
+                        let description = try!(super::read_chars(iterator, "DatabaseDescription"));
+                        meta.set_database_description(description);
+                    }
+                    "DatabaseDescriptionChanged" => {
+                        let time = try!(super::times::read_timestamp(iterator,
+                                                                     "DatabaseDescriptionChanged"));
+                        meta.set_database_description_changed(time);
+                    }
+                    "DefaultUserName" => {
+                        let username = try!(super::read_chars(iterator, "DefaultUserName"));
+                        meta.set_default_username(username);
+                    }
+                    "DefaultUserNameChanged" => {
+                        let time = try!(super::times::read_timestamp(iterator,
+                                                                     "DefaultUserNameChanged"));
+                        meta.set_default_username_changed(time);
+                    }
+                    "MaintenanceHistoryDays" => {
+                        if let Some(days) = try!(super::read_number(iterator,
+                                                                    "MaintenanceHistoryDays")) {
+                            meta.set_maintenance_history_days(days);
+                        }
+                    }
+                    "Color" => meta.set_color(try!(super::read_chars(iterator, "Color"))),
+                    "MasterKeyChanged" => {
+                        let time = try!(super::times::read_timestamp(iterator, "MasterKeyChanged"));
+                        meta.set_master_key_changed(time);
+                    }
+                    "MasterKeyChangeRec" => {
+                        if let Some(days) = try!(super::read_number(iterator,
+                                                                    "MasterKeyChangeRec")) {
+                            meta.set_master_key_change_recommended(days);
+                        }
+                    }
+                    "MasterKeyChangeForce" => {
+                        if let Some(days) = try!(super::read_number(iterator,
+                                                                    "MasterKeyChangeForce")) {
+                            meta.set_master_key_change_forced(days);
+                        }
+                    }
+                    "MemoryProtection" => {
+                        *meta.memory_protection_mut() = try!(read_memory_protection(iterator))
+                    }
+                    "CustomIcons" => {
+                        for icon in try!(read_custom_icons(iterator)) {
+                            meta.add_custom_icon(icon);
+                        }
+                    }
+                    "RecycleBinEnabled" => {
+                        if let Some(enabled) = try!(super::read_bool(iterator,
+                                                                     "RecycleBinEnabled")) {
+                            meta.set_recycle_bin_enabled(enabled);
+                        }
+                    }
+                    "RecycleBinUUID" => {
+                        meta.set_recycle_bin_uuid(try!(super::read_uuid(iterator,
+                                                                        "RecycleBinUUID")))
+                    }
+                    "RecycleBinChanged" => {
+                        let time = try!(super::times::read_timestamp(iterator,
+                                                                     "RecycleBinChanged"));
+                        meta.set_recycle_bin_changed(time);
+                    }
+                    "HistoryMaxItems" => {
+                        if let Some(max_items) = try!(super::read_number(iterator,
+                                                                         "HistoryMaxItems")) {
+                            meta.set_history_max_items(max_items);
+                        }
+                    }
+                    "HistoryMaxSize" => {
+                        if let Some(max_size) = try!(super::read_number(iterator,
+                                                                        "HistoryMaxSize")) {
+                            meta.set_history_max_size(max_size);
+                        }
+                    }
+                    "Binaries" => try!(super::binary::read_pool(iterator, protected, binaries)),
+                    "HeaderHash" => {
+                        try!(super::read_chars(iterator, "HeaderHash"));
+                    }
+                    _ => {
+                        let element = try!(super::read_element(iterator,
+                                                               &name,
+                                                               attributes,
+                                                               &namespace));
+                        meta.add_unknown_element(element, last.clone());
+                        continue;
+                    }
+                }
+                last = Some(name.local_name);
+            }
+            Some(Ok(XmlEvent::EndElement { name, .. })) => {
+                if name.local_name == "Meta" {
+                    break;
+                }
+            }
+
+            Some(Err(e)) => return Err(e),
+            None => break,
+            _ => {}
+        }
+    }
+
+    Ok(meta)
+}
+
+fn read_memory_protection(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>)
+                          -> Result<MemoryProtection, Error> {
+    let mut memory_protection = MemoryProtection::default();
+
+    loop {
+        match iterator.next() {
+            Some(Ok(XmlEvent::StartElement { name, .. })) => {
+                let flag = match &name.local_name[..] {
+                    "ProtectTitle" => &mut memory_protection.protect_title,
+                    "ProtectUserName" => &mut memory_protection.protect_username,
+                    "ProtectPassword" => &mut memory_protection.protect_password,
+                    "ProtectURL" => &mut memory_protection.protect_url,
+                    "ProtectNotes" => &mut memory_protection.protect_notes,
+                    _ => continue,
+                };
+
+                if let Some(value) = try!(super::read_bool(iterator, &name.local_name)) {
+                    *flag = value;
+                }
+            }
+            Some(Ok(XmlEvent::EndElement { name, .. })) => {
+                if name.local_name == "MemoryProtection" {
+                    break;
+                }
+            }
+
+            Some(Err(e)) => return Err(e),
+            None => break,
+            _ => {}
+        }
+    }
+
+    Ok(memory_protection)
+}
+
+fn read_custom_icons(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>)
+                     -> Result<Vec<CustomIcon>, Error> {
+    let mut icons = vec![];
+    let mut uuid = None;
+    let mut data = None;
+    let mut name = None;
+
+    loop {
+        match iterator.next() {
+            Some(Ok(XmlEvent::StartElement { name: element, .. })) => {
+                match &element.local_name[..] {
+                    "UUID" => uuid = try!(super::read_uuid(iterator, "UUID")),
+                    "Data" => data = try!(super::read_chars(iterator, "Data")),
+                    "Name" => name = try!(super::read_chars(iterator, "Name")),
+                    _ => {}
+                }
+            }
+            Some(Ok(XmlEvent::EndElement { name: element, .. })) => {
+                match &element.local_name[..] {
+                    "Icon" => {
+                        let data = try!(data.take()
+                                            .unwrap_or_default()
+                                            .from_base64()
+                                            .map_err(|e| Error::Base64(e)));
+                        let mut icon = CustomIcon::new(uuid.take().unwrap_or(Uuid::nil()), data);
+                        icon.set_name(name.take());
+                        icons.push(icon);
+                    }
+                    "CustomIcons" => break,
+                    _ => {}
+                }
+            }
+
+            Some(Err(e)) => return Err(e),
+            None => break,
+            _ => {}
+        }
+    }
+
+    Ok(icons)
+}
+
+#[cfg(test)]
+mod tests {
+    use protected::ProtectedStream;
+
+    use {Error, Timestamp, Uuid};
+
+    use std::fs::File;
+    use xml::reader::EventReader;
+
+    #[test]
+    fn should_read_meta() {
+        let file = File::open("data/xml/meta/valid.xml").unwrap();
+        let event_reader = EventReader::new(file);
+        let mut iterator = event_reader.into_iter().map(|result| result.map_err(|e| Error::Xml(e)));
+        iterator.by_ref().take(2).count(); // Skip StartDocument and <Meta>
+        let mut protected = ProtectedStream::none();
+        let meta = super::read(&mut iterator, &mut *protected, &mut vec![]).unwrap();
+
+        assert_eq!(meta.generator().as_ref().unwrap(), "KeePass");
+        assert_eq!(meta.database_name().as_ref().unwrap(), "Personal");
+        assert_eq!(meta.database_name_changed(),
+                   Some(Timestamp::new(2016, 8, 30, 14, 46, 12)));
+        assert_eq!(meta.database_description().as_ref().unwrap(), "Home passwords");
+        assert_eq!(meta.default_username().as_ref().unwrap(), "joe.bloggs");
+        assert_eq!(meta.maintenance_history_days(), 180);
+        assert_eq!(meta.color().as_ref().unwrap(), "#FF0000");
+        assert_eq!(meta.master_key_changed(), Some(Timestamp::new(2015, 4, 3, 14, 53, 49)));
+        assert_eq!(meta.master_key_change_recommended(), 365);
+        assert_eq!(meta.master_key_change_forced(), -1);
+        assert!(meta.memory_protection().protect_password);
+        assert!(meta.memory_protection().protect_notes);
+        assert!(!meta.memory_protection().protect_title);
+        assert!(!meta.recycle_bin_enabled());
+        assert!(meta.recycle_bin_uuid().unwrap().is_nil());
+        assert_eq!(meta.history_max_items(), 5);
+        assert_eq!(meta.history_max_size(), 1048576);
+
+        assert_eq!(meta.custom_icons().len(), 1);
+        let uuid = Uuid::parse("7n1R2qLNQkmRQyMQbD5aEw==").unwrap();
+        let icon = meta.custom_icon(&uuid).unwrap();
+        assert_eq!(&icon.data()[..4], b"\x89PNG");
+        assert_eq!(icon.name().as_ref().unwrap(), "Server");
+    }
+}
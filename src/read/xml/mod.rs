@@ -1,24 +1,46 @@
+mod auto_type;
+mod binary;
+mod deleted_objects;
 mod entry;
+mod group;
 mod kv;
+mod meta;
+mod times;
 
-use {Database, Error};
+use {Database, Error, Uuid, XmlElement, XmlNode};
 use protected::ProtectedStream;
 
 use std::io::Read;
+use std::str::FromStr;
 
+use xml::attribute::OwnedAttribute;
+use xml::name::OwnedName;
+use xml::namespace::{Namespace, NamespaceStack};
 use xml::reader::{EventReader, XmlEvent};
 
-pub fn read(reader: &mut Read, protected: &mut ProtectedStream) -> Result<Database, Error> {
+pub fn read(reader: &mut Read,
+            protected: &mut ProtectedStream,
+            binaries: Vec<Vec<u8>>)
+            -> Result<Database, Error> {
     let event_reader = EventReader::new(reader);
     let mut iterator = event_reader.into_iter().map(|result| result.map_err(|e| Error::Xml(e)));
 
-    let mut database = Database::new();
+    let mut binaries = binaries;
+    let mut meta = None;
+    let mut root = None;
+    let mut deleted_objects = vec![];
 
     loop {
         match iterator.next() {
             Some(Ok(XmlEvent::StartElement { name, .. })) => {
                 match &name.local_name[..] {
-                    "Entry" => database.add(try!(entry::read(&mut iterator, protected))),
+                    "Meta" => {
+                        meta = Some(try!(meta::read(&mut iterator, protected, &mut binaries)))
+                    }
+                    "Group" => root = Some(try!(group::read(&mut iterator, protected, &binaries))),
+                    "DeletedObjects" => {
+                        deleted_objects = try!(deleted_objects::read(&mut iterator))
+                    }
                     _ => {}
                 }
             }
@@ -30,12 +52,20 @@ pub fn read(reader: &mut Read, protected: &mut ProtectedStream) -> Result<Databa
         }
     }
 
+    let mut database = root.map_or_else(Database::new, Database::with_root);
+    if let Some(meta) = meta {
+        *database.meta_mut() = meta;
+    }
+    for deleted_object in deleted_objects {
+        database.add_deleted_object(deleted_object);
+    }
+
     Ok(database)
 }
 
-fn read_chars(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
-              element: &str)
-              -> Result<Option<String>, Error> {
+pub fn read_chars(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
+                  element: &str)
+                  -> Result<Option<String>, Error> {
     let mut result = None;
 
     loop {
@@ -56,6 +86,105 @@ fn read_chars(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
     Ok(result)
 }
 
+pub fn read_element(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
+                    name: &OwnedName,
+                    attributes: Vec<OwnedAttribute>,
+                    namespace: &Namespace)
+                    -> Result<XmlElement, Error> {
+    read_child(iterator,
+               name,
+               attributes,
+               namespace,
+               &NamespaceStack::default().squash())
+}
+
+fn read_child(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
+              name: &OwnedName,
+              attributes: Vec<OwnedAttribute>,
+              namespace: &Namespace,
+              parent: &Namespace)
+              -> Result<XmlElement, Error> {
+    let mut result = XmlElement::new(&qualified_name(name));
+    for (prefix, uri) in namespace {
+        if parent.get(prefix) != Some(uri) {
+            match prefix {
+                "" => result.add_attribute("xmlns", uri),
+                _ => result.add_attribute(&format!("xmlns:{}", prefix), uri),
+            }
+        }
+    }
+    for attribute in attributes {
+        result.add_attribute(&qualified_name(&attribute.name), &attribute.value);
+    }
+
+    loop {
+        match iterator.next() {
+            Some(Ok(XmlEvent::StartElement { name, attributes, namespace: child })) => {
+                let child = try!(read_child(iterator, &name, attributes, &child, namespace));
+                result.add_child(XmlNode::Element(child));
+            }
+            Some(Ok(XmlEvent::Characters(chars))) |
+            Some(Ok(XmlEvent::CData(chars))) => result.add_child(XmlNode::Text(chars)),
+            Some(Ok(XmlEvent::EndElement { .. })) => break,
+
+            Some(Err(e)) => return Err(e),
+            None => break,
+            _ => {}
+        }
+    }
+
+    Ok(result)
+}
+
+fn qualified_name(name: &OwnedName) -> String {
+    match name.prefix {
+        Some(ref prefix) => format!("{}:{}", prefix, name.local_name),
+        None => name.local_name.clone(),
+    }
+}
+
+fn read_number<T: FromStr>(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
+                           element: &str)
+                           -> Result<Option<T>, Error> {
+    let value = try!(read_chars(iterator, element));
+    Ok(value.and_then(|v| v.trim().parse().ok()))
+}
+
+fn read_uuid(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
+             element: &str)
+             -> Result<Option<Uuid>, Error> {
+    match try!(read_chars(iterator, element)) {
+        Some(value) => Uuid::parse(&value).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn read_tags(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
+             element: &str)
+             -> Result<Vec<String>, Error> {
+    let value = try!(read_chars(iterator, element));
+    Ok(value.map_or_else(Vec::new, |v| {
+        v.split(|c| c == ';' || c == ',')
+         .map(|tag| tag.trim())
+         .filter(|tag| !tag.is_empty())
+         .map(|tag| tag.to_string())
+         .collect()
+    }))
+}
+
+fn read_bool(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
+             element: &str)
+             -> Result<Option<bool>, Error> {
+    let value = try!(read_chars(iterator, element));
+    Ok(value.and_then(|v| {
+        match &v.trim().to_lowercase()[..] {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        }
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -67,7 +196,7 @@ mod tests {
     fn should_read_xml() {
         let mut file = File::open("data/xml/example.xml").unwrap();
         let mut protected = ProtectedStream::none();
-        let database = super::read(&mut file, &mut *protected).unwrap();
+        let database = super::read(&mut file, &mut *protected, vec![]).unwrap();
 
         let entry = database.find("http://example.com");
         assert!(entry.is_some());
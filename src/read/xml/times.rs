@@ -0,0 +1,136 @@
+use {Error, Times, Timestamp};
+use bytes;
+
+use rustc_serialize::base64::FromBase64;
+
+use xml::reader::XmlEvent;
+
+pub fn read(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>) -> Result<Times, Error> {
+    let mut times = Times::new();
+
+    loop {
+        match iterator.next() {
+            Some(Ok(XmlEvent::StartElement { name, .. })) => {
+                match &name.local_name[..] {
+                    "CreationTime" => {
+                        times.set_creation_time(try!(read_timestamp(iterator, "CreationTime")))
+                    }
+                    "LastModificationTime" => {
+                        let time = try!(read_timestamp(iterator, "LastModificationTime"));
+                        times.set_last_modification_time(time);
+                    }
+                    "LastAccessTime" => {
+                        times.set_last_access_time(try!(read_timestamp(iterator, "LastAccessTime")))
+                    }
+                    "ExpiryTime" => {
+                        times.set_expiry_time(try!(read_timestamp(iterator, "ExpiryTime")))
+                    }
+                    "Expires" => {
+                        let expires = try!(super::read_bool(iterator, "Expires"));
+                        times.set_expires(expires.unwrap_or(false));
+                    }
+                    "UsageCount" => {
+                        let usage_count = try!(super::read_number(iterator, "UsageCount"));
+                        times.set_usage_count(usage_count.unwrap_or(0));
+                    }
+                    "LocationChanged" => {
+                        let time = try!(read_timestamp(iterator, "LocationChanged"));
+                        times.set_location_changed(time);
+                    }
+                    _ => {}
+                }
+            }
+            Some(Ok(XmlEvent::EndElement { name, .. })) => {
+                if name.local_name == "Times" {
+                    break;
+                }
+            }
+
+            Some(Err(e)) => return Err(e),
+            None => break,
+            _ => {}
+        }
+    }
+
+    Ok(times)
+}
+
+pub fn read_timestamp(iterator: &mut Iterator<Item = Result<XmlEvent, Error>>,
+                      element: &str)
+                      -> Result<Option<Timestamp>, Error> {
+    match try!(super::read_chars(iterator, element)) {
+        Some(value) => parse_timestamp(&value).map(Some),
+        None => Ok(None),
+    }
+}
+
+// KDBX 3 stores times as ISO 8601 strings, KDBX 4 as base64 encoded seconds since 0001-01-01.
+fn parse_timestamp(value: &str) -> Result<Timestamp, Error> {
+    if value.contains('-') || value.contains(':') {
+        return Timestamp::parse(value);
+    }
+
+    let data = try!(value.trim().from_base64().map_err(|e| Error::Base64(e)));
+    if data.len() != 8 {
+        return Err(Error::InvalidTimestamp(value.to_string()));
+    }
+
+    let seconds = try!(bytes::read_u64(&mut &data[..]));
+    if seconds > i64::max_value() as u64 {
+        return Err(Error::InvalidTimestamp(value.to_string()));
+    }
+
+    Timestamp::checked_from_seconds(seconds as i64)
+        .ok_or_else(|| Error::InvalidTimestamp(value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use Error;
+    use Timestamp;
+
+    use std::fs::File;
+    use xml::reader::EventReader;
+
+    #[test]
+    fn should_read_iso8601_times() {
+        let file = File::open("data/xml/times/iso8601.xml").unwrap();
+        let event_reader = EventReader::new(file);
+        let mut iterator = event_reader.into_iter().map(|result| result.map_err(|e| Error::Xml(e)));
+        let times = super::read(&mut iterator).unwrap();
+
+        assert_eq!(times.creation_time(), Some(Timestamp::new(2016, 8, 30, 14, 46, 12)));
+        assert_eq!(times.last_modification_time(),
+                   Some(Timestamp::new(2016, 9, 1, 8, 0, 0)));
+        assert_eq!(times.last_access_time(), Some(Timestamp::new(2016, 9, 2, 10, 30, 0)));
+        assert_eq!(times.expiry_time(), Some(Timestamp::new(2017, 1, 1, 0, 0, 0)));
+        assert!(times.expires());
+        assert_eq!(times.usage_count(), 7);
+        assert_eq!(times.location_changed(), Some(Timestamp::new(2016, 8, 30, 14, 46, 12)));
+    }
+
+    #[test]
+    fn should_read_base64_times() {
+        let file = File::open("data/xml/times/base64.xml").unwrap();
+        let event_reader = EventReader::new(file);
+        let mut iterator = event_reader.into_iter().map(|result| result.map_err(|e| Error::Xml(e)));
+        let times = super::read(&mut iterator).unwrap();
+
+        assert_eq!(times.creation_time(), Some(Timestamp::new(2016, 8, 30, 14, 46, 12)));
+        assert_eq!(times.expiry_time(), Some(Timestamp::new(2017, 1, 1, 0, 0, 0)));
+        assert!(!times.expires());
+        assert_eq!(times.usage_count(), 0);
+    }
+
+    #[test]
+    fn should_return_error_if_invalid_timestamp() {
+        for value in &["AAAA", "AAAAAAAAAIA=", "//////////8="] {
+            let result = super::parse_timestamp(value);
+
+            match result {
+                Err(Error::InvalidTimestamp(ref result)) if result == value => (),
+                _ => panic!("Invalid result: {:#?}", result),
+            }
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use bytes;
 use {Error, FileType};
 
-use std::io::Read;
+use std::io::{Read, Write};
 
 const SIGNATURE_FILE: u32 = 0x9AA2D903;
 const SIGNATURE_KEEPASS1: u32 = 0xB54BFB65;
@@ -16,6 +16,11 @@ pub fn read_file_type(reader: &mut Read) -> Result<FileType, Error> {
     match_file_type(file_type)
 }
 
+pub fn write_file_type(writer: &mut Write, file_type: &FileType) -> Result<(), Error> {
+    try!(bytes::write_u32(writer, SIGNATURE_FILE));
+    bytes::write_u32(writer, file_type_signature(file_type))
+}
+
 fn check_file_signature(sig: u32) -> Result<(), Error> {
     if sig == SIGNATURE_FILE {
         Ok(())
@@ -33,6 +38,14 @@ fn match_file_type(file_type: u32) -> Result<FileType, Error> {
     }
 }
 
+fn file_type_signature(file_type: &FileType) -> u32 {
+    match *file_type {
+        FileType::KeePass1 => SIGNATURE_KEEPASS1,
+        FileType::KeePass2PreRelease => SIGNATURE_KEEPASS2_PRE_RELEASE,
+        FileType::KeePass2 => SIGNATURE_KEEPASS2,
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -84,4 +97,17 @@ mod test {
             _ => panic!("Invalid result: {:#?}", result),
         }
     }
+
+    #[test]
+    pub fn should_write_file_type() {
+        let mut bytes = vec![];
+        write_file_type(&mut bytes, &FileType::KeePass2).unwrap();
+
+        let result = read_file_type(&mut &bytes[..]);
+
+        match result {
+            Ok(FileType::KeePass2) => (),
+            _ => panic!("Invalid result: {:#?}", result),
+        }
+    }
 }
@@ -0,0 +1,268 @@
+use crypto::symmetriccipher::{BlockDecryptor, BlockEncryptor};
+
+const BLOCK_SIZE: usize = 16;
+const ROUNDS: usize = 16;
+const RHO: u32 = 0x01010101;
+
+const MDS_POLYNOMIAL: u16 = 0x169;
+const RS_POLYNOMIAL: u16 = 0x14D;
+
+const MDS: [[u8; 4]; 4] = [[0x01, 0xEF, 0x5B, 0x5B],
+                           [0x5B, 0xEF, 0xEF, 0x01],
+                           [0xEF, 0x5B, 0x01, 0xEF],
+                           [0xEF, 0x01, 0xEF, 0x5B]];
+
+const RS: [[u8; 8]; 4] = [[0x01, 0xA4, 0x55, 0x87, 0x5A, 0x58, 0xDB, 0x9E],
+                          [0xA4, 0x56, 0x82, 0xF3, 0x1E, 0xC6, 0x68, 0xE5],
+                          [0x02, 0xA1, 0xFC, 0xC1, 0x47, 0xAE, 0x3D, 0x19],
+                          [0xA4, 0x55, 0x87, 0x5A, 0x58, 0xDB, 0x9E, 0x03]];
+
+const Q0: [[u8; 16]; 4] = [[0x8, 0x1, 0x7, 0xD, 0x6, 0xF, 0x3, 0x2, 0x0, 0xB, 0x5, 0x9, 0xE, 0xC,
+                            0xA, 0x4],
+                           [0xE, 0xC, 0xB, 0x8, 0x1, 0x2, 0x3, 0x5, 0xF, 0x4, 0xA, 0x6, 0x7, 0x0,
+                            0x9, 0xD],
+                           [0xB, 0xA, 0x5, 0xE, 0x6, 0xD, 0x9, 0x0, 0xC, 0x8, 0xF, 0x3, 0x2, 0x4,
+                            0x7, 0x1],
+                           [0xD, 0x7, 0xF, 0x4, 0x1, 0x2, 0x6, 0xE, 0x9, 0xB, 0x3, 0x0, 0x8, 0x5,
+                            0xC, 0xA]];
+
+const Q1: [[u8; 16]; 4] = [[0x2, 0x8, 0xB, 0xD, 0xF, 0x7, 0x6, 0xE, 0x3, 0x1, 0x9, 0x4, 0x0, 0xA,
+                            0xC, 0x5],
+                           [0x1, 0xE, 0x2, 0xB, 0x4, 0xC, 0x3, 0x7, 0x6, 0xD, 0xA, 0x5, 0xF, 0x9,
+                            0x0, 0x8],
+                           [0x4, 0xC, 0x7, 0x5, 0x1, 0x6, 0x9, 0xA, 0x0, 0xE, 0xD, 0x8, 0x2, 0xB,
+                            0x3, 0xF],
+                           [0xB, 0x9, 0x5, 0x1, 0xC, 0x3, 0xD, 0xE, 0x6, 0x4, 0x7, 0xF, 0x2, 0x0,
+                            0x8, 0xA]];
+
+// Whether q1 (true) or q0 (false) is used at each stage of h, for each byte of the input word.
+// The first stage uses the last key word; the final permutation is applied without a key word.
+const Q_ORDER: [[bool; 5]; 4] = [[true, true, false, false, true],
+                                 [false, true, true, false, false],
+                                 [false, false, false, true, true],
+                                 [true, false, true, true, false]];
+
+pub struct Twofish {
+    subkeys: [u32; 40],
+    sboxes: [[u32; 256]; 4],
+}
+
+impl Twofish {
+    pub fn new(key: &[u8]) -> Twofish {
+        assert!(key.len() == 16 || key.len() == 24 || key.len() == 32);
+
+        let even: Vec<u32> = key.chunks(8).map(|chunk| read_u32(&chunk[0..4])).collect();
+        let odd: Vec<u32> = key.chunks(8).map(|chunk| read_u32(&chunk[4..8])).collect();
+        let sbox_key: Vec<u32> = key.chunks(8).rev().map(rs).collect();
+
+        let mut twofish = Twofish {
+            subkeys: [0; 40],
+            sboxes: [[0; 256]; 4],
+        };
+
+        for i in 0..20 {
+            let a = h(2 * i as u32 * RHO, &even);
+            let b = h((2 * i as u32 + 1) * RHO, &odd).rotate_left(8);
+            twofish.subkeys[2 * i] = a.wrapping_add(b);
+            twofish.subkeys[2 * i + 1] = a.wrapping_add(b.wrapping_mul(2)).rotate_left(9);
+        }
+
+        for (index, sbox) in twofish.sboxes.iter_mut().enumerate() {
+            for value in 0..256 {
+                sbox[value] = mds_column(index, permute(index, value as u8, &sbox_key));
+            }
+        }
+
+        twofish
+    }
+
+    fn g(&self, x: u32) -> u32 {
+        self.sboxes[0][x as u8 as usize] ^ self.sboxes[1][(x >> 8) as u8 as usize] ^
+        self.sboxes[2][(x >> 16) as u8 as usize] ^ self.sboxes[3][(x >> 24) as u8 as usize]
+    }
+
+    fn f(&self, r0: u32, r1: u32, round: usize) -> (u32, u32) {
+        let t0 = self.g(r0);
+        let t1 = self.g(r1.rotate_left(8));
+
+        let f0 = t0.wrapping_add(t1).wrapping_add(self.subkeys[2 * round + 8]);
+        let f1 = t0.wrapping_add(t1.wrapping_mul(2)).wrapping_add(self.subkeys[2 * round + 9]);
+        (f0, f1)
+    }
+}
+
+impl BlockEncryptor for Twofish {
+    fn block_size(&self) -> usize {
+        BLOCK_SIZE
+    }
+
+    fn encrypt_block(&self, input: &[u8], output: &mut [u8]) {
+        let mut r = [0; 4];
+        for i in 0..4 {
+            r[i] = read_u32(&input[4 * i..4 * i + 4]) ^ self.subkeys[i];
+        }
+
+        for round in 0..ROUNDS {
+            let (f0, f1) = self.f(r[0], r[1], round);
+            let r2 = (r[2] ^ f0).rotate_right(1);
+            let r3 = r[3].rotate_left(1) ^ f1;
+            r = [r2, r3, r[0], r[1]];
+        }
+
+        for i in 0..4 {
+            write_u32(&mut output[4 * i..4 * i + 4], r[(i + 2) % 4] ^ self.subkeys[i + 4]);
+        }
+    }
+}
+
+impl BlockDecryptor for Twofish {
+    fn block_size(&self) -> usize {
+        BLOCK_SIZE
+    }
+
+    fn decrypt_block(&self, input: &[u8], output: &mut [u8]) {
+        let mut r = [0; 4];
+        for i in 0..4 {
+            r[(i + 2) % 4] = read_u32(&input[4 * i..4 * i + 4]) ^ self.subkeys[i + 4];
+        }
+
+        for round in (0..ROUNDS).rev() {
+            let (f0, f1) = self.f(r[2], r[3], round);
+            let r0 = r[0].rotate_left(1) ^ f0;
+            let r1 = (r[1] ^ f1).rotate_right(1);
+            r = [r[2], r[3], r0, r1];
+        }
+
+        for i in 0..4 {
+            write_u32(&mut output[4 * i..4 * i + 4], r[i] ^ self.subkeys[i]);
+        }
+    }
+}
+
+fn h(x: u32, key: &[u32]) -> u32 {
+    (0..4).fold(0, |result, index| {
+        result ^ mds_column(index, permute(index, (x >> (8 * index)) as u8, key))
+    })
+}
+
+fn permute(index: usize, value: u8, key: &[u32]) -> u8 {
+    let order = &Q_ORDER[index];
+    let mut result = value;
+
+    for (stage, word) in key.iter().enumerate().rev() {
+        result = q(order[3 - stage], result) ^ (word >> (8 * index)) as u8;
+    }
+
+    q(order[4], result)
+}
+
+fn q(use_q1: bool, value: u8) -> u8 {
+    let table = if use_q1 { &Q1 } else { &Q0 };
+
+    let a0 = value >> 4;
+    let b0 = value & 0x0F;
+    let a1 = a0 ^ b0;
+    let b1 = a0 ^ ror4(b0) ^ ((a0 << 3) & 0x0F);
+    let a2 = table[0][a1 as usize];
+    let b2 = table[1][b1 as usize];
+    let a3 = a2 ^ b2;
+    let b3 = a2 ^ ror4(b2) ^ ((a2 << 3) & 0x0F);
+    let a4 = table[2][a3 as usize];
+    let b4 = table[3][b3 as usize];
+
+    (b4 << 4) | a4
+}
+
+fn ror4(value: u8) -> u8 {
+    ((value >> 1) | (value << 3)) & 0x0F
+}
+
+fn mds_column(index: usize, value: u8) -> u32 {
+    (0..4).fold(0, |result, row| {
+        result | (multiply(MDS[row][index], value, MDS_POLYNOMIAL) as u32) << (8 * row)
+    })
+}
+
+fn rs(key: &[u8]) -> u32 {
+    (0..4).fold(0, |result, row| {
+        let value = key.iter()
+                       .zip(RS[row].iter())
+                       .fold(0, |sum, (&k, &r)| sum ^ multiply(r, k, RS_POLYNOMIAL));
+        result | (value as u32) << (8 * row)
+    })
+}
+
+fn multiply(a: u8, b: u8, polynomial: u16) -> u8 {
+    let mut a = a as u16;
+    let mut b = b;
+    let mut result = 0;
+
+    while b != 0 {
+        if b & 1 != 0 {
+            result ^= a;
+        }
+        a <<= 1;
+        if a & 0x100 != 0 {
+            a ^= polynomial;
+        }
+        b >>= 1;
+    }
+
+    result as u8
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    bytes.iter().rev().fold(0, |value, &byte| (value << 8) | byte as u32)
+}
+
+fn write_u32(bytes: &mut [u8], value: u32) {
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = (value >> (8 * i)) as u8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Twofish;
+
+    use crypto::symmetriccipher::{BlockDecryptor, BlockEncryptor};
+
+    const KEY: [u8; 32] = [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF, 0xFE, 0xDC, 0xBA, 0x98,
+                           0x76, 0x54, 0x32, 0x10, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77,
+                           0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF];
+
+    const CIPHERTEXT: [u8; 16] = [0x37, 0x52, 0x7B, 0xE0, 0x05, 0x23, 0x34, 0xB8, 0x9F, 0x0C,
+                                  0xFC, 0xCA, 0xE8, 0x7C, 0xFA, 0x20];
+
+    #[test]
+    fn should_encrypt_block() {
+        let twofish = Twofish::new(&KEY);
+
+        let mut output = [0; 16];
+        twofish.encrypt_block(&[0; 16], &mut output);
+
+        assert_eq!(output, CIPHERTEXT);
+    }
+
+    #[test]
+    fn should_decrypt_block() {
+        let twofish = Twofish::new(&KEY);
+
+        let mut output = [0; 16];
+        twofish.decrypt_block(&CIPHERTEXT, &mut output);
+
+        assert_eq!(output, [0; 16]);
+    }
+
+    #[test]
+    fn should_encrypt_block_with_zero_key() {
+        let twofish = Twofish::new(&[0; 32]);
+
+        let mut output = [0; 16];
+        twofish.encrypt_block(&[0; 16], &mut output);
+
+        assert_eq!(output,
+                   [0x57, 0xFF, 0x73, 0x9D, 0x4D, 0xC9, 0x2C, 0x1B, 0xD7, 0xFC, 0x01, 0x70,
+                    0x0C, 0xC8, 0x21, 0x6F]);
+    }
+}
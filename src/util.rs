@@ -2,8 +2,11 @@ use Error;
 
 use crypto::buffer::{BufferResult, ReadBuffer, RefReadBuffer, RefWriteBuffer, WriteBuffer};
 use crypto::digest::Digest;
-use crypto::sha2::Sha256;
-use crypto::symmetriccipher::Decryptor;
+use crypto::hmac::Hmac;
+use crypto::mac::Mac;
+use crypto::sha2::{Sha256, Sha512};
+use crypto::symmetriccipher::{Decryptor, Encryptor};
+use rand::{OsRng, Rng};
 
 pub fn sha256(input: &[u8]) -> [u8; 32] {
     let mut hasher = Sha256::new();
@@ -15,6 +18,26 @@ pub fn sha256(input: &[u8]) -> [u8; 32] {
     buf
 }
 
+pub fn sha512(input: &[u8]) -> [u8; 64] {
+    let mut hasher = Sha512::new();
+    hasher.input(input);
+
+    let mut buf = [0; 64];
+    hasher.result(&mut buf);
+
+    buf
+}
+
+pub fn hmac_sha256(key: &[u8], input: &[u8]) -> [u8; 32] {
+    let mut hmac = Hmac::new(Sha256::new(), key);
+    hmac.input(input);
+
+    let mut buf = [0; 32];
+    hmac.raw_result(&mut buf);
+
+    buf
+}
+
 pub fn decrypt(decryptor: &mut Decryptor, value: &[u8]) -> Result<Vec<u8>, Error> {
     let mut final_result = vec![];
     let mut read_buffer = RefReadBuffer::new(value);
@@ -36,3 +59,32 @@ pub fn decrypt(decryptor: &mut Decryptor, value: &[u8]) -> Result<Vec<u8>, Error
 
     Ok(final_result)
 }
+
+pub fn encrypt(encryptor: &mut Encryptor, value: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut final_result = vec![];
+    let mut read_buffer = RefReadBuffer::new(value);
+    let mut buffer = [0; 4096];
+    let mut write_buffer = RefWriteBuffer::new(&mut buffer);
+
+    loop {
+        let result = try!(encryptor.encrypt(&mut read_buffer, &mut write_buffer, true)
+                                   .map_err(|e| Error::Cipher(e)));
+        final_result.extend(write_buffer.take_read_buffer()
+                                        .take_remaining()
+                                        .iter()
+                                        .map(|&i| i));
+        match result {
+            BufferResult::BufferUnderflow => break,
+            BufferResult::BufferOverflow => {}
+        }
+    }
+
+    Ok(final_result)
+}
+
+pub fn random_bytes(buffer: &mut [u8]) -> Result<(), Error> {
+    let mut rng = try!(OsRng::new().map_err(|e| Error::Io(e)));
+    rng.fill_bytes(buffer);
+
+    Ok(())
+}
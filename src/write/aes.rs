@@ -0,0 +1,29 @@
+use util;
+
+use crypto::aes;
+use crypto::aes::KeySize;
+use crypto::blockmodes::PkcsPadding;
+
+use Error;
+
+pub fn encrypt(data: &[u8], key: &[u8; 32], iv: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut encryptor = aes::cbc_encryptor(KeySize::KeySize256, key, iv, PkcsPadding);
+    util::encrypt(&mut *encryptor, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use read;
+
+    #[test]
+    fn should_encrypt() {
+        let key = [1; 32];
+        let iv = [2; 16];
+
+        let encrypted = super::encrypt(b"hunter2", &key, &iv).unwrap();
+        assert_eq!(encrypted.len(), 16);
+
+        let decrypted = read::aes::decrypt(&mut &encrypted[..], &key, &iv).unwrap();
+        assert_eq!(&decrypted[..], b"hunter2");
+    }
+}
@@ -0,0 +1,94 @@
+use std::cmp;
+use std::io;
+use std::io::Write;
+
+use bytes;
+use util::sha256;
+use Error;
+
+const BLOCK_SIZE: usize = 1024 * 1024;
+
+pub struct BlockWriter<'a> {
+    delegate: &'a mut Write,
+    next_block_id: u32,
+    block: Vec<u8>,
+}
+
+impl<'a> BlockWriter<'a> {
+    pub fn new(delegate: &'a mut Write) -> BlockWriter<'a> {
+        BlockWriter {
+            delegate: delegate,
+            next_block_id: 0,
+            block: vec![],
+        }
+    }
+
+    pub fn finish(mut self) -> Result<(), Error> {
+        if self.block.len() > 0 {
+            try!(self.write_block());
+        }
+
+        self.write_block()
+    }
+
+    fn write_block(&mut self) -> Result<(), Error> {
+        let hash = if self.block.len() == 0 {
+            [0; 32]
+        } else {
+            sha256(&self.block)
+        };
+
+        try!(bytes::write_u32(self.delegate, self.next_block_id));
+        try!(bytes::write_all(self.delegate, &hash));
+        try!(bytes::write_u32(self.delegate, self.block.len() as u32));
+        try!(bytes::write_all(self.delegate, &self.block));
+
+        self.block.clear();
+        self.next_block_id += 1;
+
+        Ok(())
+    }
+}
+
+impl<'a> Write for BlockWriter<'a> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let length = cmp::min(buf.len(), BLOCK_SIZE - self.block.len());
+        self.block.extend(buf[..length].iter().cloned());
+
+        if self.block.len() == BLOCK_SIZE {
+            try!(self.write_block().map_err(|e| io::Error::new(io::ErrorKind::Other, e)));
+        }
+
+        Ok(length)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.delegate.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BlockWriter;
+    use read::block::BlockReader;
+
+    use std::io::{Cursor, Read, Write};
+
+    #[test]
+    fn should_write_blocks() {
+        let data = (0..3 * super::BLOCK_SIZE / 2).map(|i| i as u8).collect::<Vec<_>>();
+
+        let mut bytes = vec![];
+        {
+            let mut writer = BlockWriter::new(&mut bytes);
+            writer.write_all(&data).unwrap();
+            writer.finish().unwrap();
+        }
+
+        let mut reader = BlockReader::new(Box::new(Cursor::new(bytes)));
+        let mut result = vec![];
+        reader.read_to_end(&mut result).unwrap();
+
+        assert_eq!(result, data);
+    }
+}
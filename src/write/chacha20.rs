@@ -0,0 +1,19 @@
+use util;
+
+use crypto::chacha20::ChaCha20;
+
+use Error;
+
+pub fn encrypt(data: &[u8], key: &[u8; 32], iv: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut encryptor = ChaCha20::new(key, iv);
+    util::encrypt(&mut encryptor, data)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn should_encrypt() {
+        let result = super::encrypt(b"hunter2", &[1; 32], &[2; 12]).unwrap();
+        assert_eq!(result, [0x6e, 0x94, 0x96, 0xa2, 0x0f, 0xb7, 0xf5]);
+    }
+}
@@ -0,0 +1,35 @@
+pub mod aes;
+mod block;
+pub mod chacha20;
+pub mod twofish;
+pub mod xml;
+
+use {CompositeKey, Database, Error};
+use bytes;
+use header::Header;
+
+use std::io::Write;
+
+use self::block::BlockWriter;
+
+pub fn write(writer: &mut Write,
+             header: &Header,
+             header_hash: &[u8; 32],
+             database: &Database,
+             key: &CompositeKey)
+             -> Result<(), Error> {
+    let mut xml = vec![];
+    try!(xml::write(&mut xml, database, header_hash, &mut *try!(header.protected_stream())));
+    let compressed = try!(header.compress(&xml));
+
+    let mut stream = try!(header.stream_start_bytes()).to_vec();
+    {
+        let mut block_writer = BlockWriter::new(&mut stream);
+        try!(block_writer.write_all(&compressed).map_err(|e| Error::Io(e)));
+        try!(block_writer.finish());
+    }
+
+    let master_key = try!(header.master_key(key));
+    let encrypted = try!(header.encrypt(&stream, &master_key));
+    bytes::write_all(writer, &encrypted)
+}
@@ -0,0 +1,28 @@
+use twofish::Twofish;
+use util;
+
+use crypto::blockmodes::{CbcEncryptor, PkcsPadding};
+
+use Error;
+
+pub fn encrypt(data: &[u8], key: &[u8; 32], iv: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut encryptor = CbcEncryptor::new(Twofish::new(key), PkcsPadding, iv.to_vec());
+    util::encrypt(&mut encryptor, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use read;
+
+    #[test]
+    fn should_encrypt() {
+        let key = [1; 32];
+        let iv = [2; 16];
+
+        let encrypted = super::encrypt(b"hunter2", &key, &iv).unwrap();
+        assert_eq!(encrypted.len(), 16);
+
+        let decrypted = read::twofish::decrypt(&mut &encrypted[..], &key, &iv).unwrap();
+        assert_eq!(&decrypted[..], b"hunter2");
+    }
+}
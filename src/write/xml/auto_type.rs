@@ -0,0 +1,27 @@
+use {AutoType, Error};
+
+use std::io::Write;
+
+use xml::writer::EventWriter;
+
+pub fn write<W: Write>(writer: &mut EventWriter<W>, auto_type: &AutoType) -> Result<(), Error> {
+    try!(super::start_element(writer, "AutoType"));
+    try!(super::write_chars(writer, "Enabled", super::format_bool(auto_type.enabled())));
+    try!(super::write_chars(writer,
+                            "DataTransferObfuscation",
+                            &auto_type.obfuscation().to_u32().to_string()));
+    if let Some(ref sequence) = *auto_type.default_sequence() {
+        try!(super::write_chars(writer, "DefaultSequence", sequence));
+    }
+
+    for association in auto_type.associations() {
+        try!(super::start_element(writer, "Association"));
+        try!(super::write_chars(writer, "Window", association.window()));
+        try!(super::write_chars(writer,
+                                "KeystrokeSequence",
+                                association.sequence().as_ref().map_or("", |s| &s[..])));
+        try!(super::end_element(writer));
+    }
+
+    super::end_element(writer)
+}
@@ -0,0 +1,53 @@
+use {Database, Error};
+use compress;
+
+use std::io::Write;
+
+use rustc_serialize::base64::{STANDARD, ToBase64};
+use xml::writer::{EventWriter, XmlEvent};
+
+pub fn pool(database: &Database) -> Vec<&[u8]> {
+    let mut binaries: Vec<&[u8]> = vec![];
+
+    for entry in database.entries() {
+        for entry in Some(entry).into_iter().chain(entry.history()) {
+            for attachment in entry.attachments() {
+                if !binaries.contains(&attachment.data()) {
+                    binaries.push(attachment.data());
+                }
+            }
+        }
+    }
+
+    binaries
+}
+
+pub fn write_pool<W: Write>(writer: &mut EventWriter<W>, binaries: &[&[u8]]) -> Result<(), Error> {
+    try!(super::start_element(writer, "Binaries"));
+
+    for (id, data) in binaries.iter().enumerate() {
+        let id = id.to_string();
+        let event = XmlEvent::start_element("Binary").attr("ID", &id).attr("Compressed", "True");
+        try!(super::write_event(writer, event.into()));
+
+        let compressed = try!(compress::gzip(data));
+        try!(super::write_event(writer, XmlEvent::characters(&compressed.to_base64(STANDARD))));
+        try!(super::end_element(writer));
+    }
+
+    super::end_element(writer)
+}
+
+pub fn write_attachment<W: Write>(writer: &mut EventWriter<W>,
+                                  name: &str,
+                                  data: &[u8],
+                                  binaries: &[&[u8]])
+                                  -> Result<(), Error> {
+    let id = binaries.iter().position(|&binary| binary == data).unwrap_or(0).to_string();
+
+    try!(super::start_element(writer, "Binary"));
+    try!(super::write_chars(writer, "Key", name));
+    try!(super::write_event(writer, XmlEvent::start_element("Value").attr("Ref", &id).into()));
+    try!(super::end_element(writer));
+    super::end_element(writer)
+}
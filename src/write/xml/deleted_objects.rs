@@ -0,0 +1,22 @@
+use {DeletedObject, Error};
+
+use std::io::Write;
+
+use xml::writer::EventWriter;
+
+pub fn write<W: Write>(writer: &mut EventWriter<W>,
+                       deleted_objects: &[DeletedObject])
+                       -> Result<(), Error> {
+    try!(super::start_element(writer, "DeletedObjects"));
+
+    for deleted_object in deleted_objects {
+        try!(super::start_element(writer, "DeletedObject"));
+        try!(super::write_uuid(writer, "UUID", deleted_object.uuid()));
+        try!(super::times::write_timestamp(writer,
+                                           "DeletionTime",
+                                           deleted_object.deletion_time()));
+        try!(super::end_element(writer));
+    }
+
+    super::end_element(writer)
+}
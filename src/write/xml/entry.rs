@@ -0,0 +1,81 @@
+use {DatabaseEntry, Error, MemoryProtection};
+use protected::ProtectedStream;
+
+use std::io::Write;
+
+use xml::writer::EventWriter;
+
+pub fn write<W: Write>(writer: &mut EventWriter<W>,
+                       entry: &DatabaseEntry,
+                       binaries: &[&[u8]],
+                       memory_protection: &MemoryProtection,
+                       protected: &mut ProtectedStream)
+                       -> Result<(), Error> {
+    write_entry(writer, entry, binaries, memory_protection, protected, false)
+}
+
+fn write_entry<W: Write>(writer: &mut EventWriter<W>,
+                         entry: &DatabaseEntry,
+                         binaries: &[&[u8]],
+                         memory_protection: &MemoryProtection,
+                         protected: &mut ProtectedStream,
+                         is_history: bool)
+                         -> Result<(), Error> {
+    try!(super::start_element(writer, "Entry"));
+    try!(super::write_elements(writer, entry.unknown_elements_after(None)));
+    try!(super::write_uuid(writer, "UUID", entry.uuid()));
+    try!(write_unknown(writer, entry, "UUID"));
+    if let Some(icon_id) = entry.icon_id() {
+        try!(super::write_chars(writer, "IconID", &icon_id.to_string()));
+    }
+    try!(write_unknown(writer, entry, "IconID"));
+    if let Some(ref uuid) = entry.custom_icon_uuid() {
+        try!(super::write_uuid(writer, "CustomIconUUID", uuid));
+    }
+    try!(write_unknown(writer, entry, "CustomIconUUID"));
+    let color = entry.foreground_color().as_ref().map_or("", |color| &color[..]);
+    try!(super::write_chars(writer, "ForegroundColor", color));
+    try!(write_unknown(writer, entry, "ForegroundColor"));
+    let color = entry.background_color().as_ref().map_or("", |color| &color[..]);
+    try!(super::write_chars(writer, "BackgroundColor", color));
+    try!(write_unknown(writer, entry, "BackgroundColor"));
+    try!(super::write_chars(writer, "Tags", &entry.tags().join(";")));
+    try!(write_unknown(writer, entry, "Tags"));
+    try!(super::times::write(writer, entry.times()));
+    try!(write_unknown(writer, entry, "Times"));
+
+    for field in entry.fields() {
+        let is_protected = field.is_protected() || memory_protection.protects(field.key());
+        try!(super::kv::write(writer, field.key(), field.value(), is_protected, protected));
+    }
+    try!(write_unknown(writer, entry, "String"));
+
+    for attachment in entry.attachments() {
+        try!(super::binary::write_attachment(writer,
+                                             attachment.name(),
+                                             attachment.data(),
+                                             binaries));
+    }
+    try!(write_unknown(writer, entry, "Binary"));
+
+    try!(super::auto_type::write(writer, entry.auto_type()));
+    try!(write_unknown(writer, entry, "AutoType"));
+
+    if !is_history {
+        try!(super::start_element(writer, "History"));
+        for past in entry.history() {
+            try!(write_entry(writer, past, binaries, memory_protection, protected, true));
+        }
+        try!(super::end_element(writer));
+    }
+    try!(write_unknown(writer, entry, "History"));
+
+    super::end_element(writer)
+}
+
+fn write_unknown<W: Write>(writer: &mut EventWriter<W>,
+                           entry: &DatabaseEntry,
+                           after: &str)
+                           -> Result<(), Error> {
+    super::write_elements(writer, entry.unknown_elements_after(Some(after)))
+}
@@ -0,0 +1,77 @@
+use {Error, Group, MemoryProtection};
+use protected::ProtectedStream;
+
+use std::io::Write;
+
+use xml::writer::EventWriter;
+
+pub fn write<W: Write>(writer: &mut EventWriter<W>,
+                       group: &Group,
+                       binaries: &[&[u8]],
+                       memory_protection: &MemoryProtection,
+                       protected: &mut ProtectedStream)
+                       -> Result<(), Error> {
+    try!(super::start_element(writer, "Group"));
+    try!(super::write_elements(writer, group.unknown_elements_after(None)));
+
+    try!(super::write_uuid(writer, "UUID", group.uuid()));
+    try!(write_unknown(writer, group, "UUID"));
+    try!(super::write_chars(writer, "Name", group.name()));
+    try!(write_unknown(writer, group, "Name"));
+
+    let notes = group.notes().as_ref().map_or("", |notes| &notes[..]);
+    try!(super::write_chars(writer, "Notes", notes));
+    try!(write_unknown(writer, group, "Notes"));
+    if let Some(icon_id) = group.icon_id() {
+        try!(super::write_chars(writer, "IconID", &icon_id.to_string()));
+    }
+    try!(write_unknown(writer, group, "IconID"));
+    if let Some(ref uuid) = group.custom_icon_uuid() {
+        try!(super::write_uuid(writer, "CustomIconUUID", uuid));
+    }
+    try!(write_unknown(writer, group, "CustomIconUUID"));
+    try!(super::times::write(writer, group.times()));
+    try!(write_unknown(writer, group, "Times"));
+    if let Some(is_expanded) = group.is_expanded() {
+        try!(super::write_chars(writer, "IsExpanded", super::format_bool(is_expanded)));
+    }
+    try!(write_unknown(writer, group, "IsExpanded"));
+    let sequence = group.default_auto_type_sequence().as_ref().map_or("", |sequence| &sequence[..]);
+    try!(super::write_chars(writer, "DefaultAutoTypeSequence", sequence));
+    try!(write_unknown(writer, group, "DefaultAutoTypeSequence"));
+    try!(super::write_chars(writer,
+                            "EnableAutoType",
+                            super::format_optional_bool(group.enable_auto_type())));
+    try!(write_unknown(writer, group, "EnableAutoType"));
+    try!(super::write_chars(writer,
+                            "EnableSearching",
+                            super::format_optional_bool(group.enable_searching())));
+    try!(write_unknown(writer, group, "EnableSearching"));
+    if let Some(ref uuid) = group.last_top_visible_entry() {
+        try!(super::write_uuid(writer, "LastTopVisibleEntry", uuid));
+    }
+    try!(write_unknown(writer, group, "LastTopVisibleEntry"));
+    if !group.tags().is_empty() {
+        try!(super::write_chars(writer, "Tags", &group.tags().join(";")));
+    }
+    try!(write_unknown(writer, group, "Tags"));
+
+    for entry in group.entries() {
+        try!(super::entry::write(writer, entry, binaries, memory_protection, protected));
+    }
+    try!(write_unknown(writer, group, "Entry"));
+
+    for child in group.groups() {
+        try!(write(writer, child, binaries, memory_protection, protected));
+    }
+    try!(write_unknown(writer, group, "Group"));
+
+    super::end_element(writer)
+}
+
+fn write_unknown<W: Write>(writer: &mut EventWriter<W>,
+                           group: &Group,
+                           after: &str)
+                           -> Result<(), Error> {
+    super::write_elements(writer, group.unknown_elements_after(Some(after)))
+}
@@ -0,0 +1,36 @@
+use Error;
+use protected::ProtectedStream;
+
+use std::io::Write;
+
+use xml::writer::{EventWriter, XmlEvent};
+
+pub fn write<W: Write>(writer: &mut EventWriter<W>,
+                       key: &str,
+                       value: &Option<String>,
+                       is_protected: bool,
+                       protected: &mut ProtectedStream)
+                       -> Result<(), Error> {
+    try!(super::start_element(writer, "String"));
+    try!(super::write_chars(writer, "Key", key));
+
+    if is_protected {
+        let event = XmlEvent::start_element("Value").attr("Protected", "True");
+        try!(super::write_event(writer, event.into()));
+    } else {
+        try!(super::start_element(writer, "Value"));
+    }
+
+    if let Some(ref value) = *value {
+        let value = if is_protected {
+            try!(protected.encrypt(value))
+        } else {
+            value.clone()
+        };
+
+        try!(super::write_event(writer, XmlEvent::characters(&value)));
+    }
+
+    try!(super::end_element(writer));
+    super::end_element(writer)
+}
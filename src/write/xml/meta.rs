@@ -0,0 +1,134 @@
+use {Error, Meta};
+
+use std::io::Write;
+
+use rustc_serialize::base64::{STANDARD, ToBase64};
+use xml::writer::EventWriter;
+
+const GENERATOR: &'static str = "keepass-rust";
+
+pub fn write<W: Write>(writer: &mut EventWriter<W>,
+                       meta: &Meta,
+                       header_hash: &[u8; 32],
+                       binaries: &[&[u8]])
+                       -> Result<(), Error> {
+    try!(super::start_element(writer, "Meta"));
+    try!(super::write_elements(writer, meta.unknown_elements_after(None)));
+    try!(super::write_chars(writer, "Generator", GENERATOR));
+    try!(write_unknown(writer, meta, "Generator"));
+    try!(super::write_chars(writer, "HeaderHash", &header_hash.to_base64(STANDARD)));
+    try!(write_unknown(writer, meta, "HeaderHash"));
+
+    try!(write_string(writer, "DatabaseName", meta.database_name()));
+    try!(write_unknown(writer, meta, "DatabaseName"));
+    try!(super::times::write_timestamp(writer,
+                                       "DatabaseNameChanged",
+                                       meta.database_name_changed()));
+    try!(write_unknown(writer, meta, "DatabaseNameChanged"));
+    try!(write_string(writer, "DatabaseDescription", meta.database_description()));
+    try!(write_unknown(writer, meta, "DatabaseDescription"));
+    try!(super::times::write_timestamp(writer,
+                                       "DatabaseDescriptionChanged",
+                                       meta.database_description_changed()));
+    try!(write_unknown(writer, meta, "DatabaseDescriptionChanged"));
+    try!(write_string(writer, "DefaultUserName", meta.default_username()));
+    try!(write_unknown(writer, meta, "DefaultUserName"));
+    try!(super::times::write_timestamp(writer,
+                                       "DefaultUserNameChanged",
+                                       meta.default_username_changed()));
+    try!(write_unknown(writer, meta, "DefaultUserNameChanged"));
+    try!(super::write_chars(writer,
+                            "MaintenanceHistoryDays",
+                            &meta.maintenance_history_days().to_string()));
+    try!(write_unknown(writer, meta, "MaintenanceHistoryDays"));
+    try!(write_string(writer, "Color", meta.color()));
+    try!(write_unknown(writer, meta, "Color"));
+    try!(super::times::write_timestamp(writer, "MasterKeyChanged", meta.master_key_changed()));
+    try!(write_unknown(writer, meta, "MasterKeyChanged"));
+    try!(super::write_chars(writer,
+                            "MasterKeyChangeRec",
+                            &meta.master_key_change_recommended().to_string()));
+    try!(write_unknown(writer, meta, "MasterKeyChangeRec"));
+    try!(super::write_chars(writer,
+                            "MasterKeyChangeForce",
+                            &meta.master_key_change_forced().to_string()));
+    try!(write_unknown(writer, meta, "MasterKeyChangeForce"));
+
+    let memory_protection = meta.memory_protection();
+    try!(super::start_element(writer, "MemoryProtection"));
+    try!(super::write_chars(writer,
+                            "ProtectTitle",
+                            super::format_bool(memory_protection.protect_title)));
+    try!(super::write_chars(writer,
+                            "ProtectUserName",
+                            super::format_bool(memory_protection.protect_username)));
+    try!(super::write_chars(writer,
+                            "ProtectPassword",
+                            super::format_bool(memory_protection.protect_password)));
+    try!(super::write_chars(writer,
+                            "ProtectURL",
+                            super::format_bool(memory_protection.protect_url)));
+    try!(super::write_chars(writer,
+                            "ProtectNotes",
+                            super::format_bool(memory_protection.protect_notes)));
+    try!(super::end_element(writer));
+    try!(write_unknown(writer, meta, "MemoryProtection"));
+
+    if !meta.custom_icons().is_empty() {
+        try!(super::start_element(writer, "CustomIcons"));
+        for icon in meta.custom_icons() {
+            try!(super::start_element(writer, "Icon"));
+            try!(super::write_uuid(writer, "UUID", icon.uuid()));
+            try!(super::write_chars(writer, "Data", &icon.data().to_base64(STANDARD)));
+            try!(write_optional(writer, "Name", icon.name()));
+            try!(super::end_element(writer));
+        }
+        try!(super::end_element(writer));
+    }
+    try!(write_unknown(writer, meta, "CustomIcons"));
+
+    try!(super::write_chars(writer,
+                            "RecycleBinEnabled",
+                            super::format_bool(meta.recycle_bin_enabled())));
+    try!(write_unknown(writer, meta, "RecycleBinEnabled"));
+    if let Some(ref uuid) = meta.recycle_bin_uuid() {
+        try!(super::write_chars(writer, "RecycleBinUUID", &uuid.to_string()));
+    }
+    try!(write_unknown(writer, meta, "RecycleBinUUID"));
+    try!(super::times::write_timestamp(writer, "RecycleBinChanged", meta.recycle_bin_changed()));
+    try!(write_unknown(writer, meta, "RecycleBinChanged"));
+    try!(super::write_chars(writer,
+                            "HistoryMaxItems",
+                            &meta.history_max_items().to_string()));
+    try!(write_unknown(writer, meta, "HistoryMaxItems"));
+    try!(super::write_chars(writer, "HistoryMaxSize", &meta.history_max_size().to_string()));
+    try!(write_unknown(writer, meta, "HistoryMaxSize"));
+    try!(super::binary::write_pool(writer, binaries));
+    try!(write_unknown(writer, meta, "Binaries"));
+
+    super::end_element(writer)
+}
+
+fn write_unknown<W: Write>(writer: &mut EventWriter<W>,
+                           meta: &Meta,
+                           after: &str)
+                           -> Result<(), Error> {
+    super::write_elements(writer, meta.unknown_elements_after(Some(after)))
+}
+
+fn write_string<W: Write>(writer: &mut EventWriter<W>,
+                          element: &str,
+                          value: &Option<String>)
+                          -> Result<(), Error> {
+    super::write_chars(writer, element, value.as_ref().map_or("", |value| &value[..]))
+}
+
+fn write_optional<W: Write>(writer: &mut EventWriter<W>,
+                            element: &str,
+                            value: &Option<String>)
+                            -> Result<(), Error> {
+    match *value {
+        Some(ref value) => super::write_chars(writer, element, value),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,487 @@
+mod auto_type;
+mod binary;
+mod deleted_objects;
+mod entry;
+mod group;
+mod kv;
+mod meta;
+mod times;
+
+use {Database, Error, Uuid, XmlElement, XmlNode};
+use protected::ProtectedStream;
+
+use std::io::Write;
+
+use xml::common::XmlVersion;
+use xml::writer::{EmitterConfig, EventWriter, XmlEvent};
+
+pub fn write(writer: &mut Write,
+             database: &Database,
+             header_hash: &[u8; 32],
+             protected: &mut ProtectedStream)
+             -> Result<(), Error> {
+    let mut event_writer = EmitterConfig::new().perform_indent(true).create_writer(writer);
+    let binaries = binary::pool(database);
+
+    try!(write_event(&mut event_writer,
+                     XmlEvent::StartDocument {
+                         version: XmlVersion::Version10,
+                         encoding: Some("utf-8"),
+                         standalone: Some(true),
+                     }));
+    try!(start_element(&mut event_writer, "KeePassFile"));
+
+    try!(meta::write(&mut event_writer, database.meta(), header_hash, &binaries));
+
+    try!(start_element(&mut event_writer, "Root"));
+    try!(group::write(&mut event_writer,
+                      database.root(),
+                      &binaries,
+                      database.meta().memory_protection(),
+                      protected));
+    try!(deleted_objects::write(&mut event_writer, database.deleted_objects()));
+    try!(end_element(&mut event_writer));
+
+    end_element(&mut event_writer)
+}
+
+fn write_uuid<W: Write>(writer: &mut EventWriter<W>,
+                        element: &str,
+                        uuid: &Uuid)
+                        -> Result<(), Error> {
+    write_chars(writer, element, &uuid.to_string())
+}
+
+fn write_element<W: Write>(writer: &mut EventWriter<W>, element: &XmlElement) -> Result<(), Error> {
+    let mut event = XmlEvent::start_element(element.name());
+    for &(ref name, ref value) in element.attributes() {
+        event = event.attr(&name[..], value);
+    }
+    try!(write_event(writer, event.into()));
+
+    for child in element.children() {
+        match *child {
+            XmlNode::Element(ref child) => try!(write_element(writer, child)),
+            XmlNode::Text(ref text) => try!(write_event(writer, XmlEvent::characters(text))),
+        }
+    }
+
+    end_element(writer)
+}
+
+fn write_elements<W: Write>(writer: &mut EventWriter<W>,
+                            elements: Vec<&XmlElement>)
+                            -> Result<(), Error> {
+    for element in elements {
+        try!(write_element(writer, element));
+    }
+
+    Ok(())
+}
+
+fn format_bool(value: bool) -> &'static str {
+    if value { "True" } else { "False" }
+}
+
+// KeePass writes "null" for flags that are inherited from the parent group.
+fn format_optional_bool(value: Option<bool>) -> &'static str {
+    value.map_or("null", format_bool)
+}
+
+pub fn write_event<W: Write>(writer: &mut EventWriter<W>, event: XmlEvent) -> Result<(), Error> {
+    writer.write(event).map_err(|e| Error::XmlEmitter(e))
+}
+
+pub fn start_element<W: Write>(writer: &mut EventWriter<W>, element: &str) -> Result<(), Error> {
+    write_event(writer, XmlEvent::start_element(element).into())
+}
+
+pub fn end_element<W: Write>(writer: &mut EventWriter<W>) -> Result<(), Error> {
+    write_event(writer, XmlEvent::end_element().into())
+}
+
+pub fn write_chars<W: Write>(writer: &mut EventWriter<W>,
+                             element: &str,
+                             value: &str)
+                             -> Result<(), Error> {
+    try!(start_element(writer, element));
+    try!(write_event(writer, XmlEvent::characters(value)));
+    end_element(writer)
+}
+
+#[cfg(test)]
+mod tests {
+    use {Association, Attachment, CustomIcon, Database, DatabaseEntry, DeletedObject, Error,
+         Group, Obfuscation, Timestamp, Uuid, XmlElement, XmlNode};
+    use protected::ProtectedStream;
+    use read;
+
+    use std::fs::File;
+    use std::io::Read;
+    use xml::reader::{EventReader, XmlEvent};
+
+    fn parse(bytes: &[u8]) -> XmlElement {
+        let event_reader = EventReader::new(bytes);
+        let mut iterator = event_reader.into_iter().map(|result| result.map_err(|e| Error::Xml(e)));
+        iterator.next(); // Skip StartDocument
+
+        match iterator.next() {
+            Some(Ok(XmlEvent::StartElement { name, attributes, namespace })) => {
+                let element = read::xml::read_element(&mut iterator, &name, attributes, &namespace);
+                canonical(&element.unwrap())
+            }
+            event => panic!("Unexpected event: {:#?}", event),
+        }
+    }
+
+    // Drops indentation and the values rewritten on every save.
+    fn canonical(element: &XmlElement) -> XmlElement {
+        let mut result = XmlElement::new(element.name());
+        for &(ref name, ref value) in element.attributes() {
+            result.add_attribute(name, value);
+        }
+
+        for child in element.children() {
+            match *child {
+                XmlNode::Element(ref child) => {
+                    if child.name() != "Generator" && child.name() != "HeaderHash" {
+                        result.add_child(XmlNode::Element(canonical(child)));
+                    }
+                }
+                XmlNode::Text(ref text) => {
+                    if !text.trim().is_empty() {
+                        result.add_child(XmlNode::Text(text.clone()));
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    #[test]
+    fn should_write_xml() {
+        let mut database = Database::new();
+        database.add(DatabaseEntry::new(Some("http://example.com".to_string()),
+                                        Some("joe.bloggs".to_string()),
+                                        Some("hunter2".to_string())));
+
+        let mut bytes = vec![];
+        let mut protected = ProtectedStream::none();
+        super::write(&mut bytes, &database, &[0; 32], &mut *protected).unwrap();
+
+        let mut protected = ProtectedStream::none();
+        let result = read::xml::read(&mut &bytes[..], &mut *protected, vec![]).unwrap();
+
+        let entry = result.find("http://example.com");
+        assert!(entry.is_some());
+
+        assert_eq!(entry.unwrap().username().as_ref().unwrap(), "joe.bloggs");
+        assert_eq!(entry.unwrap().password().as_ref().unwrap(), "hunter2");
+    }
+
+    #[test]
+    fn should_write_groups() {
+        let mut work = Group::new("Work");
+        work.set_uuid(Uuid::parse("rWq5bnl4V0mMm0M7TjQMhA==").unwrap());
+        work.set_notes(Some("Office".to_string()));
+        work.set_enable_searching(Some(false));
+        work.add_entry(DatabaseEntry::new(Some("GitHub".to_string()), None, None));
+
+        let mut root = Group::new("Root");
+        root.add_group(work);
+        let database = Database::with_root(root);
+
+        let mut bytes = vec![];
+        let mut protected = ProtectedStream::none();
+        super::write(&mut bytes, &database, &[0; 32], &mut *protected).unwrap();
+
+        let mut protected = ProtectedStream::none();
+        let result = read::xml::read(&mut &bytes[..], &mut *protected, vec![]).unwrap();
+
+        let group = result.find_group("Work").unwrap();
+        assert_eq!(group.uuid().to_string(), "rWq5bnl4V0mMm0M7TjQMhA==");
+        assert_eq!(group.notes().as_ref().unwrap(), "Office");
+        assert_eq!(group.enable_searching(), Some(false));
+        assert!(result.find_entry("Work/GitHub").is_some());
+    }
+
+    #[test]
+    fn should_write_attachments() {
+        let mut first = DatabaseEntry::new(Some("Server".to_string()), None, None);
+        first.add_attachment(Attachment::new("id_ed25519.pub", b"ssh-ed25519 AAAA".to_vec()));
+        first.add_attachment(Attachment::new("empty.txt", vec![]));
+
+        let mut second = DatabaseEntry::new(Some("Backup".to_string()), None, None);
+        second.add_attachment(Attachment::new("key.pub", b"ssh-ed25519 AAAA".to_vec()));
+
+        let mut database = Database::new();
+        database.add(first);
+        database.add(second);
+
+        let mut bytes = vec![];
+        let mut protected = ProtectedStream::none();
+        super::write(&mut bytes, &database, &[0; 32], &mut *protected).unwrap();
+
+        assert_eq!(String::from_utf8(bytes.clone()).unwrap().matches("<Binary ID=").count(),
+                   2);
+
+        let mut protected = ProtectedStream::none();
+        let result = read::xml::read(&mut &bytes[..], &mut *protected, vec![]).unwrap();
+
+        let entry = result.find("Server").unwrap();
+        assert_eq!(entry.attachments(), database.find("Server").unwrap().attachments());
+
+        let entry = result.find("Backup").unwrap();
+        assert_eq!(entry.attachment("key.pub").unwrap().data(), b"ssh-ed25519 AAAA");
+    }
+
+    #[test]
+    fn should_write_history() {
+        let mut entry = DatabaseEntry::new(Some("GitHub".to_string()),
+                                           None,
+                                           Some("hunter1".to_string()));
+        entry.create_backup();
+        entry.set_field("Password", Some("hunter2".to_string()), true);
+
+        let mut database = Database::new();
+        database.meta_mut().set_history_max_items(5);
+        database.add(entry);
+
+        let mut bytes = vec![];
+        let mut protected = ProtectedStream::none();
+        super::write(&mut bytes, &database, &[0; 32], &mut *protected).unwrap();
+
+        let mut protected = ProtectedStream::none();
+        let result = read::xml::read(&mut &bytes[..], &mut *protected, vec![]).unwrap();
+
+        assert_eq!(result.meta().history_max_items(), 5);
+        assert_eq!(result.entries().len(), 1);
+
+        let entry = result.find("GitHub").unwrap();
+        assert_eq!(entry.password().as_ref().unwrap(), "hunter2");
+        assert_eq!(entry.history().len(), 1);
+        assert_eq!(entry.history()[0].password().as_ref().unwrap(), "hunter1");
+    }
+
+    #[test]
+    fn should_write_times() {
+        let mut entry = DatabaseEntry::new(Some("GitHub".to_string()), None, None);
+        entry.times_mut().set_creation_time(Some(Timestamp::new(2016, 8, 30, 14, 46, 12)));
+        entry.times_mut().set_expiry_time(Some(Timestamp::new(2017, 1, 1, 0, 0, 0)));
+        entry.times_mut().set_expires(true);
+        entry.times_mut().set_usage_count(3);
+
+        let mut database = Database::new();
+        database.root_mut()
+                .times_mut()
+                .set_last_modification_time(Some(Timestamp::new(2016, 9, 1, 8, 0, 0)));
+        database.add(entry.clone());
+
+        let mut bytes = vec![];
+        let mut protected = ProtectedStream::none();
+        super::write(&mut bytes, &database, &[0; 32], &mut *protected).unwrap();
+
+        let mut protected = ProtectedStream::none();
+        let result = read::xml::read(&mut &bytes[..], &mut *protected, vec![]).unwrap();
+
+        assert_eq!(result.find("GitHub").unwrap().times(), entry.times());
+        assert_eq!(result.root().times(), database.root().times());
+    }
+
+    #[test]
+    fn should_write_meta() {
+        let mut database = Database::new();
+        database.meta_mut().set_database_name(Some("Personal".to_string()));
+        database.meta_mut().set_color(Some("#FF0000".to_string()));
+        database.meta_mut().set_master_key_changed(Some(Timestamp::new(2015, 4, 3, 14, 53, 49)));
+        database.meta_mut().set_master_key_change_recommended(365);
+        database.meta_mut().set_recycle_bin_enabled(false);
+        database.meta_mut().memory_protection_mut().protect_notes = true;
+
+        let mut bytes = vec![];
+        let mut protected = ProtectedStream::none();
+        super::write(&mut bytes, &database, &[0; 32], &mut *protected).unwrap();
+
+        let mut protected = ProtectedStream::none();
+        let result = read::xml::read(&mut &bytes[..], &mut *protected, vec![]).unwrap();
+
+        let meta = result.meta();
+        assert_eq!(meta.generator().as_ref().unwrap(), "keepass-rust");
+        assert_eq!(meta.database_name().as_ref().unwrap(), "Personal");
+        assert_eq!(meta.color().as_ref().unwrap(), "#FF0000");
+        assert_eq!(meta.master_key_changed(), database.meta().master_key_changed());
+        assert_eq!(meta.master_key_change_recommended(), 365);
+        assert!(!meta.recycle_bin_enabled());
+        assert_eq!(meta.memory_protection(), database.meta().memory_protection());
+    }
+
+    #[test]
+    fn should_write_fields_protected_by_meta() {
+        let mut entry = DatabaseEntry::new(Some("GitHub".to_string()), None, None);
+        entry.set_notes(Some("Recovery codes".to_string()));
+        entry.set_field("Token", Some("abc".to_string()), true);
+
+        let mut database = Database::new();
+        database.meta_mut().memory_protection_mut().protect_notes = true;
+        database.add(entry);
+
+        let mut bytes = vec![];
+        let mut protected = ProtectedStream::none();
+        super::write(&mut bytes, &database, &[0; 32], &mut *protected).unwrap();
+
+        let mut protected = ProtectedStream::none();
+        let result = read::xml::read(&mut &bytes[..], &mut *protected, vec![]).unwrap();
+
+        let entry = result.find("GitHub").unwrap();
+        assert!(entry.field("Notes").unwrap().is_protected());
+        assert!(entry.field("Token").unwrap().is_protected());
+        assert!(!entry.field("Title").unwrap().is_protected());
+    }
+
+    #[test]
+    fn should_keep_uuids() {
+        let mut database = Database::new();
+        database.add(DatabaseEntry::new(Some("GitHub".to_string()), None, None));
+        database.root_mut().add_group(Group::new("Work"));
+
+        let mut bytes = vec![];
+        let mut protected = ProtectedStream::none();
+        super::write(&mut bytes, &database, &[0; 32], &mut *protected).unwrap();
+
+        let mut protected = ProtectedStream::none();
+        let result = read::xml::read(&mut &bytes[..], &mut *protected, vec![]).unwrap();
+
+        let uuid = database.find("GitHub").unwrap().uuid();
+        assert!(result.entry_by_uuid(uuid).is_some());
+        assert_eq!(result.root().uuid(), database.root().uuid());
+        assert_eq!(result.find_group("Work").unwrap().uuid(),
+                   database.find_group("Work").unwrap().uuid());
+    }
+
+    #[test]
+    fn should_write_tags_and_icons() {
+        let icon = Uuid::new_v4();
+
+        let mut entry = DatabaseEntry::new(Some("API".to_string()), None, None);
+        entry.set_icon_id(Some(12));
+        entry.set_custom_icon_uuid(Some(icon));
+        entry.set_foreground_color(Some("#FF0000".to_string()));
+        entry.set_tags(vec!["prod".to_string(), "backend".to_string()]);
+
+        let mut group = Group::new("Servers");
+        group.set_custom_icon_uuid(Some(icon));
+        group.set_tags(vec!["infra".to_string()]);
+        group.add_entry(entry);
+
+        let mut database = Database::new();
+        database.meta_mut().add_custom_icon(CustomIcon::new(icon, b"\x89PNG".to_vec()));
+        database.root_mut().add_group(group);
+
+        let mut bytes = vec![];
+        let mut protected = ProtectedStream::none();
+        super::write(&mut bytes, &database, &[0; 32], &mut *protected).unwrap();
+
+        let mut protected = ProtectedStream::none();
+        let result = read::xml::read(&mut &bytes[..], &mut *protected, vec![]).unwrap();
+
+        let entry = result.find("API").unwrap();
+        assert_eq!(entry.icon_id(), Some(12));
+        assert_eq!(entry.foreground_color().as_ref().unwrap(), "#FF0000");
+        assert!(entry.background_color().is_none());
+        assert_eq!(entry.tags(), ["prod", "backend"]);
+
+        let group = result.find_group("Servers").unwrap();
+        assert_eq!(group.tags(), ["infra"]);
+
+        let uuid = entry.custom_icon_uuid().unwrap();
+        assert_eq!(group.custom_icon_uuid(), Some(uuid));
+        assert_eq!(result.meta().custom_icon(&uuid).unwrap().data(), b"\x89PNG");
+    }
+
+    #[test]
+    fn should_write_auto_type() {
+        let mut entry = DatabaseEntry::new(Some("GitHub".to_string()), None, None);
+        entry.auto_type_mut().set_obfuscation(Obfuscation::UseClipboard);
+        entry.auto_type_mut().set_default_sequence(Some("{USERNAME}{ENTER}".to_string()));
+        entry.auto_type_mut().add_association(Association::new("*Firefox", None));
+
+        let mut database = Database::new();
+        database.add(entry.clone());
+
+        let mut bytes = vec![];
+        let mut protected = ProtectedStream::none();
+        super::write(&mut bytes, &database, &[0; 32], &mut *protected).unwrap();
+
+        let mut protected = ProtectedStream::none();
+        let result = read::xml::read(&mut &bytes[..], &mut *protected, vec![]).unwrap();
+
+        assert_eq!(result.find("GitHub").unwrap().auto_type(), entry.auto_type());
+    }
+
+    #[test]
+    fn should_write_deleted_objects() {
+        let mut database = Database::new();
+        database.add(DatabaseEntry::new(Some("GitHub".to_string()), None, None));
+        let uuid = *database.find("GitHub").unwrap().uuid();
+        database.delete_entry(&uuid).unwrap();
+
+        let deletion_time = Timestamp::new(2016, 8, 30, 0, 0, 0);
+        let deleted = DeletedObject::new(Uuid::new_v4(), Some(deletion_time));
+        database.add_deleted_object(deleted.clone());
+
+        let mut bytes = vec![];
+        let mut protected = ProtectedStream::none();
+        super::write(&mut bytes, &database, &[0; 32], &mut *protected).unwrap();
+
+        let mut protected = ProtectedStream::none();
+        let result = read::xml::read(&mut &bytes[..], &mut *protected, vec![]).unwrap();
+
+        assert_eq!(result.deleted_objects(), [deleted]);
+        assert_eq!(result.recycle_bin().unwrap().entries()[0].uuid(), &uuid);
+    }
+
+    #[test]
+    fn should_write_unknown_elements_unchanged() {
+        let mut bytes = vec![];
+        File::open("data/xml/unknown/keepass.xml").unwrap().read_to_end(&mut bytes).unwrap();
+
+        let mut protected = ProtectedStream::none();
+        let database = read::xml::read(&mut &bytes[..], &mut *protected, vec![]).unwrap();
+
+        let unknown_elements = database.meta().unknown_elements();
+        let custom_data = unknown_elements.iter().find(|element| element.name() == "CustomData");
+        let custom_data = custom_data.unwrap();
+        assert_eq!(custom_data.elements()[0].elements()[1].text(), "1000");
+
+        let mut written = vec![];
+        let mut protected = ProtectedStream::none();
+        super::write(&mut written, &database, &[0; 32], &mut *protected).unwrap();
+
+        assert_eq!(parse(&written), parse(&bytes));
+    }
+
+    #[test]
+    fn should_keep_namespace_prefixes() {
+        let bytes = br#"<KeePassFile><Root><Group><Entry>
+            <Extra xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:nil="true" />
+            </Entry></Group></Root></KeePassFile>"#;
+
+        let mut protected = ProtectedStream::none();
+        let database = read::xml::read(&mut &bytes[..], &mut *protected, vec![]).unwrap();
+
+        let mut written = vec![];
+        let mut protected = ProtectedStream::none();
+        super::write(&mut written, &database, &[0; 32], &mut *protected).unwrap();
+
+        let mut protected = ProtectedStream::none();
+        let result = read::xml::read(&mut &written[..], &mut *protected, vec![]).unwrap();
+
+        let entry = &result.root().entries()[0];
+        let extra = entry.unknown_elements()[0];
+        assert_eq!(extra.name(), "Extra");
+        assert_eq!(extra.attribute("xmlns:xsi"),
+                   Some("http://www.w3.org/2001/XMLSchema-instance"));
+        assert_eq!(extra.attribute("xsi:nil"), Some("true"));
+    }
+}
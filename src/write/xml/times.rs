@@ -0,0 +1,29 @@
+use {Error, Times, Timestamp};
+
+use std::io::Write;
+
+use xml::writer::EventWriter;
+
+pub fn write<W: Write>(writer: &mut EventWriter<W>, times: &Times) -> Result<(), Error> {
+    try!(super::start_element(writer, "Times"));
+
+    try!(write_timestamp(writer, "CreationTime", times.creation_time()));
+    try!(write_timestamp(writer, "LastModificationTime", times.last_modification_time()));
+    try!(write_timestamp(writer, "LastAccessTime", times.last_access_time()));
+    try!(write_timestamp(writer, "ExpiryTime", times.expiry_time()));
+    try!(super::write_chars(writer, "Expires", super::format_bool(times.expires())));
+    try!(super::write_chars(writer, "UsageCount", &times.usage_count().to_string()));
+    try!(write_timestamp(writer, "LocationChanged", times.location_changed()));
+
+    super::end_element(writer)
+}
+
+pub fn write_timestamp<W: Write>(writer: &mut EventWriter<W>,
+                                 element: &str,
+                                 timestamp: Option<Timestamp>)
+                                 -> Result<(), Error> {
+    match timestamp {
+        Some(timestamp) => super::write_chars(writer, element, &timestamp.to_string()),
+        None => Ok(()),
+    }
+}
@@ -1,8 +1,48 @@
 extern crate keepass;
 
+use keepass::{AutoType, CipherType, CompositeKey, Group, KeyAction, Placeholders, Search,
+              Timestamp, Uuid};
+
+use std::env;
+use std::fs;
+
 #[test]
 fn should_read_database() {
-    let result = keepass::read("data/test.kdbx", "hunter2");
+    let key = CompositeKey::new().password("hunter2");
+    let result = keepass::read("data/test.kdbx", &key);
+    assert!(result.is_ok());
+
+    let database = result.unwrap();
+    let entry = database.find("http://example.com").unwrap();
+
+    assert_eq!(entry.title().as_ref().unwrap(), "http://example.com");
+    assert_eq!(entry.username().as_ref().unwrap(), "joe.bloggs");
+    assert_eq!(entry.password().as_ref().unwrap(), "hunter2");
+}
+
+#[test]
+fn should_read_rc4_database() {
+    let key = CompositeKey::new().password("hunter2");
+    let result = keepass::read("data/test_rc4.kdbx", &key);
+    assert!(result.is_ok());
+
+    let database = result.unwrap();
+
+    let entry = database.find("http://example.com").unwrap();
+    assert_eq!(entry.username().as_ref().unwrap(), "joe.bloggs");
+    assert_eq!(entry.password().as_ref().unwrap(), "hunter2");
+
+    let entry = database.find("http://example.org").unwrap();
+    assert_eq!(entry.username().as_ref().unwrap(), "jane.doe");
+    assert_eq!(entry.password().as_ref().unwrap(), "correct horse battery staple");
+    assert_eq!(entry.times().creation_time(), Some(Timestamp::new(2009, 3, 14, 10, 22, 41)));
+    assert_eq!(database.root().name(), "test_rc4");
+}
+
+#[test]
+fn should_read_kdbx4_database() {
+    let key = CompositeKey::new().password("hunter2");
+    let result = keepass::read("data/test_kdbx4.kdbx", &key);
     assert!(result.is_ok());
 
     let database = result.unwrap();
@@ -12,3 +52,499 @@ fn should_read_database() {
     assert_eq!(entry.username().as_ref().unwrap(), "joe.bloggs");
     assert_eq!(entry.password().as_ref().unwrap(), "hunter2");
 }
+
+#[test]
+fn should_return_error_if_writing_kdbx4_database() {
+    let path = env::temp_dir().join("keepass-rust-should-not-downgrade-kdbx4.kdbx");
+
+    let key = CompositeKey::new().password("hunter2");
+    let mut database = keepass::read("data/test_kdbx4_argon2id.kdbx", &key).unwrap();
+    assert_eq!(database.version(), 0x00040000);
+
+    let result = database.save(&path, &key);
+    match result {
+        Err(keepass::Error::UnsupportedWriteVersion(0x00040000)) => (),
+        _ => panic!("Invalid result: {:#?}", result),
+    }
+
+    database.set_version(keepass::KDBX_VERSION);
+    database.save(&path, &key).unwrap();
+
+    let database = keepass::read(&path, &key).unwrap();
+    assert_eq!(database.version(), keepass::KDBX_VERSION);
+    assert_eq!(database.find("http://example.com").unwrap().password().as_ref().unwrap(),
+               "hunter2");
+}
+
+#[test]
+fn should_keep_cipher_and_transform_rounds_when_writing() {
+    let path = env::temp_dir().join("keepass-rust-should-keep-transform-rounds.kdbx");
+
+    let key = CompositeKey::new().password("hunter2");
+    let database = keepass::read("data/test_rounds.kdbx", &key).unwrap();
+    assert_eq!(database.cipher(), CipherType::Aes);
+    assert_eq!(database.transform_rounds(), 60000);
+
+    database.save(&path, &key).unwrap();
+
+    let database = keepass::read(&path, &key).unwrap();
+    assert_eq!(database.cipher(), CipherType::Aes);
+    assert_eq!(database.transform_rounds(), 60000);
+
+    let key = CompositeKey::new().password("demopass");
+    let mut database = keepass::read("data/test_kdbx4_twofish.kdbx", &key).unwrap();
+    database.set_version(keepass::KDBX_VERSION);
+    database.save(&path, &key).unwrap();
+
+    let database = keepass::read(&path, &key).unwrap();
+    assert_eq!(database.cipher(), CipherType::Twofish);
+    assert_eq!(database.find("test").unwrap().password().as_ref().unwrap(), "test");
+}
+
+#[test]
+fn should_read_kdbx4_argon2id_database() {
+    let key = CompositeKey::new().password("hunter2");
+    let result = keepass::read("data/test_kdbx4_argon2id.kdbx", &key);
+    assert!(result.is_ok());
+
+    let database = result.unwrap();
+    let entry = database.find("http://example.com").unwrap();
+
+    assert_eq!(entry.username().as_ref().unwrap(), "joe.bloggs");
+    assert_eq!(entry.password().as_ref().unwrap(), "hunter2");
+}
+
+#[test]
+fn should_read_kdbx4_argon2d_database() {
+    let key = CompositeKey::new().password("demopass");
+    let result = keepass::read("data/test_kdbx4_argon2d.kdbx", &key);
+    assert!(result.is_ok());
+
+    let database = result.unwrap();
+    let entry = database.find("Test").unwrap();
+
+    assert_eq!(entry.username().as_ref().unwrap(), "user");
+    assert_eq!(entry.password().as_ref().unwrap(), "pass");
+}
+
+#[test]
+fn should_read_kdbx4_chacha20_database() {
+    let key = CompositeKey::new().password("demopass");
+    let result = keepass::read("data/test_kdbx4_chacha20.kdbx", &key);
+    assert!(result.is_ok());
+
+    let database = result.unwrap();
+    let entry = database.find("test").unwrap();
+
+    assert_eq!(entry.username().as_ref().unwrap(), "test");
+    assert_eq!(entry.password().as_ref().unwrap(), "test");
+}
+
+#[test]
+fn should_read_kdbx4_twofish_database() {
+    let key = CompositeKey::new().password("demopass");
+    let result = keepass::read("data/test_kdbx4_twofish.kdbx", &key);
+    assert!(result.is_ok());
+
+    let database = result.unwrap();
+    let entry = database.find("test").unwrap();
+
+    assert_eq!(entry.username().as_ref().unwrap(), "test");
+    assert_eq!(entry.password().as_ref().unwrap(), "test");
+}
+
+#[test]
+fn should_return_error_if_kdbx4_key_incorrect() {
+    let key = CompositeKey::new().password("hunter3");
+    let result = keepass::read("data/test_kdbx4.kdbx", &key);
+
+    match result {
+        Err(keepass::Error::IncorrectHeaderHmac) => (),
+        _ => panic!("Invalid result: {:#?}", result),
+    }
+}
+
+#[test]
+fn should_read_attachments() {
+    let key = CompositeKey::new().password("hunter2");
+    let database = keepass::read("data/test_attachments.kdbx", &key).unwrap();
+
+    let entry = database.find("Server").unwrap();
+    assert_eq!(entry.password().as_ref().unwrap(), "hunter2");
+    assert_eq!(entry.attachments().len(), 2);
+
+    let attachment = entry.attachment("server.crt").unwrap();
+    assert!(attachment.data().starts_with(b"-----BEGIN CERTIFICATE-----"));
+
+    let attachment = entry.attachment("id_ed25519.pub").unwrap();
+    assert!(attachment.data().starts_with(b"ssh-ed25519 "));
+
+    let entry = database.find("Backup").unwrap();
+    assert_eq!(entry.password().as_ref().unwrap(), "correct horse");
+    assert_eq!(entry.attachment("backup.pub").unwrap().data(), attachment.data());
+}
+
+#[test]
+fn should_read_kdbx4_attachments() {
+    let key = CompositeKey::new().password("hunter2");
+    let database = keepass::read("data/test_kdbx4_attachments.kdbx", &key).unwrap();
+
+    let entry = database.find("Server").unwrap();
+    assert_eq!(entry.attachments().len(), 2);
+    let attachment = entry.attachment("server.crt").unwrap();
+    assert!(attachment.data().ends_with(b"-----END CERTIFICATE-----\n"));
+    assert!(entry.attachment("id_ed25519.pub").unwrap().data().starts_with(b"ssh-ed25519 "));
+
+    let entry = database.find("Backup").unwrap();
+    assert!(entry.attachment("backup.pub").unwrap().data().starts_with(b"ssh-ed25519 "));
+}
+
+#[test]
+fn should_read_kdb_database() {
+    let key = CompositeKey::new().password("foobar");
+    let result = keepass::read("data/test.kdb", &key);
+    assert!(result.is_ok());
+
+    let database = result.unwrap();
+    assert_eq!(database.entries().len(), 3);
+
+    let entry = database.find("title").unwrap();
+    assert_eq!(entry.username().as_ref().unwrap(), "user");
+    assert_eq!(entry.password().as_ref().unwrap(), "password");
+    assert_eq!(entry.url().as_ref().unwrap(), "url");
+    assert_eq!(entry.notes().as_ref().unwrap(), "notes");
+}
+
+#[test]
+fn should_read_kdb_groups() {
+    let key = CompositeKey::new().password("foobar");
+    let database = keepass::read("data/test.kdb", &key).unwrap();
+
+    let names: Vec<_> = database.root().groups().iter().map(|group| group.name()).collect();
+    assert_eq!(names, vec!["General", "Backup", "depth0"]);
+
+    let group = database.find_group("General/Homebanking");
+    assert!(group.is_some());
+    assert!(group.unwrap().entries().is_empty());
+
+    let entry = database.find_entry("depth0/depth1.1/depth2/title").unwrap();
+    assert_eq!(entry.username().as_ref().unwrap(), "user");
+}
+
+#[test]
+fn should_return_error_if_kdb_key_incorrect() {
+    let key = CompositeKey::new().password("hunter2");
+    let result = keepass::read("data/test.kdb", &key);
+
+    match result {
+        Err(keepass::Error::Cipher(_)) |
+        Err(keepass::Error::IncorrectContentsHash) => (),
+        _ => panic!("Invalid result: {:#?}", result),
+    }
+}
+
+#[test]
+fn should_read_database_with_key_file() {
+    let key = CompositeKey::new().key_file("data/test_key_file.key").unwrap();
+    let result = keepass::read("data/test_key_file.kdbx", &key);
+    assert!(result.is_ok());
+
+    let database = result.unwrap();
+    let entry = database.find("Test key").unwrap();
+
+    assert_eq!(entry.username().as_ref().unwrap(), "jdoe");
+    assert_eq!(entry.password().as_ref().unwrap(), "1234");
+}
+
+#[test]
+fn should_read_database_with_xml_key_file() {
+    let key = CompositeKey::new().key_file("data/test_xml_key_file.key").unwrap();
+    let result = keepass::read("data/test_xml_key_file.kdbx", &key);
+    assert!(result.is_ok());
+
+    let database = result.unwrap();
+    assert_eq!(database.entries().len(), 6);
+
+    let entry = database.find("In another group").unwrap();
+    assert_eq!(entry.username().as_ref().unwrap(), "demouser");
+    assert_eq!(entry.password().as_ref().unwrap(), "demopassword");
+}
+
+#[test]
+fn should_read_history() {
+    let key = CompositeKey::new().key_file("data/test_xml_key_file.key").unwrap();
+    let database = keepass::read("data/test_xml_key_file.kdbx", &key).unwrap();
+
+    assert!(database.find("In a group").is_none());
+
+    let entry = database.find("In another group").unwrap();
+    assert_eq!(entry.history().len(), 1);
+    assert_eq!(entry.history()[0].title().as_ref().unwrap(), "In a group");
+
+    let entry = database.find("Test").unwrap();
+    assert_eq!(entry.password().as_ref().unwrap(), "testing");
+    assert_eq!(entry.history()[0].password().as_ref().unwrap(), "McTest");
+}
+
+#[test]
+fn should_read_groups() {
+    let key = CompositeKey::new().key_file("data/test_xml_key_file.key").unwrap();
+    let database = keepass::read("data/test_xml_key_file.kdbx", &key).unwrap();
+
+    let root = database.root();
+    assert_eq!(root.name(), "Root");
+    assert_eq!(root.entries().len(), 2);
+    assert_eq!(root.groups().len(), 2);
+
+    let group = database.find_group("Some group/Sub-Group 2 of group").unwrap();
+    assert_eq!(group.entries().len(), 2);
+
+    let entry = database.find_entry("Some group/Sub-Group 2 of group/Walked").unwrap();
+    assert_eq!(entry.username().as_ref().unwrap(), "the");
+
+    assert!(database.find_group("Some group/Empty Sub-Group of group").is_some());
+    assert!(database.find_entry("Another group/Walked").is_none());
+}
+
+#[test]
+fn should_read_kdbx4_database_with_key_file() {
+    let key = CompositeKey::new().key_file("data/test_key_file.key").unwrap();
+    let result = keepass::read("data/test_kdbx4_key_file.kdbx", &key);
+    assert!(result.is_ok());
+
+    let database = result.unwrap();
+    let entry = database.find("Test").unwrap();
+
+    assert_eq!(entry.username().as_ref().unwrap(), "user");
+    assert_eq!(entry.password().as_ref().unwrap(), "pass");
+}
+
+#[test]
+fn should_return_error_if_key_file_missing() {
+    let key = CompositeKey::new();
+    let result = keepass::read("data/test_key_file.kdbx", &key);
+
+    match result {
+        Err(keepass::Error::MissingCompositeKey) => (),
+        _ => panic!("Invalid result: {:#?}", result),
+    }
+}
+
+#[test]
+fn should_write_database() {
+    let path = env::temp_dir().join("keepass-rust-should-write-database.kdbx");
+
+    let key = CompositeKey::new().password("hunter2");
+    let database = keepass::read("data/test.kdbx", &key).unwrap();
+
+    let key = CompositeKey::new().password("correct horse");
+    database.save(&path, &key).unwrap();
+
+    let result = keepass::read(&path, &key);
+    assert!(result.is_ok());
+
+    let database = result.unwrap();
+    assert_eq!(database.root().name(), "test");
+
+    let entry = database.find("http://example.com").unwrap();
+
+    assert_eq!(entry.username().as_ref().unwrap(), "joe.bloggs");
+    assert_eq!(entry.password().as_ref().unwrap(), "hunter2");
+}
+
+#[test]
+fn should_write_database_with_generated_key_file() {
+    let database_path = env::temp_dir().join("keepass-rust-should-write-key-file.kdbx");
+    let key_file_path = env::temp_dir().join("keepass-rust-should-write-key-file.key");
+
+    let key = CompositeKey::new().password("hunter2");
+    let database = keepass::read("data/test.kdbx", &key).unwrap();
+
+    let _ = fs::remove_file(&key_file_path);
+    keepass::generate_key_file(&key_file_path).unwrap();
+    let contents = fs::read(&key_file_path).unwrap();
+    assert!(keepass::generate_key_file(&key_file_path).is_err());
+    assert_eq!(fs::read(&key_file_path).unwrap(), contents);
+
+    let key = CompositeKey::new().password("correct horse").key_file(&key_file_path).unwrap();
+    database.save(&database_path, &key).unwrap();
+
+    let key = CompositeKey::new().password("correct horse").key_file(&key_file_path).unwrap();
+    let result = keepass::read(&database_path, &key);
+    assert!(result.is_ok());
+
+    let key = CompositeKey::new().password("correct horse");
+    let result = keepass::read(&database_path, &key);
+    assert!(result.is_err());
+}
+
+#[test]
+fn should_read_times() {
+    let key = CompositeKey::new().password("hunter2");
+    let database = keepass::read("data/test.kdbx", &key).unwrap();
+
+    let times = database.find("http://example.com").unwrap().times();
+    assert_eq!(times.creation_time(), Some(Timestamp::new(2015, 4, 3, 14, 53, 49)));
+    assert_eq!(times.last_modification_time(), Some(Timestamp::new(2015, 4, 3, 14, 54, 13)));
+    assert_eq!(times.usage_count(), 1);
+    assert!(!times.expires());
+    assert!(database.expired_entries(Timestamp::now()).is_empty());
+}
+
+#[test]
+fn should_read_kdb_times() {
+    let key = CompositeKey::new().password("foobar");
+    let database = keepass::read("data/test.kdb", &key).unwrap();
+
+    let times = database.find("title").unwrap().times();
+    assert_eq!(times.creation_time(), Some(Timestamp::new(2020, 5, 26, 16, 12, 53)));
+    assert!(times.expiry_time().is_none());
+    assert!(!times.expires());
+}
+
+#[test]
+fn should_read_meta() {
+    let key = CompositeKey::new().password("hunter2");
+    let database = keepass::read("data/test.kdbx", &key).unwrap();
+
+    let meta = database.meta();
+    assert_eq!(meta.generator().as_ref().unwrap(), "KeePass");
+    assert_eq!(meta.master_key_changed(), Some(Timestamp::new(2015, 4, 3, 14, 52, 57)));
+    assert_eq!(meta.master_key_change_recommended(), -1);
+    assert!(meta.memory_protection().protect_password);
+    assert!(!meta.recycle_bin_enabled());
+    assert_eq!(meta.history_max_items(), 10);
+}
+
+#[test]
+fn should_find_entry_by_uuid() {
+    let key = CompositeKey::new().password("hunter2");
+    let database = keepass::read("data/test.kdbx", &key).unwrap();
+
+    let uuid = Uuid::parse("BoUeiK+XGUeIQisekLa2uw==").unwrap();
+    let entry = database.entry_by_uuid(&uuid).unwrap();
+    assert_eq!(entry.title().as_ref().unwrap(), "http://example.com");
+}
+
+#[test]
+fn should_find_kdb_entries_with_same_title_by_uuid() {
+    let key = CompositeKey::new().password("foobar");
+    let database = keepass::read("data/test.kdb", &key).unwrap();
+
+    let first = Uuid::parse("ugfasCBvCkdz8QWeyctlzA==").unwrap();
+    let second = Uuid::parse("5CuN0jE9xOo3OyaMLDiPwg==").unwrap();
+    assert_eq!(database.entry_by_uuid(&first).unwrap().title().as_ref().unwrap(), "foo");
+    assert_eq!(database.entry_by_uuid(&second).unwrap().title().as_ref().unwrap(), "foo");
+}
+
+#[test]
+fn should_read_auto_type() {
+    let key = CompositeKey::new().password("hunter2");
+    let database = keepass::read("data/test.kdbx", &key).unwrap();
+
+    let entry = database.find("http://example.com").unwrap();
+    assert!(entry.auto_type().enabled());
+
+    let sequence = database.auto_type_sequence(entry, None).unwrap();
+    let actions = AutoType::parse_sequence(&sequence).unwrap();
+    assert_eq!(actions[0], KeyAction::Placeholder("USERNAME".to_string()));
+}
+
+#[test]
+fn should_write_deleted_objects() {
+    let path = env::temp_dir().join("keepass-rust-should-write-deleted-objects.kdbx");
+
+    let key = CompositeKey::new().password("hunter2");
+    let mut database = keepass::read("data/test.kdbx", &key).unwrap();
+    assert!(!database.meta().recycle_bin_enabled());
+    assert!(database.recycle_bin().is_none());
+    assert_eq!(database.deleted_objects().len(), 9);
+
+    let uuid = *database.find("http://example.com").unwrap().uuid();
+    database.delete_entry(&uuid).unwrap();
+    database.save(&path, &key).unwrap();
+
+    let database = keepass::read(&path, &key).unwrap();
+    assert!(database.entry_by_uuid(&uuid).is_none());
+    assert_eq!(database.deleted_objects().len(), 10);
+    assert_eq!(database.deleted_objects()[9].uuid(), &uuid);
+}
+
+#[test]
+fn should_write_edited_entries() {
+    let path = env::temp_dir().join("keepass-rust-should-write-edited-entries.kdbx");
+
+    let key = CompositeKey::new().password("hunter2");
+    let mut database = keepass::read("data/test.kdbx", &key).unwrap();
+    let root = *database.root().uuid();
+    let uuid = *database.find("http://example.com").unwrap().uuid();
+    let history = database.entry_by_uuid(&uuid).unwrap().history().len();
+
+    let group = database.add_group(&root, Group::new("Archive")).unwrap();
+    database.update_entry(&uuid, |entry| entry.set_notes(Some("moved".to_string()))).unwrap();
+    database.move_entry(&uuid, &group).unwrap();
+    database.save(&path, &key).unwrap();
+
+    let database = keepass::read(&path, &key).unwrap();
+    let entry = database.entry_by_uuid(&uuid).unwrap();
+    let path = database.root().entry_path(&uuid).unwrap();
+    assert_eq!(path.last().unwrap().name(), "Archive");
+    assert_eq!(entry.notes(), &Some("moved".to_string()));
+    assert_eq!(entry.history().len(), history + 1);
+    assert_eq!(entry.times().creation_time(), Some(Timestamp::new(2015, 4, 3, 14, 53, 49)));
+    assert!(entry.times().location_changed() > entry.times().creation_time());
+}
+
+#[test]
+fn should_expand_placeholders() {
+    let key = CompositeKey::new().password("hunter2");
+    let database = keepass::read("data/test.kdbx", &key).unwrap();
+    let entry = database.find("http://example.com").unwrap();
+
+    let placeholders = Placeholders::new(&database).provider("SHELL", "ssh".to_string());
+    let result = placeholders.expand(entry, "{SHELL} {USERNAME}@{TITLE} ({GROUP}, {UUID})");
+    assert_eq!(result.unwrap(),
+               "ssh joe.bloggs@http://example.com (test, 06851E88AF97194788422B1E90B6B6BB)");
+}
+
+#[test]
+fn should_search_entries() {
+    let key = CompositeKey::new().password("hunter2");
+    let database = keepass::read("data/test.kdbx", &key).unwrap();
+
+    let search = Search::new().text("EXAMPLE").fields(&["Title"]).exclude_recycle_bin();
+    let results: Vec<_> = database.search(&search).collect();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].entry().username().as_ref().unwrap(), "joe.bloggs");
+    assert_eq!(results[0].groups()[0].name(), "test");
+    assert_eq!(results[0].group_path(), "");
+
+    let search = Search::new().regex(r"^joe\.").unwrap().expired(true);
+    assert_eq!(database.search(&search).count(), 0);
+}
+
+#[test]
+fn should_keep_existing_database_if_write_fails() {
+    let path = env::temp_dir().join("keepass-rust-should-keep-existing-database.kdbx");
+
+    let key = CompositeKey::new().password("hunter2");
+    let database = keepass::read("data/test.kdbx", &key).unwrap();
+    database.save(&path, &key).unwrap();
+
+    let result = database.save(&path, &CompositeKey::new());
+    assert!(result.is_err());
+
+    let database = keepass::read(&path, &key).unwrap();
+    assert_eq!(database.root().name(), "test");
+
+    let temp_files = env::temp_dir()
+                         .read_dir()
+                         .unwrap()
+                         .filter_map(|entry| entry.ok())
+                         .filter(|entry| {
+                             let name = entry.file_name().to_string_lossy().into_owned();
+                             name.starts_with(".keepass-rust-should-keep-existing-database")
+                         })
+                         .count();
+    assert_eq!(temp_files, 0);
+}